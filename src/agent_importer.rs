@@ -0,0 +1,468 @@
+use log::debug;
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fs::File;
+use std::io::{Read, Seek};
+use std::path::Path;
+use yaml_rust::yaml::Hash;
+use yaml_rust::{Yaml, YamlEmitter};
+use zip::ZipArchive;
+
+use crate::errors::{new_error_from, ErrorKind, Result};
+use crate::yaml_parser::{TestSuiteSpec, TestSuiteType};
+
+pub struct AgentImportOptions {
+    pub lang: Option<String>, // if not specified default language of the agent is used
+    pub sample_ratio: f64,    // portion of (not held out) training phrases turned into tests
+    pub holdout_ratio: f64,   // portion of training phrases held out before sampling
+    pub credentials_file: String,
+}
+
+impl Default for AgentImportOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl AgentImportOptions {
+    pub fn new() -> Self {
+        AgentImportOptions {
+            lang: None,
+            sample_ratio: 1.0,
+            holdout_ratio: 0.0,
+            credentials_file: String::from("/path/to/credentials.json"),
+        }
+    }
+}
+
+pub struct AgentImportResult {
+    pub suite_yaml: String,
+    pub holdout_suite_yaml: Option<String>, // None if no training phrase was held out
+    pub intent_count: usize,
+    pub skipped_intents: Vec<String>, // intents without any test (all their phrases were held out)
+    pub test_count: usize,
+    pub holdout_count: usize,
+}
+
+// training phrases of single intent as found in agent export
+#[derive(Debug, PartialEq)]
+pub struct IntentTrainingPhrases {
+    pub intent_name: String,
+    pub phrases: Vec<String>,
+}
+
+pub struct DialogFlowAgent {
+    pub name: Option<String>,
+    pub default_lang: Option<String>,
+    files: BTreeMap<String, String>, // file name -> file content, only json files are kept
+}
+
+fn import_error(message: String) -> crate::errors::Error {
+    new_error_from(ErrorKind::GenericError(message))
+}
+
+impl DialogFlowAgent {
+    // reads DialogFlow ES agent export (zip file downloaded from DialogFlow console)
+    pub fn from_zip<R: Read + Seek>(reader: R) -> Result<DialogFlowAgent> {
+        let mut archive = ZipArchive::new(reader)?;
+        let mut files = BTreeMap::new();
+        for idx in 0..archive.len() {
+            let mut file = archive.by_index(idx)?;
+            let file_name = file.name().replace("\\", "/");
+            if file.is_dir() || !file_name.ends_with(".json") {
+                continue;
+            }
+            let mut content = String::new();
+            file.read_to_string(&mut content)?;
+            files.insert(file_name, content);
+        }
+
+        let mut agent = DialogFlowAgent {
+            name: None,
+            default_lang: None,
+            files,
+        };
+
+        if let Some(agent_json) = agent.find_file("agent.json") {
+            let agent_json: Value = serde_json::from_str(agent_json)?;
+            agent.name = agent_json["displayName"].as_str().map(|s| s.to_owned());
+            agent.default_lang = agent_json["language"].as_str().map(|s| s.to_owned());
+        }
+
+        Ok(agent)
+    }
+
+    // export can be zipped either with or without enclosing root folder
+    fn find_file(&self, file_name: &str) -> Option<&String> {
+        self.files
+            .iter()
+            .find(|(name, _)| *name == file_name || name.ends_with(&format!("/{}", file_name)))
+            .map(|(_, content)| content)
+    }
+
+    // returns training phrases for every intent which has some in given language, sorted by intent name
+    pub fn training_phrases(&self, lang: &str) -> Result<Vec<IntentTrainingPhrases>> {
+        let usersays_suffix = format!("_usersays_{}.json", lang);
+        let mut intents = BTreeMap::new();
+
+        for (file_name, content) in &self.files {
+            let is_intent_file =
+                file_name.starts_with("intents/") || file_name.contains("/intents/");
+            if !is_intent_file || !file_name.ends_with(&usersays_suffix) {
+                continue;
+            }
+
+            // intent name is taken from intent definition file, file name is used only as fallback
+            // since DialogFlow replaces some characters when generating file names
+            let intent_file_name = format!(
+                "{}.json",
+                &file_name[..file_name.len() - usersays_suffix.len()]
+            );
+            let intent_name = match self.files.get(&intent_file_name) {
+                Some(intent_json) => {
+                    let intent_json: Value = serde_json::from_str(intent_json)?;
+                    intent_json["name"].as_str().map(|s| s.to_owned())
+                }
+                None => None,
+            };
+            let intent_name = intent_name.unwrap_or_else(|| {
+                let base_name = intent_file_name.rsplit('/').next().unwrap();
+                base_name[..base_name.len() - ".json".len()].to_owned()
+            });
+
+            let usersays: Value = serde_json::from_str(content)?;
+            let usersays = usersays.as_array().ok_or(import_error(format!(
+                "Unexpected format of training phrases file {}, array expected",
+                file_name
+            )))?;
+
+            let mut phrases = vec![];
+            let mut seen = HashSet::new();
+            for training_phrase in usersays {
+                // template mode phrases (@sys.any etc.) cannot be sent as utterance
+                if let Some(true) = training_phrase["isTemplate"].as_bool() {
+                    continue;
+                }
+                let text: String = match training_phrase["data"].as_array() {
+                    Some(parts) => parts
+                        .iter()
+                        .map(|part| part["text"].as_str().unwrap_or(""))
+                        .collect(),
+                    None => continue,
+                };
+                let text = text.trim().to_owned();
+                if text.is_empty() || !seen.insert(text.to_lowercase()) {
+                    continue;
+                }
+                phrases.push(text);
+            }
+
+            debug!("intent {}: {} training phrases", intent_name, phrases.len());
+            if !phrases.is_empty() {
+                intents.insert(
+                    intent_name.clone(),
+                    IntentTrainingPhrases {
+                        intent_name,
+                        phrases,
+                    },
+                );
+            }
+        }
+
+        Ok(intents.into_values().collect())
+    }
+}
+
+// selects evenly spread items so that roughly ratio * len items is selected
+// selection is deterministic so that repeated imports of the same agent produce the same suite
+fn select_evenly(len: usize, ratio: f64) -> Vec<bool> {
+    (0..len)
+        .map(|idx| ((idx + 1) as f64 * ratio).floor() > (idx as f64 * ratio).floor())
+        .collect()
+}
+
+// splits training phrases into (sampled, held out) phrases. Unless sample ratio is zero at least
+// one phrase of every intent is sampled (low ratio would otherwise skip intents with few phrases)
+pub fn split_phrases(
+    phrases: &[String],
+    sample_ratio: f64,
+    holdout_ratio: f64,
+) -> (Vec<String>, Vec<String>) {
+    let mut remaining = vec![];
+    let mut held_out = vec![];
+    for (phrase, is_held_out) in phrases
+        .iter()
+        .zip(select_evenly(phrases.len(), holdout_ratio))
+    {
+        if is_held_out {
+            held_out.push(phrase.clone());
+        } else {
+            remaining.push(phrase.clone());
+        }
+    }
+
+    let mut sampled: Vec<String> = remaining
+        .iter()
+        .zip(select_evenly(remaining.len(), sample_ratio))
+        .filter(|(_, is_sampled)| *is_sampled)
+        .map(|(phrase, _)| phrase.clone())
+        .collect();
+    if sampled.is_empty() && sample_ratio > 0.0 && !remaining.is_empty() {
+        sampled.push(remaining[0].clone());
+    }
+
+    (sampled, held_out)
+}
+
+fn yaml_str(val: &str) -> Yaml {
+    Yaml::String(val.to_owned())
+}
+
+// generates test suite yaml with single turn test per training phrase, suite without
+// any test would be rejected by the parser, i.e. at least one phrase is required
+pub fn generate_suite_yaml(
+    suite_name: &str,
+    credentials_file: &str,
+    lang: &str,
+    intents: &Vec<IntentTrainingPhrases>,
+) -> Result<String> {
+    let mut config = HashMap::new();
+    config.insert(
+        String::from("credentials_file"),
+        credentials_file.to_owned(),
+    );
+    let suite_spec = TestSuiteSpec::new(suite_name.to_owned(), TestSuiteType::DialogFlow, config);
+
+    if intents.iter().all(|intent| intent.phrases.is_empty()) {
+        return Err(import_error(format!(
+            "No training phrases selected for test suite '{}'",
+            suite_name
+        )));
+    }
+
+    let mut tests = vec![];
+    for intent in intents {
+        for (idx, phrase) in intent.phrases.iter().enumerate() {
+            let mut assertion = Hash::new();
+            assertion.insert(yaml_str("userSays"), yaml_str(phrase));
+            assertion.insert(yaml_str("botRespondsWith"), yaml_str(&intent.intent_name));
+
+            let mut test = Hash::new();
+            test.insert(
+                yaml_str("name"),
+                Yaml::String(format!("{} #{}", intent.intent_name, idx + 1)),
+            );
+            test.insert(yaml_str("lang"), yaml_str(lang));
+            test.insert(
+                yaml_str("assertions"),
+                Yaml::Array(vec![Yaml::Hash(assertion)]),
+            );
+            tests.push(Yaml::Hash(test));
+        }
+    }
+
+    let mut suite = Hash::new();
+    suite.insert(yaml_str("suite-spec"), suite_spec.to_yaml());
+    suite.insert(yaml_str("tests"), Yaml::Array(tests));
+
+    let mut yaml_string = String::new();
+    YamlEmitter::new(&mut yaml_string)
+        .dump(&Yaml::Hash(suite))
+        .map_err(|err| import_error(format!("Unable to generate test suite yaml: {:?}", err)))?;
+    yaml_string.push('\n');
+    Ok(yaml_string)
+}
+
+pub fn import_agent(agent_zip: &Path, options: &AgentImportOptions) -> Result<AgentImportResult> {
+    let agent = DialogFlowAgent::from_zip(File::open(agent_zip)?)?;
+
+    let lang = options
+        .lang
+        .clone()
+        .or(agent.default_lang.clone())
+        .unwrap_or(String::from("en"));
+
+    let intents = agent.training_phrases(&lang)?;
+    if intents.is_empty() {
+        return Err(import_error(format!(
+            "No training phrases for language '{}' found in {}",
+            lang,
+            agent_zip.display()
+        )));
+    }
+
+    let mut sampled_intents = vec![];
+    let mut holdout_intents = vec![];
+    for intent in &intents {
+        let (sampled, held_out) =
+            split_phrases(&intent.phrases, options.sample_ratio, options.holdout_ratio);
+        sampled_intents.push(IntentTrainingPhrases {
+            intent_name: intent.intent_name.clone(),
+            phrases: sampled,
+        });
+        holdout_intents.push(IntentTrainingPhrases {
+            intent_name: intent.intent_name.clone(),
+            phrases: held_out,
+        });
+    }
+
+    let agent_name = agent.name.clone().unwrap_or(
+        agent_zip
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().to_string())
+            .unwrap_or(String::from("DialogFlow agent")),
+    );
+
+    let suite_yaml = generate_suite_yaml(
+        &format!("{} - imported training phrases", agent_name),
+        &options.credentials_file,
+        &lang,
+        &sampled_intents,
+    )?;
+    let holdout_count: usize = holdout_intents.iter().map(|i| i.phrases.len()).sum();
+    let holdout_suite_yaml = match holdout_count {
+        0 => None,
+        _ => Some(generate_suite_yaml(
+            &format!("{} - held out training phrases", agent_name),
+            &options.credentials_file,
+            &lang,
+            &holdout_intents,
+        )?),
+    };
+
+    Ok(AgentImportResult {
+        suite_yaml,
+        holdout_suite_yaml,
+        intent_count: intents.len(),
+        skipped_intents: sampled_intents
+            .iter()
+            .filter(|intent| intent.phrases.is_empty())
+            .map(|intent| intent.intent_name.clone())
+            .collect(),
+        test_count: sampled_intents.iter().map(|i| i.phrases.len()).sum(),
+        holdout_count,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::yaml_parser::TestSuite;
+    use std::io::{Cursor, Write};
+    use yaml_rust::YamlLoader;
+    use zip::write::FileOptions;
+    use zip::ZipWriter;
+
+    fn build_agent_zip() -> Result<Cursor<Vec<u8>>> {
+        let mut writer = ZipWriter::new(Cursor::new(Vec::new()));
+        let files = vec![
+            (
+                "agent.json",
+                r#"{"displayName": "Express Tracking", "language": "en"}"#,
+            ),
+            (
+                "intents/Tracking_CS_0_Prompt_Gen.json",
+                r#"{"name": "Tracking|CS|0|Prompt|Gen"}"#,
+            ),
+            (
+                "intents/Tracking_CS_0_Prompt_Gen_usersays_en.json",
+                r#"[
+                    {"data": [{"text": "track a package"}], "isTemplate": false},
+                    {"data": [{"text": "track "}, {"text": "1234567891", "alias": "tracking_id"}], "isTemplate": false},
+                    {"data": [{"text": "Track a package"}], "isTemplate": false},
+                    {"data": [{"text": "@sys.any:any"}], "isTemplate": true},
+                    {"data": [{"text": "where is my parcel"}], "isTemplate": false}
+                ]"#,
+            ),
+            (
+                "intents/Tracking_CS_0_Prompt_Gen_usersays_es.json",
+                r#"[{"data": [{"text": "rastrear paquete"}], "isTemplate": false}]"#,
+            ),
+            (
+                "intents/Default Fallback Intent.json",
+                r#"{"name": "Default Fallback Intent"}"#,
+            ),
+        ];
+        for (name, content) in files {
+            writer.start_file(name, FileOptions::default())?;
+            writer.write_all(content.as_bytes())?;
+        }
+        Ok(writer.finish()?)
+    }
+
+    #[test]
+    fn test_training_phrases() -> Result<()> {
+        let agent = DialogFlowAgent::from_zip(build_agent_zip()?)?;
+        assert_eq!(agent.name, Some("Express Tracking".to_owned()));
+        assert_eq!(agent.default_lang, Some("en".to_owned()));
+
+        let intents = agent.training_phrases("en")?;
+        assert_eq!(
+            intents,
+            vec![IntentTrainingPhrases {
+                intent_name: "Tracking|CS|0|Prompt|Gen".to_owned(),
+                phrases: vec![
+                    "track a package".to_owned(),
+                    "track 1234567891".to_owned(),
+                    "where is my parcel".to_owned()
+                ],
+            }]
+        );
+        assert_eq!(
+            agent.training_phrases("es")?[0].phrases,
+            ["rastrear paquete"]
+        );
+        assert_eq!(agent.training_phrases("de")?.len(), 0);
+        Ok(())
+    }
+
+    #[test]
+    fn test_split_phrases() {
+        let phrases: Vec<String> = (1..=10).map(|i| format!("phrase {}", i)).collect();
+
+        let (sampled, held_out) = split_phrases(&phrases, 1.0, 0.0);
+        assert_eq!(sampled.len(), 10);
+        assert_eq!(held_out.len(), 0);
+
+        let (sampled, held_out) = split_phrases(&phrases, 0.5, 0.2);
+        assert_eq!(held_out, ["phrase 5", "phrase 10"]);
+        assert_eq!(sampled, ["phrase 2", "phrase 4", "phrase 7", "phrase 9"]);
+
+        // intent with few phrases is still represented
+        let (sampled, _) = split_phrases(&phrases[..3], 0.1, 0.0);
+        assert_eq!(sampled, ["phrase 1"]);
+        let (sampled, _) = split_phrases(&phrases, 0.0, 0.0);
+        assert_eq!(sampled.len(), 0);
+    }
+
+    #[test]
+    fn test_generated_suite_is_valid() -> Result<()> {
+        let agent = DialogFlowAgent::from_zip(build_agent_zip()?)?;
+        let intents = agent.training_phrases("en")?;
+        let yaml_string = generate_suite_yaml("Express Tracking", "/path/to/cred", "en", &intents)?;
+
+        let docs = YamlLoader::load_from_str(&yaml_string)?;
+        let suite = TestSuite::from_yaml(&docs[0])?;
+        assert_eq!(suite.suite_spec.name, "Express Tracking");
+        assert_eq!(suite.tests.len(), 3);
+        assert_eq!(suite.tests[1].name, "Tracking|CS|0|Prompt|Gen #2");
+        assert_eq!(suite.tests[1].assertions[0].user_says, "track 1234567891");
+        assert_eq!(
+            suite.tests[1].assertions[0].bot_responds_with,
+            ["Tracking|CS|0|Prompt|Gen"]
+        );
+
+        let empty_intents = vec![IntentTrainingPhrases {
+            intent_name: "Tracking|CS|0|Prompt|Gen".to_owned(),
+            phrases: vec![],
+        }];
+        let err = generate_suite_yaml("Empty", "/path/to/cred", "en", &empty_intents)
+            .err()
+            .unwrap();
+        assert_eq!(
+            err.message,
+            "GenericError: No training phrases selected for test suite 'Empty'"
+        );
+        Ok(())
+    }
+}
@@ -1,24 +1,94 @@
-use clap::{App, Arg, ArgMatches};
+use clap::{App, AppSettings, Arg, ArgGroup, ArgMatches, SubCommand};
 use log::debug;
 use std::path::Path;
 
+use crate::suite_loader::SuiteFormat;
+
 pub struct CommandLine<'a> {
     pub test_suite_file: Box<&'a Path>,
+    pub format: Option<SuiteFormat>,
+    pub suite_spec_file: Option<Box<&'a Path>>,
     pub print_to_std_out: bool,
-    pub html_report_path: Option<Box<&'a Path>>,
-    pub json_report_path: Option<Box<&'a Path>>,
+    pub reports: Vec<String>, // report specifications <kind>=<path>, see ResultReporterRegistry
+    pub history_dir: Option<Box<&'a Path>>,
     pub threadpool_size: usize,
+    pub update_snapshots: bool,
+    pub repeat: usize,
+    pub min_pass_rate: f64,
 }
 
 impl<'a> CommandLine<'a> {
-    fn new(test_suite_file: Box<&'a Path>) -> Self {
-        return CommandLine {
-            test_suite_file,
+    fn new(test_suite_file: &'a Path) -> Self {
+        CommandLine {
+            test_suite_file: Box::new(test_suite_file),
+            format: None,
+            suite_spec_file: None,
             print_to_std_out: true,
-            html_report_path: None,
-            json_report_path: None,
+            reports: vec![],
+            history_dir: None,
             threadpool_size: 4,
-        };
+            update_snapshots: false,
+            repeat: 1,
+            min_pass_rate: 1.0,
+        }
+    }
+}
+
+pub struct ImportAgentCommandLine<'a> {
+    pub agent_zip: Box<&'a Path>,
+    pub output_path: Option<Box<&'a Path>>,
+    pub holdout_output_path: Option<Box<&'a Path>>,
+    pub lang: Option<String>,
+    pub sample_ratio: f64,
+    pub holdout_ratio: f64,
+    pub credentials_file: Option<String>,
+}
+
+pub struct RecordCommandLine<'a> {
+    pub suite_file: Box<&'a Path>,
+    pub report_path: Option<Box<&'a Path>>,
+    pub transcript_path: Option<Box<&'a Path>>,
+    pub output_path: Option<Box<&'a Path>>,
+    pub lang: String,
+    pub checks: Vec<String>,
+    pub threadpool_size: usize,
+}
+
+pub struct ValidateCommandLine<'a> {
+    pub suite_files: Vec<Box<&'a Path>>,
+}
+
+pub struct SchemaCommandLine<'a> {
+    pub output_path: Option<Box<&'a Path>>,
+}
+
+pub struct DiffCommandLine<'a> {
+    pub old_report: Box<&'a Path>,
+    pub new_report: Box<&'a Path>,
+    pub html_report_path: Option<Box<&'a Path>>,
+    pub print_to_std_out: bool,
+}
+
+pub struct HistoryCommandLine<'a> {
+    pub history_dir: Box<&'a Path>,
+    pub last_runs: usize,
+    pub flaky_only: bool,
+}
+
+fn validate_positive_integer(value: String) -> Result<(), String> {
+    match value.parse::<usize>() {
+        Ok(number) if number > 0 => Ok(()),
+        _ => Err(format!("positive integer expected, got {}", value)),
+    }
+}
+
+fn validate_ratio(value: String) -> Result<(), String> {
+    match value.parse::<f64>() {
+        Ok(ratio) if (0.0..=1.0).contains(&ratio) => Ok(()),
+        _ => Err(format!(
+            "ratio must be number between 0 and 1, got {}",
+            value
+        )),
     }
 }
 
@@ -32,10 +102,27 @@ pub fn get_cmd_line_parser<'a, 'b>() -> App<'a, 'b> {
                 .short("f")
                 .long("suite-file")
                 .value_name("FILE")
-                .help("Yaml file with test suite definition")
+                .help("File with test suite definition (yaml, json or toml) or json report of previous run")
                 .takes_value(true)
                 .required(true)
         )
+        .arg(
+            Arg::with_name("format")
+                .long("format")
+                .value_name("FORMAT")
+                .help("Format of suite file. If not specified it is derived from file extension (.json, .toml), yaml otherwise.")
+                .takes_value(true)
+                .possible_values(&["yaml", "json", "toml"])
+                .required(false)
+        )
+        .arg(
+            Arg::with_name("suite_spec")
+                .long("suite-spec")
+                .value_name("FILE")
+                .help("Suite file whose suite-spec is used when running tests from json report of previous run")
+                .takes_value(true)
+                .required(false)
+        )
         .arg(
             Arg::with_name("html_report")
                 .long("html-report")
@@ -52,6 +139,40 @@ pub fn get_cmd_line_parser<'a, 'b>() -> App<'a, 'b> {
                 .takes_value(true)
                 .required(false)
         )
+        .arg(
+            Arg::with_name("md_report")
+                .long("md-report")
+                .value_name("FILE")
+                .help("Path to optional markdown report (summary and failing tests, suitable for pull request comments)")
+                .takes_value(true)
+                .required(false)
+        )
+        .arg(
+            Arg::with_name("classification_report")
+                .long("classification-report")
+                .value_name("FILE")
+                .help("Path to optional html intent classification report (confusion matrix, precision/recall per intent)")
+                .takes_value(true)
+                .required(false)
+        )
+        .arg(
+            Arg::with_name("report")
+                .long("report")
+                .value_name("KIND=FILE")
+                .help("Additional report, can be specified multiple times. Kinds: stdout, html, json, jsonl (json line per test written as tests finish), md, classification (printed to standard output if file is not specified), history (run history directory)")
+                .takes_value(true)
+                .multiple(true)
+                .number_of_values(1)
+                .required(false)
+        )
+        .arg(
+            Arg::with_name("history_dir")
+                .long("history-dir")
+                .value_name("DIR")
+                .help("Directory with run history. Results of the run are appended there and html report shows history of every test (see history subcommand).")
+                .takes_value(true)
+                .required(false)
+        )
         .arg(
             Arg::with_name("surpress_stdout_report")
                 .long("disable-stdout-report")
@@ -67,13 +188,257 @@ pub fn get_cmd_line_parser<'a, 'b>() -> App<'a, 'b> {
                 .takes_value(true)
                 .default_value("4")
         )
+        .arg(
+            Arg::with_name("update_snapshots")
+                .long("update-snapshots")
+                .help("Rewrites stored response snapshots by actual responses instead of comparing them")
+                .required(false)
+        )
+        .arg(
+            Arg::with_name("repeat")
+                .long("repeat")
+                .value_name("N")
+                .help("Runs every test N times (each time as new conversation) and reports pass rate and intents observed per turn")
+                .takes_value(true)
+                .validator(validate_positive_integer)
+                .conflicts_with("update_snapshots")
+                .required(false)
+        )
+        .arg(
+            Arg::with_name("min_pass_rate")
+                .long("min-pass-rate")
+                .value_name("RATIO")
+                .help("Portion of repetitions (0-1) which must pass for test to pass. If not specified all repetitions must pass.")
+                .takes_value(true)
+                .validator(validate_ratio)
+                .requires("repeat")
+                .required(false)
+        )
+        // suite file is not required when running subcommand
+        .setting(AppSettings::SubcommandsNegateReqs)
+        .subcommand(
+            SubCommand::with_name("import-agent")
+                .about("Generates test suite yaml from training phrases of DialogFlow ES agent export (zip file)")
+                .arg(
+                    Arg::with_name("agent_zip")
+                        .value_name("AGENT_ZIP")
+                        .help("DialogFlow agent export zip file")
+                        .required(true)
+                        .index(1)
+                )
+                .arg(
+                    Arg::with_name("output")
+                        .short("o")
+                        .long("output")
+                        .value_name("FILE")
+                        .help("Path to generated test suite yaml. If not specified suite is printed to standard output.")
+                        .takes_value(true)
+                        .required(false)
+                )
+                .arg(
+                    Arg::with_name("lang")
+                        .short("l")
+                        .long("lang")
+                        .value_name("LANG")
+                        .help("Language of imported training phrases. If not specified default language of agent is used.")
+                        .takes_value(true)
+                        .required(false)
+                )
+                .arg(
+                    Arg::with_name("sample_ratio")
+                        .long("sample-ratio")
+                        .value_name("RATIO")
+                        .help("Portion of training phrases (0-1) converted into tests. If not specified defaults to 1.")
+                        .takes_value(true)
+                        .validator(validate_ratio)
+                        .default_value("1")
+                )
+                .arg(
+                    Arg::with_name("holdout_ratio")
+                        .long("holdout-ratio")
+                        .value_name("RATIO")
+                        .help("Portion of training phrases (0-1) held out before sampling. If not specified defaults to 0.")
+                        .takes_value(true)
+                        .validator(validate_ratio)
+                        .default_value("0")
+                )
+                .arg(
+                    Arg::with_name("holdout_output")
+                        .long("holdout-output")
+                        .value_name("FILE")
+                        .help("Path to test suite yaml generated from held out training phrases")
+                        .takes_value(true)
+                        .required(false)
+                )
+                .arg(
+                    Arg::with_name("credentials_file")
+                        .long("credentials-file")
+                        .value_name("FILE")
+                        .help("Path to DialogFlow credentials file written into generated suite")
+                        .takes_value(true)
+                        .required(false)
+                )
+        )
+        .subcommand(
+            SubCommand::with_name("record")
+                .about("Runs recorded conversations and generates test suite yaml from observed responses")
+                .arg(
+                    Arg::with_name("suite_file")
+                        .short("f")
+                        .long("suite-file")
+                        .value_name("FILE")
+                        .help("File with test suite definition (yaml, json or toml), only suite-spec is used (NLP provider and its configuration)")
+                        .takes_value(true)
+                        .required(true)
+                )
+                .arg(
+                    Arg::with_name("from_report")
+                        .long("from-report")
+                        .value_name("FILE")
+                        .help("Json report (see --json-report) with conversations to record")
+                        .takes_value(true)
+                )
+                .arg(
+                    Arg::with_name("from_transcript")
+                        .long("from-transcript")
+                        .value_name("FILE")
+                        .help("Text file with conversations to record, one utterance per line, conversations separated by empty line")
+                        .takes_value(true)
+                )
+                .group(
+                    ArgGroup::with_name("source")
+                        .args(&["from_report", "from_transcript"])
+                        .required(true)
+                )
+                .arg(
+                    Arg::with_name("output")
+                        .short("o")
+                        .long("output")
+                        .value_name("FILE")
+                        .help("Path to generated test suite yaml. If not specified suite is printed to standard output.")
+                        .takes_value(true)
+                        .required(false)
+                )
+                .arg(
+                    Arg::with_name("lang")
+                        .short("l")
+                        .long("lang")
+                        .value_name("LANG")
+                        .help("Language of transcript conversations. If not specified defaults to en.")
+                        .takes_value(true)
+                        .default_value("en")
+                )
+                .arg(
+                    Arg::with_name("checks")
+                        .long("checks")
+                        .value_name("CHECK")
+                        .help("Response checks generated from observed responses")
+                        .takes_value(true)
+                        .multiple(true)
+                        .use_delimiter(true)
+                        .possible_values(&["action", "parameters"])
+                )
+                .arg(
+                    Arg::with_name("threadpool_size")
+                        .short("t")
+                        .long("threadpool-size")
+                        .value_name("INTEGER")
+                        .help("Number of worker threads for parallel recording. If not specified defaults to 4.")
+                        .takes_value(true)
+                        .default_value("4")
+                )
+        )
+        .subcommand(
+            SubCommand::with_name("validate")
+                .about("Checks test suite files without running the tests (no credentials or network access needed)")
+                .arg(
+                    Arg::with_name("suite_files")
+                        .value_name("FILE")
+                        .help("Files with test suite definition (yaml, json or toml, format is derived from file extension)")
+                        .required(true)
+                        .multiple(true)
+                        .index(1)
+                )
+        )
+        .subcommand(
+            SubCommand::with_name("schema")
+                .about("Prints json schema of test suite file format (e.g. for autocompletion in VS Code)")
+                .arg(
+                    Arg::with_name("output")
+                        .short("o")
+                        .long("output")
+                        .value_name("FILE")
+                        .help("Path to generated json schema. If not specified schema is printed to standard output.")
+                        .takes_value(true)
+                        .required(false)
+                )
+        )
+        .subcommand(
+            SubCommand::with_name("diff")
+                .about("Compares two json reports (e.g. before and after agent training change): newly failing/passing tests and intent changes")
+                .arg(
+                    Arg::with_name("old_report")
+                        .value_name("OLD_JSON")
+                        .help("Json report of previous run")
+                        .required(true)
+                        .index(1)
+                )
+                .arg(
+                    Arg::with_name("new_report")
+                        .value_name("NEW_JSON")
+                        .help("Json report of current run")
+                        .required(true)
+                        .index(2)
+                )
+                .arg(
+                    Arg::with_name("html_report")
+                        .long("html-report")
+                        .value_name("FILE")
+                        .help("Path to optional html comparison report")
+                        .takes_value(true)
+                        .required(false)
+                )
+                .arg(
+                    Arg::with_name("surpress_stdout_report")
+                        .long("disable-stdout-report")
+                        .help("Disables comparison tables printed to standard output")
+                        .required(false)
+                )
+        )
+        .subcommand(
+            SubCommand::with_name("history")
+                .about("Reports pass rate, flaky tests and trends from run history (see --history-dir)")
+                .arg(
+                    Arg::with_name("history_dir")
+                        .value_name("DIR")
+                        .help("Directory with run history")
+                        .required(true)
+                        .index(1)
+                )
+                .arg(
+                    Arg::with_name("last")
+                        .short("n")
+                        .long("last")
+                        .value_name("RUNS")
+                        .help("Number of most recent runs evaluated. If not specified defaults to 10.")
+                        .takes_value(true)
+                        .validator(validate_positive_integer)
+                        .default_value("10")
+                )
+                .arg(
+                    Arg::with_name("flaky_only")
+                        .long("flaky-only")
+                        .help("Lists only flaky tests (both passed and failed within evaluated runs)")
+                        .required(false)
+                )
+        )
 }
 
 pub fn get_cmdl_options<'a>(matches: &'a ArgMatches) -> CommandLine<'a> {
     let mut command_line;
     if let Some(file) = matches.value_of("suite_file") {
         debug!("Value for suite_file: {}", file);
-        command_line = CommandLine::new(Box::new(Path::new(file)));
+        command_line = CommandLine::new(Path::new(file));
     } else {
         // this will never hapen since clap will not allow to get here without suite file
         // but we need to implement this to fool compiler, otherwise it will be complaining about error:
@@ -82,14 +447,49 @@ pub fn get_cmdl_options<'a>(matches: &'a ArgMatches) -> CommandLine<'a> {
         std::process::exit(1);
     }
 
+    if let Some(format) = matches.value_of("format") {
+        debug!("Value for format: {}", format);
+        command_line.format = SuiteFormat::from_name(format);
+    }
+
+    if let Some(file) = matches.value_of("suite_spec") {
+        debug!("Value for suite_spec: {}", file);
+        command_line.suite_spec_file = Some(Box::new(Path::new(file)));
+    }
+
     if let Some(file) = matches.value_of("html_report") {
         debug!("Value for html_report: {}", file);
-        command_line.html_report_path = Some(Box::new(Path::new(file)));
+        command_line.reports.push(format!("html={}", file));
     }
 
     if let Some(file) = matches.value_of("json_report") {
         debug!("Value for json_report: {}", file);
-        command_line.json_report_path = Some(Box::new(Path::new(file)));
+        command_line.reports.push(format!("json={}", file));
+    }
+
+    if let Some(file) = matches.value_of("md_report") {
+        debug!("Value for md_report: {}", file);
+        command_line.reports.push(format!("md={}", file));
+    }
+
+    if let Some(file) = matches.value_of("classification_report") {
+        debug!("Value for classification_report: {}", file);
+        command_line
+            .reports
+            .push(format!("classification={}", file));
+    }
+
+    if let Some(reports) = matches.values_of("report") {
+        for report in reports {
+            debug!("Value for report: {}", report);
+            command_line.reports.push(report.to_owned());
+        }
+    }
+
+    if let Some(dir) = matches.value_of("history_dir") {
+        debug!("Value for history_dir: {}", dir);
+        command_line.history_dir = Some(Box::new(Path::new(dir)));
+        command_line.reports.push(format!("history={}", dir));
     }
 
     if matches.is_present("surpress_stdout_report") {
@@ -97,6 +497,22 @@ pub fn get_cmdl_options<'a>(matches: &'a ArgMatches) -> CommandLine<'a> {
         command_line.print_to_std_out = false;
     }
 
+    if matches.is_present("update_snapshots") {
+        debug!("Response snapshots will be updated.");
+        command_line.update_snapshots = true;
+    }
+
+    // safe to unwrap, clap validates the values
+    if let Some(repeat) = matches.value_of("repeat") {
+        debug!("Value for repeat: {}", repeat);
+        command_line.repeat = repeat.parse::<usize>().unwrap();
+    }
+
+    if let Some(min_pass_rate) = matches.value_of("min_pass_rate") {
+        debug!("Value for min_pass_rate: {}", min_pass_rate);
+        command_line.min_pass_rate = min_pass_rate.parse::<f64>().unwrap();
+    }
+
     // safe to unwrap, clap provides default value
     command_line.threadpool_size = matches
         .value_of("threadpool_size")
@@ -107,3 +523,161 @@ pub fn get_cmdl_options<'a>(matches: &'a ArgMatches) -> CommandLine<'a> {
 
     command_line
 }
+
+pub fn get_import_agent_cmdl_options<'a>(matches: &'a ArgMatches) -> ImportAgentCommandLine<'a> {
+    // safe to unwrap, clap will not allow to get here without agent zip file
+    let agent_zip = matches.value_of("agent_zip").unwrap();
+    debug!("Value for agent_zip: {}", agent_zip);
+
+    // safe to unwrap, clap provides default values and validates them
+    let mut command_line = ImportAgentCommandLine {
+        agent_zip: Box::new(Path::new(agent_zip)),
+        output_path: None,
+        holdout_output_path: None,
+        lang: None,
+        sample_ratio: matches
+            .value_of("sample_ratio")
+            .unwrap()
+            .parse::<f64>()
+            .unwrap(),
+        holdout_ratio: matches
+            .value_of("holdout_ratio")
+            .unwrap()
+            .parse::<f64>()
+            .unwrap(),
+        credentials_file: None,
+    };
+
+    if let Some(file) = matches.value_of("output") {
+        debug!("Value for output: {}", file);
+        command_line.output_path = Some(Box::new(Path::new(file)));
+    }
+
+    if let Some(file) = matches.value_of("holdout_output") {
+        debug!("Value for holdout_output: {}", file);
+        command_line.holdout_output_path = Some(Box::new(Path::new(file)));
+    }
+
+    if let Some(lang) = matches.value_of("lang") {
+        debug!("Value for lang: {}", lang);
+        command_line.lang = Some(lang.to_owned());
+    }
+
+    if let Some(file) = matches.value_of("credentials_file") {
+        debug!("Value for credentials_file: {}", file);
+        command_line.credentials_file = Some(file.to_owned());
+    }
+
+    command_line
+}
+
+pub fn get_record_cmdl_options<'a>(matches: &'a ArgMatches) -> RecordCommandLine<'a> {
+    // safe to unwrap, clap will not allow to get here without suite file
+    let suite_file = matches.value_of("suite_file").unwrap();
+    debug!("Value for suite_file: {}", suite_file);
+
+    // safe to unwrap, clap provides default values
+    let mut command_line = RecordCommandLine {
+        suite_file: Box::new(Path::new(suite_file)),
+        report_path: None,
+        transcript_path: None,
+        output_path: None,
+        lang: matches.value_of("lang").unwrap().to_owned(),
+        checks: vec![],
+        threadpool_size: matches
+            .value_of("threadpool_size")
+            .unwrap()
+            .parse::<usize>()
+            .unwrap(),
+    };
+
+    if let Some(file) = matches.value_of("from_report") {
+        debug!("Value for from_report: {}", file);
+        command_line.report_path = Some(Box::new(Path::new(file)));
+    }
+
+    if let Some(file) = matches.value_of("from_transcript") {
+        debug!("Value for from_transcript: {}", file);
+        command_line.transcript_path = Some(Box::new(Path::new(file)));
+    }
+
+    if let Some(file) = matches.value_of("output") {
+        debug!("Value for output: {}", file);
+        command_line.output_path = Some(Box::new(Path::new(file)));
+    }
+
+    if let Some(checks) = matches.values_of("checks") {
+        command_line.checks = checks.map(|check| check.to_owned()).collect();
+        debug!("Value for checks: {:?}", command_line.checks);
+    }
+
+    command_line
+}
+
+pub fn get_validate_cmdl_options<'a>(matches: &'a ArgMatches) -> ValidateCommandLine<'a> {
+    // safe to unwrap, clap will not allow to get here without suite files
+    let suite_files: Vec<Box<&'a Path>> = matches
+        .values_of("suite_files")
+        .unwrap()
+        .map(|file| Box::new(Path::new(file)))
+        .collect();
+    debug!("Value for suite_files: {:?}", suite_files);
+
+    ValidateCommandLine { suite_files }
+}
+
+pub fn get_schema_cmdl_options<'a>(matches: &'a ArgMatches) -> SchemaCommandLine<'a> {
+    let mut command_line = SchemaCommandLine { output_path: None };
+
+    if let Some(file) = matches.value_of("output") {
+        debug!("Value for output: {}", file);
+        command_line.output_path = Some(Box::new(Path::new(file)));
+    }
+
+    command_line
+}
+
+pub fn get_diff_cmdl_options<'a>(matches: &'a ArgMatches) -> DiffCommandLine<'a> {
+    // safe to unwrap, clap will not allow to get here without both reports
+    let old_report = matches.value_of("old_report").unwrap();
+    let new_report = matches.value_of("new_report").unwrap();
+    debug!(
+        "Value for old_report: {}, new_report: {}",
+        old_report, new_report
+    );
+
+    let mut command_line = DiffCommandLine {
+        old_report: Box::new(Path::new(old_report)),
+        new_report: Box::new(Path::new(new_report)),
+        html_report_path: None,
+        print_to_std_out: true,
+    };
+
+    if let Some(file) = matches.value_of("html_report") {
+        debug!("Value for html_report: {}", file);
+        command_line.html_report_path = Some(Box::new(Path::new(file)));
+    }
+
+    if matches.is_present("surpress_stdout_report") {
+        debug!("Standard output report will be surpressed.");
+        command_line.print_to_std_out = false;
+    }
+
+    command_line
+}
+
+pub fn get_history_cmdl_options<'a>(matches: &'a ArgMatches) -> HistoryCommandLine<'a> {
+    // safe to unwrap, clap will not allow to get here without history directory
+    let history_dir = matches.value_of("history_dir").unwrap();
+    debug!("Value for history_dir: {}", history_dir);
+
+    // safe to unwrap, clap provides default value and validates it
+    let command_line = HistoryCommandLine {
+        history_dir: Box::new(Path::new(history_dir)),
+        last_runs: matches.value_of("last").unwrap().parse::<usize>().unwrap(),
+        flaky_only: matches.is_present("flaky_only"),
+    };
+    debug!("Value for last: {}", command_line.last_runs);
+
+    command_line
+}
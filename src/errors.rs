@@ -1,4 +1,5 @@
 use crate::yaml_parser::Test;
+use crate::yaml_positions::Position;
 use jmespath::JmespathError;
 use jsonwebtoken;
 use reqwest;
@@ -11,12 +12,30 @@ use std::result;
 use std::sync::mpsc::SendError;
 use yaml_rust::scanner::ScanError;
 
+// single problem found in test suite definition. Path addresses respective yaml node
+// (e.g. tests[0].assertions[1].userSays), position is known only when suite is parsed from source string
+#[derive(Debug, Clone, PartialEq)]
+pub struct YamlParsingIssue {
+    pub message: String,
+    pub path: String,
+    pub position: Option<Position>,
+}
+
+impl fmt::Display for YamlParsingIssue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.position {
+            Some(position) => write!(f, "{}: {}", position, self.message),
+            None => write!(f, "{}", self.message),
+        }
+    }
+}
+
 #[derive(Debug)]
 pub enum ErrorKind {
     GDFTokenRetrievalError,
     GDFInvocationError,
     HttpInvocationError(reqwest::Error),
-    YamlParsingError(String),
+    YamlParsingError(Vec<YamlParsingIssue>),
     YamlLoadingError(ScanError),
     JsonParsingError(JmespathError),
     IOError(std::io::Error),
@@ -26,7 +45,10 @@ pub enum ErrorKind {
     InvalidHeaderValueError(InvalidHeaderValue),
     InvalidTestAssertionEvaluation,
     InvalidTestAssertionResponseCheckEvaluation,
+    InvalidTestAssertionConfidence,
     ChannelSendError(SendError<Test>),
+    ZipError(zip::result::ZipError),
+    TomlParsingError(toml::de::Error),
 }
 
 //default is required if we want to skip ErrorKind for serialization/deserialization, see #[serde(skip)] below
@@ -42,7 +64,10 @@ impl fmt::Display for ErrorKind {
             ErrorKind::GDFTokenRetrievalError => write!(f, "GDFTokenRetrievalError"),
             ErrorKind::GDFInvocationError => write!(f, "GDFInvocationError"),
             ErrorKind::HttpInvocationError(err) => write!(f, "HttpInvocationError: {}", err),
-            ErrorKind::YamlParsingError(err) => write!(f, "YamlParsingError: {}", err),
+            ErrorKind::YamlParsingError(issues) => {
+                let issues: Vec<String> = issues.iter().map(|issue| issue.to_string()).collect();
+                write!(f, "YamlParsingError: {}", issues.join("\n"))
+            }
             ErrorKind::YamlLoadingError(err) => write!(f, "YamlLoadingError: {}", err),
             ErrorKind::JsonParsingError(err) => write!(f, "JsonParsingError: {}", err),
             ErrorKind::IOError(err) => write!(f, "IOError: {}", err),
@@ -58,7 +83,12 @@ impl fmt::Display for ErrorKind {
             ErrorKind::InvalidTestAssertionResponseCheckEvaluation => {
                 write!(f, "InvalidTestAssertionResponseCheckEvaluation")
             }
+            ErrorKind::InvalidTestAssertionConfidence => {
+                write!(f, "InvalidTestAssertionConfidence")
+            }
             ErrorKind::ChannelSendError(_) => write!(f, "ChannelSendError"),
+            ErrorKind::ZipError(err) => write!(f, "ZipError: {}", err),
+            ErrorKind::TomlParsingError(err) => write!(f, "TomlParsingError: {}", err),
         }
     }
 }
@@ -96,7 +126,7 @@ pub(crate) fn new_error_from(kind: ErrorKind) -> Error {
     let message = kind.to_string();
     Error {
         kind: Box::new(kind),
-        message: message,
+        message,
         code: None,
         backend_response: None, // used to capture Google DialogFlow or VAP response for final error report.
                                 //User must see what went wrong while evaluating response from DialogFlow/VAP
@@ -159,7 +189,10 @@ impl StdError for Error {
             ErrorKind::InvalidHeaderValueError(ref err) => Some(err),
             ErrorKind::InvalidTestAssertionEvaluation => None,
             ErrorKind::InvalidTestAssertionResponseCheckEvaluation => None,
+            ErrorKind::InvalidTestAssertionConfidence => None,
             ErrorKind::ChannelSendError(ref err) => Some(err),
+            ErrorKind::ZipError(ref err) => Some(err),
+            ErrorKind::TomlParsingError(ref err) => Some(err),
         }
     }
 }
@@ -224,6 +257,18 @@ impl From<SendError<Test>> for Error {
     }
 }
 
+impl From<zip::result::ZipError> for Error {
+    fn from(error: zip::result::ZipError) -> Error {
+        new_error_from(ErrorKind::ZipError(error))
+    }
+}
+
+impl From<toml::de::Error> for Error {
+    fn from(error: toml::de::Error) -> Error {
+        new_error_from(ErrorKind::TomlParsingError(error))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
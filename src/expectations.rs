@@ -0,0 +1,333 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use std::fmt;
+
+use crate::errors::{new_error, new_service_call_error, ErrorKind, Result};
+use crate::json_parser::JsonParser;
+use crate::yaml_parser::TestAssertionResponseCheck;
+
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub struct ContextSpec {
+    pub name: String,
+    pub lifespan: Option<i64>,
+}
+
+impl fmt::Display for ContextSpec {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.lifespan {
+            Some(lifespan) => write!(f, "{} (lifespan {})", self.name, lifespan),
+            None => write!(f, "{}", self.name),
+        }
+    }
+}
+
+// expectParameters and expectContexts assertion fields
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub enum ExpectSpec {
+    Parameters(Map<String, Value>), // listed parameters must be present with equal values
+    Contexts(Vec<ContextSpec>),     // listed contexts must be active, order is not important
+}
+
+impl fmt::Display for ExpectSpec {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ExpectSpec::Parameters(parameters) => {
+                write!(f, "{}", Value::Object(parameters.clone()))
+            }
+            ExpectSpec::Contexts(contexts) => write!(
+                f,
+                "{}",
+                contexts
+                    .iter()
+                    .map(|c| c.to_string())
+                    .collect::<Vec<String>>()
+                    .join(", ")
+            ),
+        }
+    }
+}
+
+impl ExpectSpec {
+    // applies f to every string of expectation (used for parameter substitution)
+    pub fn map_strings<F>(&self, f: F) -> Result<ExpectSpec>
+    where
+        F: Fn(&str) -> Result<String>,
+    {
+        fn map_value<F>(value: &Value, f: &F) -> Result<Value>
+        where
+            F: Fn(&str) -> Result<String>,
+        {
+            Ok(match value {
+                Value::String(str_val) => Value::String(f(str_val)?),
+                Value::Array(arr) => Value::Array(
+                    arr.iter()
+                        .map(|v| map_value(v, f))
+                        .collect::<Result<Vec<Value>>>()?,
+                ),
+                Value::Object(obj) => {
+                    let mut mapped = Map::new();
+                    for (key, v) in obj {
+                        mapped.insert(key.to_owned(), map_value(v, f)?);
+                    }
+                    Value::Object(mapped)
+                }
+                _ => value.clone(),
+            })
+        }
+
+        Ok(match self {
+            ExpectSpec::Parameters(parameters) => {
+                let mut mapped = Map::new();
+                for (key, value) in parameters {
+                    mapped.insert(key.to_owned(), map_value(value, &f)?);
+                }
+                ExpectSpec::Parameters(mapped)
+            }
+            ExpectSpec::Contexts(contexts) => ExpectSpec::Contexts(
+                contexts
+                    .iter()
+                    .map(|c| {
+                        Ok(ContextSpec {
+                            name: f(&c.name)?,
+                            lifespan: c.lifespan,
+                        })
+                    })
+                    .collect::<Result<Vec<ContextSpec>>>()?,
+            ),
+        })
+    }
+}
+
+// equality of json values where numbers are compared as floats (DialogFlow returns 2 as 2.0)
+// and arrays are compared regardless of order of their elements
+pub fn values_equal(actual: &Value, expected: &Value) -> bool {
+    match (actual, expected) {
+        (Value::Number(actual), Value::Number(expected)) => actual.as_f64() == expected.as_f64(),
+        (Value::Array(actual), Value::Array(expected)) => {
+            if actual.len() != expected.len() {
+                return false;
+            }
+            // values_equal is equivalence, i.e. greedy matching is sufficient
+            let mut used = vec![false; actual.len()];
+            expected.iter().all(|expected_value| {
+                let found = actual.iter().enumerate().position(|(idx, actual_value)| {
+                    !used[idx] && values_equal(actual_value, expected_value)
+                });
+                match found {
+                    Some(idx) => {
+                        used[idx] = true;
+                        true
+                    }
+                    None => false,
+                }
+            })
+        }
+        (Value::Object(actual), Value::Object(expected)) => {
+            actual.len() == expected.len()
+                && expected
+                    .iter()
+                    .all(|(key, expected_value)| match actual.get(key) {
+                        Some(actual_value) => values_equal(actual_value, expected_value),
+                        None => false,
+                    })
+        }
+        _ => actual == expected,
+    }
+}
+
+// context names are matched by suffix, i.e. 'tracking' matches
+// 'projects/<project>/agent/sessions/<session>/contexts/tracking'
+fn context_name_matches(actual_name: &str, expected_name: &str) -> bool {
+    let actual_name = actual_name.to_lowercase();
+    let expected_name = expected_name.to_lowercase();
+    actual_name == expected_name || actual_name.ends_with(&format!("/{}", expected_name))
+}
+
+fn short_context_name(name: &str) -> &str {
+    name.rsplit('/').next().unwrap_or(name)
+}
+
+pub fn check_parameters(
+    actual: &Value,
+    expected: &Map<String, Value>,
+) -> std::result::Result<(), String> {
+    let empty = Map::new();
+    let actual = actual.as_object().unwrap_or(&empty);
+
+    let mut differences = vec![];
+    for (name, expected_value) in expected {
+        match actual.get(name) {
+            Some(actual_value) if values_equal(actual_value, expected_value) => {}
+            Some(actual_value) => differences.push(format!(
+                "  {}: expected {}, actual {}",
+                name, expected_value, actual_value
+            )),
+            None => differences.push(format!("  {}: missing", name)),
+        }
+    }
+
+    if differences.is_empty() {
+        return Ok(());
+    }
+
+    Err(format!(
+        "Parameters not matching:\n{}\nActual parameters: {}",
+        differences.join("\n"),
+        Value::Object(actual.clone())
+    ))
+}
+
+pub fn check_contexts(
+    actual: &Value,
+    expected: &Vec<ContextSpec>,
+) -> std::result::Result<(), String> {
+    let empty = vec![];
+    let actual = actual.as_array().unwrap_or(&empty);
+
+    let mut differences = vec![];
+    for expected_context in expected {
+        let actual_context = actual.iter().find(|context| {
+            context_name_matches(
+                context["name"].as_str().unwrap_or(""),
+                &expected_context.name,
+            )
+        });
+
+        match (actual_context, expected_context.lifespan) {
+            (None, _) => differences.push(format!("  {}: missing", expected_context.name)),
+            (Some(actual_context), Some(lifespan)) => {
+                // expired contexts are returned without lifespanCount
+                let actual_lifespan = actual_context["lifespanCount"].as_i64().unwrap_or(0);
+                if actual_lifespan != lifespan {
+                    differences.push(format!(
+                        "  {}: expected lifespan {}, actual {}",
+                        expected_context.name, lifespan, actual_lifespan
+                    ));
+                }
+            }
+            (Some(_), None) => {}
+        }
+    }
+
+    if differences.is_empty() {
+        return Ok(());
+    }
+
+    let actual_contexts: Vec<String> = actual
+        .iter()
+        .map(|context| {
+            format!(
+                "{} (lifespan {})",
+                short_context_name(context["name"].as_str().unwrap_or("")),
+                context["lifespanCount"].as_i64().unwrap_or(0)
+            )
+        })
+        .collect();
+
+    Err(format!(
+        "Contexts not matching:\n{}\nActual contexts: {}",
+        differences.join("\n"),
+        if actual_contexts.is_empty() {
+            "(none)".to_owned()
+        } else {
+            actual_contexts.join(", ")
+        }
+    ))
+}
+
+// evaluates expectParameters/expectContexts check, expression points to queryResult node of response
+pub fn check_expectation(
+    spec: &ExpectSpec,
+    response_check: &TestAssertionResponseCheck,
+    parser: &JsonParser,
+) -> Result<()> {
+    let expression = &response_check.expression;
+    let query_result = response_check.search(parser)?;
+    let query_result = json!(query_result);
+
+    if !query_result.is_object() {
+        return Err(new_error(
+            ErrorKind::InvalidTestAssertionResponseCheckEvaluation,
+            format!(
+                "Unable to retrieve query result from expression: {}",
+                expression
+            ),
+            None,
+        ));
+    }
+
+    let result = match spec {
+        ExpectSpec::Parameters(parameters) => {
+            check_parameters(&query_result["parameters"], parameters)
+        }
+        ExpectSpec::Contexts(contexts) => check_contexts(&query_result["outputContexts"], contexts),
+    };
+
+    result.map_err(|message| {
+        new_service_call_error(
+            ErrorKind::InvalidTestAssertionResponseCheckEvaluation,
+            message,
+            None,
+            Some(parser.json().to_owned()),
+        )
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_parameters_and_contexts() {
+        let query_result = json!({
+            "parameters": {"tracking_id": "1234567891", "count": 2.0, "services": ["express", "economy"]},
+            "outputContexts": [
+                {"name": "projects/express-cs-dummy/agent/sessions/98fe9b3d/contexts/__system_counters__", "lifespanCount": 1},
+                {"name": "projects/express-cs-dummy/agent/sessions/98fe9b3d/contexts/tracking_prompt", "lifespanCount": 2},
+                {"name": "projects/express-cs-dummy/agent/sessions/98fe9b3d/contexts/welcome"}
+            ]
+        });
+
+        let parameters = json!({"count": 2, "services": ["economy", "express"]});
+        assert_eq!(
+            check_parameters(&query_result["parameters"], parameters.as_object().unwrap()),
+            Ok(())
+        );
+
+        let parameters = json!({"tracking_id": "1", "country": "DE", "services": ["express"]});
+        assert_eq!(
+            check_parameters(&query_result["parameters"], parameters.as_object().unwrap()),
+            Err("Parameters not matching:\n  country: missing\n  services: expected [\"express\"], actual [\"express\",\"economy\"]\n  tracking_id: expected \"1\", actual \"1234567891\"\nActual parameters: {\"count\":2.0,\"services\":[\"express\",\"economy\"],\"tracking_id\":\"1234567891\"}".to_owned())
+        );
+
+        let contexts = vec![
+            ContextSpec {
+                name: "Tracking_Prompt".to_owned(),
+                lifespan: Some(2),
+            },
+            ContextSpec {
+                name: "contexts/welcome".to_owned(),
+                lifespan: None,
+            },
+        ];
+        assert_eq!(
+            check_contexts(&query_result["outputContexts"], &contexts),
+            Ok(())
+        );
+
+        let contexts = vec![
+            ContextSpec {
+                name: "welcome".to_owned(),
+                lifespan: Some(1),
+            },
+            ContextSpec {
+                name: "prompt".to_owned(),
+                lifespan: None,
+            },
+        ];
+        assert_eq!(
+            check_contexts(&query_result["outputContexts"], &contexts),
+            Err("Contexts not matching:\n  welcome: expected lifespan 1, actual 0\n  prompt: missing\nActual contexts: __system_counters__ (lifespan 1), tracking_prompt (lifespan 2), welcome (lifespan 0)".to_owned())
+        );
+    }
+}
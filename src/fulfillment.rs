@@ -0,0 +1,483 @@
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::fmt;
+
+use crate::errors::{new_error, new_service_call_error, ErrorKind, Result};
+use crate::json_parser::JsonParser;
+use crate::yaml_parser::{TestAssertionResponseCheck, TestSuiteType};
+
+// prefix of regular expressions in botSays
+pub const REGEX_PREFIX: &str = "re:";
+
+// JMESPath expression of queryResult node for given suite type
+pub fn query_result_expression(suite_type: &TestSuiteType) -> &'static str {
+    match suite_type {
+        TestSuiteType::DialogFlow | TestSuiteType::Custom(_) => "queryResult",
+        TestSuiteType::DHLVAP => "dfResponse.queryResult",
+    }
+}
+
+#[derive(Debug, PartialEq, Clone, Default, Serialize, Deserialize)]
+pub struct CardSpec {
+    pub title: Option<String>,
+    pub subtitle: Option<String>,
+    pub text: Option<String>,
+    pub image_uri: Option<String>,
+    pub buttons: Vec<String>,
+}
+
+impl fmt::Display for CardSpec {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut fields = vec![];
+        if let Some(title) = &self.title {
+            fields.push(format!("title: {}", title));
+        }
+        if let Some(subtitle) = &self.subtitle {
+            fields.push(format!("subtitle: {}", subtitle));
+        }
+        if let Some(text) = &self.text {
+            fields.push(format!("text: {}", text));
+        }
+        if let Some(image_uri) = &self.image_uri {
+            fields.push(format!("imageUri: {}", image_uri));
+        }
+        if !self.buttons.is_empty() {
+            fields.push(format!("buttons: {}", self.buttons.join(", ")));
+        }
+        write!(f, "{}", fields.join(", "))
+    }
+}
+
+// expectation about fulfillment messages (botSays, quickReplies, card assertion fields)
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub enum MessageSpec {
+    Text(String), // substring or regular expression (re: prefix) of any text message
+    QuickReplies(Vec<String>), // all listed quick replies must be offered, order is not important
+    Card(CardSpec), // at least one card must match all specified fields
+}
+
+impl fmt::Display for MessageSpec {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            MessageSpec::Text(text) => write!(f, "{}", text),
+            MessageSpec::QuickReplies(replies) => write!(f, "{}", replies.join(", ")),
+            MessageSpec::Card(card) => write!(f, "{}", card),
+        }
+    }
+}
+
+impl MessageSpec {
+    // applies f to every string of expectation (used for parameter substitution)
+    pub fn map_strings<F>(&self, f: F) -> Result<MessageSpec>
+    where
+        F: Fn(&str) -> Result<String>,
+    {
+        let map_opt = |val: &Option<String>| -> Result<Option<String>> {
+            match val {
+                Some(val) => Ok(Some(f(val)?)),
+                None => Ok(None),
+            }
+        };
+        let map_vec =
+            |vals: &Vec<String>| -> Result<Vec<String>> { vals.iter().map(|val| f(val)).collect() };
+
+        Ok(match self {
+            MessageSpec::Text(text) => MessageSpec::Text(f(text)?),
+            MessageSpec::QuickReplies(replies) => MessageSpec::QuickReplies(map_vec(replies)?),
+            MessageSpec::Card(card) => MessageSpec::Card(CardSpec {
+                title: map_opt(&card.title)?,
+                subtitle: map_opt(&card.subtitle)?,
+                text: map_opt(&card.text)?,
+                image_uri: map_opt(&card.image_uri)?,
+                buttons: map_vec(&card.buttons)?,
+            }),
+        })
+    }
+
+    // checks that regular expressions are valid
+    pub fn validate(&self) -> std::result::Result<(), String> {
+        if let MessageSpec::Text(text) = self {
+            if let Some(pattern) = text.strip_prefix(REGEX_PREFIX) {
+                Regex::new(pattern).map_err(|err| {
+                    format!("Invalid regular expression '{}'. Error: {}", text, err)
+                })?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, PartialEq, Default)]
+pub struct Card {
+    pub title: String,
+    pub subtitle: String,
+    pub text: String,
+    pub image_uri: String,
+    pub buttons: Vec<String>,
+}
+
+impl fmt::Display for Card {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "title: {}, subtitle: {}, text: {}, imageUri: {}, buttons: [{}]",
+            self.title,
+            self.subtitle,
+            self.text,
+            self.image_uri,
+            self.buttons.join(", ")
+        )
+    }
+}
+
+// platform independent view of fulfillment messages
+#[derive(Debug, PartialEq, Default)]
+pub struct FulfillmentMessages {
+    pub texts: Vec<String>,
+    pub quick_replies: Vec<String>,
+    pub cards: Vec<Card>,
+}
+
+fn str_field(value: &Value, field: &str) -> String {
+    value[field].as_str().unwrap_or("").to_owned()
+}
+
+fn push_unique(values: &mut Vec<String>, value: String) {
+    if !value.is_empty() && !values.contains(&value) {
+        values.push(value);
+    }
+}
+
+impl FulfillmentMessages {
+    // collects texts, quick replies and cards of all platforms from queryResult node
+    // supports text, quickReplies, card, simpleResponses, basicCard, suggestions
+    // and custom payloads (DialogFlow Messenger richContent, Facebook quick_replies)
+    pub fn from_query_result(query_result: &Value) -> FulfillmentMessages {
+        let mut messages = FulfillmentMessages::default();
+
+        push_unique(
+            &mut messages.texts,
+            str_field(query_result, "fulfillmentText"),
+        );
+
+        let empty = vec![];
+        let fulfillment_messages = query_result["fulfillmentMessages"]
+            .as_array()
+            .unwrap_or(&empty);
+
+        for message in fulfillment_messages {
+            for text in message["text"]["text"].as_array().unwrap_or(&empty) {
+                push_unique(&mut messages.texts, text.as_str().unwrap_or("").to_owned());
+            }
+
+            for simple_response in message["simpleResponses"]["simpleResponses"]
+                .as_array()
+                .unwrap_or(&empty)
+            {
+                push_unique(
+                    &mut messages.texts,
+                    str_field(simple_response, "textToSpeech"),
+                );
+                push_unique(
+                    &mut messages.texts,
+                    str_field(simple_response, "displayText"),
+                );
+            }
+
+            for reply in message["quickReplies"]["quickReplies"]
+                .as_array()
+                .unwrap_or(&empty)
+            {
+                push_unique(
+                    &mut messages.quick_replies,
+                    reply.as_str().unwrap_or("").to_owned(),
+                );
+            }
+
+            for suggestion in message["suggestions"]["suggestions"]
+                .as_array()
+                .unwrap_or(&empty)
+            {
+                push_unique(&mut messages.quick_replies, str_field(suggestion, "title"));
+            }
+
+            let card = &message["card"];
+            if card.is_object() {
+                messages.cards.push(Card {
+                    title: str_field(card, "title"),
+                    subtitle: str_field(card, "subtitle"),
+                    text: String::new(),
+                    image_uri: str_field(card, "imageUri"),
+                    buttons: card["buttons"]
+                        .as_array()
+                        .unwrap_or(&empty)
+                        .iter()
+                        .map(|b| str_field(b, "text"))
+                        .collect(),
+                });
+            }
+
+            let basic_card = &message["basicCard"];
+            if basic_card.is_object() {
+                messages.cards.push(Card {
+                    title: str_field(basic_card, "title"),
+                    subtitle: str_field(basic_card, "subtitle"),
+                    text: str_field(basic_card, "formattedText"),
+                    image_uri: str_field(&basic_card["image"], "imageUri"),
+                    buttons: basic_card["buttons"]
+                        .as_array()
+                        .unwrap_or(&empty)
+                        .iter()
+                        .map(|b| str_field(b, "title"))
+                        .collect(),
+                });
+            }
+
+            let payload = &message["payload"];
+            if payload.is_object() {
+                messages.add_payload(payload);
+            }
+        }
+
+        messages
+    }
+
+    fn add_payload(&mut self, payload: &Value) {
+        let empty = vec![];
+
+        // facebook quick replies
+        for reply in payload["facebook"]["quick_replies"]
+            .as_array()
+            .unwrap_or(&empty)
+        {
+            push_unique(&mut self.quick_replies, str_field(reply, "title"));
+        }
+
+        // DialogFlow Messenger rich content (list of lists of items)
+        for items in payload["richContent"].as_array().unwrap_or(&empty) {
+            for item in items.as_array().unwrap_or(&empty) {
+                match item["type"].as_str() {
+                    Some("chips") => {
+                        for option in item["options"].as_array().unwrap_or(&empty) {
+                            push_unique(&mut self.quick_replies, str_field(option, "text"));
+                        }
+                    }
+                    Some("info") | Some("description") => {
+                        let text = match &item["text"] {
+                            Value::Array(lines) => lines
+                                .iter()
+                                .filter_map(|l| l.as_str())
+                                .collect::<Vec<&str>>()
+                                .join("\n"),
+                            _ => String::new(),
+                        };
+                        self.cards.push(Card {
+                            title: str_field(item, "title"),
+                            subtitle: str_field(item, "subtitle"),
+                            text,
+                            image_uri: str_field(&item["image"]["src"], "rawUrl"),
+                            buttons: vec![],
+                        });
+                    }
+                    Some("button") => {
+                        // standalone buttons are attached to preceding card
+                        if let Some(card) = self.cards.last_mut() {
+                            card.buttons.push(str_field(item, "text"));
+                        }
+                    }
+                    _ => {}
+                }
+            }
+        }
+    }
+
+    fn list(values: &[String]) -> String {
+        if values.is_empty() {
+            return "  (none)".to_owned();
+        }
+        values
+            .iter()
+            .map(|v| format!("  - {}", v))
+            .collect::<Vec<String>>()
+            .join("\n")
+    }
+
+    pub fn check(&self, spec: &MessageSpec) -> std::result::Result<(), String> {
+        match spec {
+            MessageSpec::Text(expected) => {
+                let matches = if let Some(pattern) = expected.strip_prefix(REGEX_PREFIX) {
+                    let regex = Regex::new(pattern).map_err(|err| {
+                        format!("Invalid regular expression '{}'. Error: {}", expected, err)
+                    })?;
+                    self.texts.iter().any(|text| regex.is_match(text))
+                } else {
+                    self.texts.iter().any(|text| text.contains(expected))
+                };
+
+                if matches {
+                    Ok(())
+                } else {
+                    Err(format!(
+                        "Bot did not say '{}'. Bot said:\n{}",
+                        expected,
+                        FulfillmentMessages::list(&self.texts)
+                    ))
+                }
+            }
+            MessageSpec::QuickReplies(expected) => {
+                let missing: Vec<String> = expected
+                    .iter()
+                    .filter(|reply| !self.quick_replies.contains(reply))
+                    .cloned()
+                    .collect();
+
+                if missing.is_empty() {
+                    Ok(())
+                } else {
+                    Err(format!(
+                        "Quick replies missing: {}. Offered quick replies:\n{}",
+                        missing.join(", "),
+                        FulfillmentMessages::list(&self.quick_replies)
+                    ))
+                }
+            }
+            MessageSpec::Card(expected) => {
+                let field_matches = |expected: &Option<String>, real: &String| match expected {
+                    Some(expected) => expected == real,
+                    None => true,
+                };
+                let matches = self.cards.iter().any(|card| {
+                    field_matches(&expected.title, &card.title)
+                        && field_matches(&expected.subtitle, &card.subtitle)
+                        && field_matches(&expected.text, &card.text)
+                        && field_matches(&expected.image_uri, &card.image_uri)
+                        && expected.buttons.iter().all(|b| card.buttons.contains(b))
+                });
+
+                if matches {
+                    Ok(())
+                } else {
+                    let cards: Vec<String> = self.cards.iter().map(|c| c.to_string()).collect();
+                    Err(format!(
+                        "No card matching {}. Cards in response:\n{}",
+                        expected,
+                        FulfillmentMessages::list(&cards)
+                    ))
+                }
+            }
+        }
+    }
+}
+
+// evaluates botSays/quickReplies/card check, expression points to queryResult node of response
+pub fn check_messages(
+    spec: &MessageSpec,
+    response_check: &TestAssertionResponseCheck,
+    parser: &JsonParser,
+) -> Result<()> {
+    let expression = &response_check.expression;
+    let query_result = response_check.search(parser)?;
+    let query_result = json!(query_result);
+
+    if !query_result.is_object() {
+        return Err(new_error(
+            ErrorKind::InvalidTestAssertionResponseCheckEvaluation,
+            format!(
+                "Unable to retrieve fulfillment messages from expression: {}",
+                expression
+            ),
+            None,
+        ));
+    }
+
+    FulfillmentMessages::from_query_result(&query_result)
+        .check(spec)
+        .map_err(|message| {
+            new_service_call_error(
+                ErrorKind::InvalidTestAssertionResponseCheckEvaluation,
+                message,
+                None,
+                Some(parser.json().to_owned()),
+            )
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fulfillment_messages() {
+        let query_result = json!({
+            "fulfillmentText": "Hi, this is Dummy Express.",
+            "fulfillmentMessages": [
+                {"text": {"text": ["Hi, this is Dummy Express."]}, "platform": "FACEBOOK"},
+                {"quickReplies": {"quickReplies": ["Track a package", "Get a quote"]}, "platform": "FACEBOOK"},
+                {"simpleResponses": {"simpleResponses": [{"textToSpeech": "Welcome to Dummy Express"}]}, "platform": "ACTIONS_ON_GOOGLE"},
+                {"suggestions": {"suggestions": [{"title": "Track a package"}, {"title": "Pay duties"}]}, "platform": "ACTIONS_ON_GOOGLE"},
+                {"card": {"title": "Tracking", "subtitle": "Shipment 1234", "buttons": [{"text": "Details", "postback": "details"}]}},
+                {"payload": {"richContent": [[
+                    {"type": "info", "title": "Rates", "subtitle": "Export"},
+                    {"type": "button", "text": "Get rate"},
+                    {"type": "chips", "options": [{"text": "Manage delivery"}]}
+                ]]}}
+            ]
+        });
+
+        let messages = FulfillmentMessages::from_query_result(&query_result);
+        assert_eq!(
+            messages.texts,
+            vec!["Hi, this is Dummy Express.", "Welcome to Dummy Express"]
+        );
+        assert_eq!(
+            messages.quick_replies,
+            vec![
+                "Track a package",
+                "Get a quote",
+                "Pay duties",
+                "Manage delivery"
+            ]
+        );
+        assert_eq!(messages.cards.len(), 2);
+        assert_eq!(messages.cards[1].buttons, vec!["Get rate"]);
+
+        assert!(messages
+            .check(&MessageSpec::Text("Dummy Express".to_owned()))
+            .is_ok());
+        assert!(messages
+            .check(&MessageSpec::Text("re:^Welcome to \\w+".to_owned()))
+            .is_ok());
+        assert_eq!(
+            messages.check(&MessageSpec::Text("Goodbye".to_owned())),
+            Err("Bot did not say 'Goodbye'. Bot said:\n  - Hi, this is Dummy Express.\n  - Welcome to Dummy Express".to_owned())
+        );
+
+        assert!(messages
+            .check(&MessageSpec::QuickReplies(vec![
+                "Pay duties".to_owned(),
+                "Track a package".to_owned()
+            ]))
+            .is_ok());
+        assert!(messages
+            .check(&MessageSpec::QuickReplies(vec![
+                "Commercial invoice".to_owned()
+            ]))
+            .unwrap_err()
+            .starts_with("Quick replies missing: Commercial invoice."));
+
+        let mut card = CardSpec {
+            title: Some("Tracking".to_owned()),
+            subtitle: None,
+            text: None,
+            image_uri: None,
+            buttons: vec!["Details".to_owned()],
+        };
+        assert!(messages.check(&MessageSpec::Card(card.clone())).is_ok());
+        card.buttons = vec!["Get rate".to_owned()];
+        assert!(messages
+            .check(&MessageSpec::Card(card))
+            .unwrap_err()
+            .starts_with("No card matching title: Tracking, buttons: Get rate."));
+    }
+}
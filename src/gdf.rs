@@ -14,7 +14,7 @@ struct Claims {
     iat: u64,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GDFCredentials {
     pub r#type: String,
     pub project_id: String,
@@ -28,7 +28,7 @@ pub struct GDFCredentials {
     pub client_x509_cert_url: String,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GoogleApisOauthToken {
     pub access_token: String,
     pub token_type: String,
@@ -62,9 +62,9 @@ fn str_to_encoding_key(priv_key_str: String) -> Result<EncodingKey> {
 }
 
 #[allow(dead_code)]
-fn pem_to_decoding_key<'a>(file_bytes: &'a Vec<u8>) -> Result<DecodingKey<'a>> {
+fn pem_to_decoding_key<'a>(file_bytes: &'a [u8]) -> Result<DecodingKey<'a>> {
     // DecodingKey::from_rsa_pem(&file_bytes[..]).ok()
-    let key = DecodingKey::from_rsa_pem(&file_bytes[..])?;
+    let key = DecodingKey::from_rsa_pem(file_bytes)?;
     Ok(key)
 }
 
@@ -209,13 +209,10 @@ mod tests {
         let decoded_token = decode::<Claims>(&token, &dec_key, &Validation::new(Algorithm::RS256));
         match decoded_token {
             Err(err) => match err.kind() {
-                ErrorKind::InvalidSignature => assert!(true),
-                _ => assert!(
-                    false,
-                    "expected InvalidSignature error, got different error instead"
-                ),
+                ErrorKind::InvalidSignature => {}
+                _ => panic!("expected InvalidSignature error, got different error instead"),
             },
-            _ => assert!(false, "expected InvalidSignature error, got result instead"),
+            _ => panic!("expected InvalidSignature error, got result instead"),
         }
 
         // using correct public key we should be able to decode the token and examine claims values
@@ -0,0 +1,458 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::fs::{self, OpenOptions};
+use std::io::{ErrorKind as IOErrorKind, Write};
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+
+use crate::errors::{new_error_from, ErrorKind, Result};
+use crate::yaml_parser::{Test, TestResult};
+
+// run history is stored in directory, every run is one JSON Lines file (one executed test per line)
+// named run_<milliseconds since epoch>.jsonl, i.e. lexical order of files is order of runs.
+// Line is stored test extended with name of its suite, several suites can share one history directory
+const RUN_FILE_PREFIX: &str = "run_";
+const RUN_FILE_SUFFIX: &str = ".jsonl";
+
+// only name and outcome of executed test are kept in memory, history can contain many runs
+#[derive(Debug, Clone, PartialEq)]
+pub struct HistoryTestResult {
+    pub suite_name: String,
+    pub name: String,
+    pub passed: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct HistoryRun {
+    pub timestamp: u64, // milliseconds since epoch
+    pub tests: Vec<HistoryTestResult>,
+}
+
+impl HistoryRun {
+    // not executed tests are skipped
+    pub fn new(suite_name: &str, tests: &[Test]) -> Self {
+        HistoryRun {
+            timestamp: now_millis(),
+            tests: tests
+                .iter()
+                .filter_map(|test| history_test_result(suite_name, &test.name, &test.test_result))
+                .collect(),
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct StoredTestRef<'a> {
+    suite: &'a str,
+    #[serde(flatten)]
+    test: &'a Test,
+}
+
+// fields of stored test needed by history, the rest of the line is ignored.
+// Runs stored before suite name was added belong to suite with empty name
+#[derive(Deserialize)]
+struct StoredTest {
+    #[serde(default)]
+    suite: String,
+    name: String,
+    test_result: Option<TestResult>,
+}
+
+fn history_test_result(
+    suite_name: &str,
+    name: &str,
+    test_result: &Option<TestResult>,
+) -> Option<HistoryTestResult> {
+    let passed = match test_result {
+        Some(TestResult::Ok) => true,
+        Some(TestResult::Ko) => false,
+        None => return None,
+    };
+    Some(HistoryTestResult {
+        suite_name: suite_name.to_owned(),
+        name: name.to_owned(),
+        passed,
+    })
+}
+
+fn now_millis() -> u64 {
+    // clock set before 1970 is not worth proper error
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+fn run_timestamp(file_name: &str) -> Option<u64> {
+    if file_name.starts_with(RUN_FILE_PREFIX) && file_name.ends_with(RUN_FILE_SUFFIX) {
+        file_name[RUN_FILE_PREFIX.len()..file_name.len() - RUN_FILE_SUFFIX.len()]
+            .parse::<u64>()
+            .ok()
+    } else {
+        None
+    }
+}
+
+// appends executed tests (not executed tests are skipped) as new run, returns path of the run file
+pub fn append_run(history_dir: &Path, suite_name: &str, tests: &[Test]) -> Result<PathBuf> {
+    fs::create_dir_all(history_dir)?;
+
+    // two runs finished in the same millisecond must not overwrite each other
+    let mut timestamp = now_millis();
+    let (run_path, mut file) = loop {
+        let run_path = history_dir.join(format!(
+            "{}{}{}",
+            RUN_FILE_PREFIX, timestamp, RUN_FILE_SUFFIX
+        ));
+        match OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(&run_path)
+        {
+            Ok(file) => break (run_path, file),
+            Err(err) if err.kind() == IOErrorKind::AlreadyExists => timestamp += 1,
+            Err(err) => return Err(err.into()),
+        }
+    };
+
+    for test in tests.iter().filter(|test| test.test_result.is_some()) {
+        let stored_test = StoredTestRef {
+            suite: suite_name,
+            test,
+        };
+        writeln!(file, "{}", serde_json::to_string(&stored_test)?)?;
+    }
+    Ok(run_path)
+}
+
+// run files stored in history directory with their timestamps, oldest first.
+// Missing directory means empty history
+fn run_files(history_dir: &Path) -> Result<Vec<(u64, PathBuf)>> {
+    if !history_dir.exists() {
+        return Ok(vec![]);
+    }
+
+    let mut run_files = vec![];
+    for entry in fs::read_dir(history_dir)? {
+        let run_path = entry?.path();
+        let timestamp = run_path
+            .file_name()
+            .and_then(|file_name| file_name.to_str())
+            .and_then(run_timestamp);
+        // other files are not runs
+        if let Some(timestamp) = timestamp {
+            run_files.push((timestamp, run_path));
+        }
+    }
+
+    run_files.sort_by_key(|(timestamp, _)| *timestamp);
+    Ok(run_files)
+}
+
+// number of runs stored in history directory
+pub fn run_count(history_dir: &Path) -> Result<usize> {
+    Ok(run_files(history_dir)?.len())
+}
+
+// last_runs most recent runs stored in history directory, oldest first, older runs are not read
+pub fn load_runs(history_dir: &Path, last_runs: usize) -> Result<Vec<HistoryRun>> {
+    let run_files = run_files(history_dir)?;
+    let skipped_runs = run_files.len().saturating_sub(last_runs);
+
+    let mut runs = vec![];
+    for (timestamp, run_path) in run_files.into_iter().skip(skipped_runs) {
+        let mut tests = vec![];
+        for (line_idx, line) in fs::read_to_string(&run_path)?.lines().enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            let test = serde_json::from_str::<StoredTest>(line).map_err(|err| {
+                new_error_from(ErrorKind::GenericError(format!(
+                    "Invalid history file {}, line {}: {}",
+                    run_path.display(),
+                    line_idx + 1,
+                    err
+                )))
+            })?;
+            tests.extend(history_test_result(
+                &test.suite,
+                &test.name,
+                &test.test_result,
+            ));
+        }
+        runs.push(HistoryRun { timestamp, tests });
+    }
+
+    Ok(runs)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Trend {
+    Improving,
+    Degrading,
+    Stable,
+}
+
+impl fmt::Display for Trend {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Trend::Improving => write!(f, "improving"),
+            Trend::Degrading => write!(f, "degrading"),
+            Trend::Stable => write!(f, "stable"),
+        }
+    }
+}
+
+// results of one test across runs (oldest first), true means passed
+#[derive(Debug, PartialEq)]
+pub struct TestHistory {
+    pub suite_name: String,
+    pub test_name: String,
+    pub results: Vec<bool>,
+}
+
+impl TestHistory {
+    pub fn passed(&self) -> usize {
+        self.results.iter().filter(|passed| **passed).count()
+    }
+
+    pub fn pass_rate(&self) -> f64 {
+        if self.results.is_empty() {
+            return 0.0;
+        }
+        self.passed() as f64 / self.results.len() as f64
+    }
+
+    // test both passed and failed, i.e. outcome depends on something else than the test itself
+    pub fn is_flaky(&self) -> bool {
+        self.passed() > 0 && self.passed() < self.results.len()
+    }
+
+    // pass rate of newer half of the runs compared to older half
+    pub fn trend(&self) -> Trend {
+        let (older, newer) = self.results.split_at(self.results.len() / 2);
+        if older.is_empty() {
+            return Trend::Stable;
+        }
+        let older_rate = older.iter().filter(|passed| **passed).count() as f64 / older.len() as f64;
+        let newer_rate = newer.iter().filter(|passed| **passed).count() as f64 / newer.len() as f64;
+        if newer_rate > older_rate {
+            Trend::Improving
+        } else if newer_rate < older_rate {
+            Trend::Degrading
+        } else {
+            Trend::Stable
+        }
+    }
+
+    // one character per run, full block for passed run, low block for failed one
+    pub fn sparkline(&self) -> String {
+        self.results
+            .iter()
+            .map(|passed| if *passed { '█' } else { '▁' })
+            .collect()
+    }
+}
+
+// history of every test executed within last_runs runs, tests are identified by suite name
+// and test name and listed in order of their first occurrence
+pub fn test_histories(runs: &[HistoryRun], last_runs: usize) -> Vec<TestHistory> {
+    let mut histories: Vec<TestHistory> = vec![];
+    let mut history_idx: HashMap<(String, String), usize> = HashMap::new();
+
+    let skipped_runs = runs.len().saturating_sub(last_runs);
+    for run in runs.iter().skip(skipped_runs) {
+        for test in &run.tests {
+            let key = (test.suite_name.clone(), test.name.clone());
+            let idx = *history_idx.entry(key).or_insert_with(|| {
+                histories.push(TestHistory {
+                    suite_name: test.suite_name.clone(),
+                    test_name: test.name.clone(),
+                    results: vec![],
+                });
+                histories.len() - 1
+            });
+            histories[idx].results.push(test.passed);
+        }
+    }
+
+    histories
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(results: Vec<(&str, Option<TestResult>)>) -> Vec<Test> {
+        results
+            .into_iter()
+            .map(|(name, test_result)| {
+                let mut test = Test::new(name.to_owned(), None, "en".to_owned());
+                test.test_result = test_result;
+                test
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_append_and_load_runs() -> Result<()> {
+        // unique directory, tests run in parallel (possibly in several processes)
+        let history_dir = std::env::temp_dir().join(format!(
+            "gdf_testing_history_{}_{}",
+            std::process::id(),
+            now_millis()
+        ));
+        assert_eq!(load_runs(&history_dir, 10)?.len(), 0);
+
+        append_run(
+            &history_dir,
+            "Sample",
+            &run(vec![("Hello", Some(TestResult::Ok)), ("Bye", None)]),
+        )?;
+        append_run(
+            &history_dir,
+            "Sample",
+            &run(vec![("Hello", Some(TestResult::Ko))]),
+        )?;
+        fs::write(history_dir.join("notes.txt"), "not a run")?;
+
+        assert_eq!(run_count(&history_dir)?, 2);
+        let runs = load_runs(&history_dir, 10)?;
+        assert_eq!(runs.len(), 2);
+        assert!(runs[0].timestamp < runs[1].timestamp);
+        // not executed tests are not stored
+        assert_eq!(
+            runs[0].tests,
+            vec![HistoryTestResult {
+                suite_name: "Sample".to_owned(),
+                name: "Hello".to_owned(),
+                passed: true
+            }]
+        );
+        assert_eq!(
+            test_histories(&runs, 10),
+            vec![TestHistory {
+                suite_name: "Sample".to_owned(),
+                test_name: "Hello".to_owned(),
+                results: vec![true, false]
+            }]
+        );
+
+        // only the most recent run is read
+        let runs = load_runs(&history_dir, 1)?;
+        assert_eq!(runs.len(), 1);
+        assert!(!runs[0].tests[0].passed);
+
+        fs::remove_dir_all(&history_dir)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_test_histories() {
+        let runs: Vec<HistoryRun> = [
+            run(vec![
+                ("Hello", Some(TestResult::Ko)),
+                ("Bye", Some(TestResult::Ok)),
+            ]),
+            run(vec![
+                ("Hello", Some(TestResult::Ko)),
+                ("Bye", Some(TestResult::Ok)),
+            ]),
+            run(vec![
+                ("Hello", Some(TestResult::Ok)),
+                ("Bye", Some(TestResult::Ok)),
+            ]),
+            run(vec![
+                ("Track", Some(TestResult::Ko)),
+                ("Hello", Some(TestResult::Ok)),
+            ]),
+        ]
+        .iter()
+        .map(|tests| HistoryRun::new("Sample", tests))
+        .collect();
+
+        let histories = test_histories(&runs, 10);
+        let names: Vec<&str> = histories
+            .iter()
+            .map(|history| history.test_name.as_str())
+            .collect();
+        assert_eq!(names, vec!["Hello", "Bye", "Track"]);
+
+        let hello = &histories[0];
+        assert_eq!(hello.sparkline(), "▁▁██");
+        assert_eq!(hello.pass_rate(), 0.5);
+        assert!(hello.is_flaky());
+        assert_eq!(hello.trend(), Trend::Improving);
+
+        let bye = &histories[1];
+        assert_eq!(bye.pass_rate(), 1.0);
+        assert!(!bye.is_flaky());
+        assert_eq!(bye.trend(), Trend::Stable);
+
+        // only last two runs
+        let histories = test_histories(&runs, 2);
+        assert_eq!(histories[0].test_name, "Hello");
+        assert_eq!(histories[0].results, vec![true, true]);
+        assert_eq!(histories[1].test_name, "Bye");
+        assert_eq!(histories[1].results, vec![true]);
+        assert_eq!(histories[2].trend(), Trend::Stable);
+    }
+
+    #[test]
+    fn test_test_histories_of_several_suites() -> Result<()> {
+        let history_dir = std::env::temp_dir().join(format!(
+            "gdf_testing_history_suites_{}_{}",
+            std::process::id(),
+            now_millis()
+        ));
+
+        // run stored before suite name was added to the lines
+        fs::create_dir_all(&history_dir)?;
+        fs::write(
+            history_dir.join("run_1.jsonl"),
+            r#"{"name":"Welcome","test_result":"Ko"}"#,
+        )?;
+        append_run(
+            &history_dir,
+            "Bank",
+            &run(vec![("Welcome", Some(TestResult::Ok))]),
+        )?;
+        append_run(
+            &history_dir,
+            "Travel",
+            &run(vec![("Welcome", Some(TestResult::Ko))]),
+        )?;
+        append_run(
+            &history_dir,
+            "Bank",
+            &run(vec![("Welcome", Some(TestResult::Ok))]),
+        )?;
+
+        // tests with the same name in different suites are not merged
+        let histories = test_histories(&load_runs(&history_dir, 10)?, 10);
+        let keys: Vec<(&str, &str, &Vec<bool>)> = histories
+            .iter()
+            .map(|history| {
+                (
+                    history.suite_name.as_str(),
+                    history.test_name.as_str(),
+                    &history.results,
+                )
+            })
+            .collect();
+        assert_eq!(
+            keys,
+            vec![
+                ("", "Welcome", &vec![false]),
+                ("Bank", "Welcome", &vec![true, true]),
+                ("Travel", "Welcome", &vec![false]),
+            ]
+        );
+        assert!(histories.iter().all(|history| !history.is_flaky()));
+
+        fs::remove_dir_all(&history_dir)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,266 @@
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::borrow::Borrow;
+use std::convert::TryFrom;
+use std::fmt;
+
+// botRespondsWith/botDoesNotRespondWith rules are either exact intent names,
+// prefixes (prefix: Tracking|CS|) or regular expressions (re: ^Tracking\|CS\|\d+)
+pub const REGEX_RULE_PREFIX: &str = "re:";
+pub const PREFIX_RULE_PREFIX: &str = "prefix:";
+
+#[derive(Clone)]
+enum RuleMatcher {
+    Exact,
+    Prefix(String),
+    Regex(Regex),
+}
+
+// intent rule compiled when test suite is loaded, i.e. regular expression is compiled once.
+// Serialized as its source so that tests loaded from json report compile the rule again
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct IntentRule {
+    source: String,
+    matcher: RuleMatcher,
+}
+
+impl IntentRule {
+    pub fn compile(rule: &str) -> std::result::Result<IntentRule, String> {
+        let matcher = if let Some(pattern) = rule.strip_prefix(REGEX_RULE_PREFIX) {
+            let regex = Regex::new(pattern.trim())
+                .map_err(|err| format!("Invalid regular expression '{}'. Error: {}", rule, err))?;
+            RuleMatcher::Regex(regex)
+        } else if let Some(prefix) = rule.strip_prefix(PREFIX_RULE_PREFIX) {
+            RuleMatcher::Prefix(prefix.trim().to_owned())
+        } else {
+            RuleMatcher::Exact
+        };
+        Ok(IntentRule {
+            source: rule.to_owned(),
+            matcher,
+        })
+    }
+
+    // rule matching exactly given intent name (no re:/prefix: parsing)
+    pub fn intent_name(intent_name: &str) -> IntentRule {
+        IntentRule {
+            source: intent_name.to_owned(),
+            matcher: RuleMatcher::Exact,
+        }
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.source
+    }
+
+    pub fn matches(&self, intent_name: &str) -> bool {
+        match &self.matcher {
+            RuleMatcher::Exact => self.source == intent_name,
+            RuleMatcher::Prefix(prefix) => intent_name.starts_with(prefix.as_str()),
+            RuleMatcher::Regex(regex) => regex.is_match(intent_name),
+        }
+    }
+}
+
+impl PartialEq for IntentRule {
+    fn eq(&self, other: &IntentRule) -> bool {
+        self.source == other.source
+    }
+}
+
+impl PartialEq<&str> for IntentRule {
+    fn eq(&self, other: &&str) -> bool {
+        self.source == *other
+    }
+}
+
+impl Borrow<str> for IntentRule {
+    fn borrow(&self) -> &str {
+        &self.source
+    }
+}
+
+impl fmt::Debug for IntentRule {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?}", self.source)
+    }
+}
+
+impl fmt::Display for IntentRule {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.source)
+    }
+}
+
+impl TryFrom<String> for IntentRule {
+    type Error = String;
+
+    fn try_from(rule: String) -> std::result::Result<IntentRule, String> {
+        IntentRule::compile(&rule)
+    }
+}
+
+impl From<IntentRule> for String {
+    fn from(rule: IntentRule) -> String {
+        rule.source
+    }
+}
+
+pub fn matches_any(rules: &[IntentRule], intent_name: &str) -> bool {
+    rules.iter().any(|rule| rule.matches(intent_name))
+}
+
+// empty list of expected rules means that any intent is accepted (unless it is forbidden)
+pub fn check_intent_name(
+    expected: &[IntentRule],
+    forbidden: &[IntentRule],
+    intent_name: &str,
+) -> std::result::Result<(), String> {
+    if !expected.is_empty() && !matches_any(expected, intent_name) {
+        return Err(format!(
+            "Wrong intent name received. Expected one of: '{}', got: '{}'",
+            expected.join(","),
+            intent_name
+        ));
+    }
+
+    if let Some(rule) = forbidden.iter().find(|rule| rule.matches(intent_name)) {
+        return Err(format!(
+            "Forbidden intent name received. Got: '{}', which matches botDoesNotRespondWith rule: '{}'",
+            intent_name, rule
+        ));
+    }
+
+    Ok(())
+}
+
+// minConfidence and isFallback checks, confidence missing in response is unknown, i.e. it does not
+// satisfy minConfidence. Missing isFallback flag is treated as false. Intent name is None when
+// no intent was detected (allowed if assertion specifies botDoesNotRespondWith only)
+pub fn check_confidence(
+    min_confidence: Option<f64>,
+    is_fallback: Option<bool>,
+    confidence: Option<f64>,
+    fallback: bool,
+    intent_name: Option<&str>,
+) -> std::result::Result<(), String> {
+    let intent = match intent_name {
+        Some(intent_name) => format!("intent: '{}'", intent_name),
+        None => "no intent detected".to_owned(),
+    };
+
+    if let Some(min_confidence) = min_confidence {
+        match confidence {
+            Some(confidence) if confidence >= min_confidence => {}
+            Some(confidence) => {
+                return Err(format!(
+                    "Intent detection confidence too low. Expected at least: {}, got: {} ({})",
+                    min_confidence, confidence, intent
+                ))
+            }
+            None => {
+                return Err(format!(
+                    "Intent detection confidence missing in response. Expected at least: {} ({})",
+                    min_confidence, intent
+                ))
+            }
+        }
+    }
+
+    if let Some(is_fallback) = is_fallback {
+        if fallback != is_fallback {
+            return Err(format!(
+                "Fallback flag mismatch. Expected isFallback: {}, got: {} ({})",
+                is_fallback, fallback, intent
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rules(rules: Vec<&str>) -> Vec<IntentRule> {
+        rules
+            .into_iter()
+            .map(|rule| IntentRule::compile(rule).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_check_intent_name() {
+        let expected = rules(vec![
+            "Generic|BIT|0|Welcome|Gen",
+            "prefix:Tracking|CS|",
+            r"re:^Rates\|CS\|\d+\|.*Found",
+        ]);
+        let forbidden = rules(vec!["re:(?i)fallback", "Tracking|CS|9|Error|Gen"]);
+
+        assert_eq!(
+            check_intent_name(&expected, &forbidden, "Generic|BIT|0|Welcome|Gen"),
+            Ok(())
+        );
+        assert_eq!(
+            check_intent_name(&expected, &forbidden, "Tracking|CS|4|Found OK|Gen"),
+            Ok(())
+        );
+        assert_eq!(
+            check_intent_name(&expected, &forbidden, "Rates|CS|2|Not Found|Gen"),
+            Ok(())
+        );
+        assert_eq!(
+            check_intent_name(&expected, &forbidden, "Rates|CS|x|Found|Gen"),
+            Err(r"Wrong intent name received. Expected one of: 'Generic|BIT|0|Welcome|Gen,prefix:Tracking|CS|,re:^Rates\|CS\|\d+\|.*Found', got: 'Rates|CS|x|Found|Gen'".to_owned())
+        );
+        assert_eq!(
+            check_intent_name(&expected, &forbidden, "Tracking|CS|9|Error|Gen"),
+            Err("Forbidden intent name received. Got: 'Tracking|CS|9|Error|Gen', which matches botDoesNotRespondWith rule: 'Tracking|CS|9|Error|Gen'".to_owned())
+        );
+        assert_eq!(
+            check_intent_name(&[], &forbidden, "Default Fallback Intent"),
+            Err("Forbidden intent name received. Got: 'Default Fallback Intent', which matches botDoesNotRespondWith rule: 're:(?i)fallback'".to_owned())
+        );
+        assert!(IntentRule::compile("re:(unclosed").is_err());
+        // exact intent name is not parsed as rule
+        assert!(IntentRule::intent_name("re:(unclosed").matches("re:(unclosed"));
+        // rule is serialized as its source and compiled again when deserialized
+        let rule: IntentRule =
+            serde_json::from_str(&serde_json::to_string(&expected[2]).unwrap()).unwrap();
+        assert!(rule.matches("Rates|CS|2|Not Found|Gen"));
+        assert!(serde_json::from_str::<IntentRule>(r#""re:(unclosed""#).is_err());
+    }
+
+    #[test]
+    fn test_check_confidence() {
+        let intent = Some("Tracking|CS|3|ID valid|Gen");
+        assert_eq!(
+            check_confidence(None, None, Some(0.1), false, intent),
+            Ok(())
+        );
+        assert_eq!(check_confidence(None, None, None, false, intent), Ok(()));
+        assert_eq!(
+            check_confidence(Some(0.8), Some(false), Some(0.8), false, intent),
+            Ok(())
+        );
+        assert_eq!(
+            check_confidence(Some(0.8), None, None, false, intent),
+            Err("Intent detection confidence missing in response. Expected at least: 0.8 (intent: 'Tracking|CS|3|ID valid|Gen')".to_owned())
+        );
+        assert_eq!(
+            check_confidence(Some(0.8), None, Some(0.65), false, intent),
+            Err("Intent detection confidence too low. Expected at least: 0.8, got: 0.65 (intent: 'Tracking|CS|3|ID valid|Gen')".to_owned())
+        );
+        assert_eq!(
+            check_confidence(None, Some(false), Some(1.0), true, Some("Default Fallback Intent")),
+            Err("Fallback flag mismatch. Expected isFallback: false, got: true (intent: 'Default Fallback Intent')".to_owned())
+        );
+        assert_eq!(
+            check_confidence(Some(0.8), None, Some(0.3), false, None),
+            Err("Intent detection confidence too low. Expected at least: 0.8, got: 0.3 (no intent detected)".to_owned())
+        );
+    }
+}
@@ -1,11 +1,13 @@
 use crate::errors::Result;
 use assert_json_diff::assert_json_eq_no_panic;
 use jmespath;
-use jmespath::Variable;
+use jmespath::{Rcvar, Variable};
 use serde_json::from_str;
 use serde_json::json;
+use serde_json::Value;
+use std::borrow::Cow;
 use std::fmt;
-use std::rc::Rc;
+use std::sync::Arc;
 type StdResult<T, E> = std::result::Result<T, E>;
 
 // JMESPath types.
@@ -40,59 +42,104 @@ impl fmt::Display for JmespathType {
     }
 }
 
+// JMESPath expression compiled once when test suite is loaded. jmespath is built with sync feature,
+// i.e. compiled expressions can be shared by test executors running in thread pool
+#[derive(Clone)]
+pub struct JmespathExpression(Arc<jmespath::Expression<'static>>);
+
+impl JmespathExpression {
+    pub fn compile(expression: &str) -> Result<JmespathExpression> {
+        Ok(JmespathExpression(Arc::new(jmespath::compile(expression)?)))
+    }
+
+    pub fn as_str(&self) -> &str {
+        self.0.as_str()
+    }
+}
+
+impl PartialEq for JmespathExpression {
+    fn eq(&self, other: &JmespathExpression) -> bool {
+        self.as_str() == other.as_str()
+    }
+}
+
+impl fmt::Debug for JmespathExpression {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "JmespathExpression({})", self.as_str())
+    }
+}
+
+// response is parsed once when parser is created, i.e. one parser should be shared
+// by all checks evaluated against the same response
 pub struct JsonParser<'a> {
-    json: &'a str,
+    json: Cow<'a, str>,
+    data: StdResult<Rcvar, String>, // parsing error is reported by every search
 }
 
 impl<'a> JsonParser<'a> {
     pub fn new(json: &'a str) -> Self {
-        JsonParser { json }
+        JsonParser {
+            json: Cow::Borrowed(json),
+            data: Variable::from_json(json).map(Rcvar::new),
+        }
+    }
+
+    // parser owning the response, i.e. it can be passed around together with the response
+    pub fn from_string(json: String) -> JsonParser<'static> {
+        let data = Variable::from_json(&json).map(Rcvar::new);
+        JsonParser {
+            json: Cow::Owned(json),
+            data,
+        }
+    }
+
+    pub fn json(&self) -> &str {
+        &self.json
+    }
+
+    pub fn into_json(self) -> String {
+        self.json.into_owned()
+    }
+
+    // ad hoc search, expression is compiled on every call
+    pub fn search(&self, expression: &str) -> Result<Rcvar> {
+        self.search_expression(&JmespathExpression::compile(expression)?)
     }
 
-    pub fn search(&self, expression: &str) -> Result<Rc<Variable>> {
-        let jmespath_expr = jmespath::compile(expression)?;
-        let data = jmespath::Variable::from_json(&self.json)?;
-        let rc_var = jmespath_expr.search(data)?;
+    pub fn search_expression(&self, expression: &JmespathExpression) -> Result<Rcvar> {
+        let data = self.data.as_ref().map_err(|err| err.to_owned())?;
+        let rc_var = expression.0.search(data.clone())?;
         Ok(rc_var)
     }
 
-    pub fn extract_as_string(variable: &'a Rc<Variable>) -> Option<&'a str> {
+    pub fn extract_as_string(variable: &'a Rcvar) -> Option<&'a str> {
         match variable.as_string() {
             Some(str_value) => Some(str_value),
             _ => None,
         }
     }
 
-    pub fn extract_as_number(variable: &'a Rc<Variable>) -> Option<f64> {
-        match variable.as_number() {
-            Some(number_value) => Some(number_value),
-            _ => None,
-        }
+    pub fn extract_as_number(variable: &'a Rcvar) -> Option<f64> {
+        variable.as_number()
     }
 
-    pub fn extract_as_bool(variable: &'a Rc<Variable>) -> Option<bool> {
-        match variable.as_boolean() {
-            Some(bool_value) => Some(bool_value),
-            _ => None,
-        }
+    pub fn extract_as_bool(variable: &'a Rcvar) -> Option<bool> {
+        variable.as_boolean()
     }
 
-    pub fn extract_as_array(variable: &'a Rc<Variable>) -> Option<Vec<Rc<Variable>>> {
-        match variable.as_array() {
-            Some(array_value) => Some(array_value.to_vec()),
-            _ => None,
-        }
+    pub fn extract_as_array(variable: &'a Rcvar) -> Option<Vec<Rcvar>> {
+        variable.as_array().map(|array_value| array_value.to_vec())
     }
 
-    pub fn extract_as_object(variable: &'a Rc<Variable>) -> Option<Rc<Variable>> {
-        if variable.is_object() == true {
+    pub fn extract_as_object(variable: &'a Rcvar) -> Option<Rcvar> {
+        if variable.is_object() {
             Some(variable.clone())
         } else {
             None
         }
     }
 
-    pub fn get_jmespath_var_type(variable: &'a Rc<Variable>) -> Option<JmespathType> {
+    pub fn get_jmespath_var_type(variable: &'a Rcvar) -> Option<JmespathType> {
         if variable.is_null() {
             return Some(JmespathType::Null);
         }
@@ -123,7 +170,7 @@ impl<'a> JsonParser<'a> {
         None
     }
 
-    pub fn compare_object_with_str(obj: &Rc<Variable>, str_val: &str) -> Result<String> {
+    pub fn compare_object_with_str(obj: &Rcvar, str_val: &str) -> Result<String> {
         let json_comparison_result = assert_json_eq_no_panic(&json!(obj), &from_str(str_val)?);
 
         match json_comparison_result {
@@ -132,7 +179,7 @@ impl<'a> JsonParser<'a> {
         }
     }
 
-    pub fn compare_array_with_str(arr: &Vec<Rc<Variable>>, str_val: &str) -> Result<String> {
+    pub fn compare_array_with_str(arr: &Vec<Rcvar>, str_val: &str) -> Result<String> {
         let json_comparison_result = assert_json_eq_no_panic(&json!(arr), &from_str(str_val)?);
 
         match json_comparison_result {
@@ -140,6 +187,185 @@ impl<'a> JsonParser<'a> {
             Err(err_msg) => Ok(err_msg), // TBD: probably define custom error here, this is cumbersome
         }
     }
+
+    // checks that expected json is structurally contained in retrieved value (see json_includes)
+    // returns list of differences, empty list means expected json is included
+    pub fn compare_includes_with_str(
+        variable: &Rcvar,
+        str_val: &str,
+        unordered_arrays: bool,
+    ) -> Result<Vec<String>> {
+        let expected: Value = from_str(str_val)?;
+        let actual = json!(variable);
+        let mut differences = vec![];
+        json_includes(&actual, &expected, unordered_arrays, "", &mut differences);
+        Ok(differences)
+    }
+}
+
+fn json_type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+fn json_includes_path(path: &str) -> &str {
+    if path.is_empty() {
+        "<root>"
+    } else {
+        path
+    }
+}
+
+// subset comparison of json values. objects in actual value can contain additional keys.
+// arrays are matched by position (actual array can be longer) or, when unordered_arrays is set,
+// every expected element must be included in different element of actual array
+// all found differences are collected together with their paths
+pub fn json_includes(
+    actual: &Value,
+    expected: &Value,
+    unordered_arrays: bool,
+    path: &str,
+    differences: &mut Vec<String>,
+) {
+    match (actual, expected) {
+        (Value::Object(actual_map), Value::Object(expected_map)) => {
+            for (key, expected_value) in expected_map {
+                let key_path = format!("{}.{}", path, key);
+                match actual_map.get(key) {
+                    Some(actual_value) => json_includes(
+                        actual_value,
+                        expected_value,
+                        unordered_arrays,
+                        &key_path,
+                        differences,
+                    ),
+                    None => differences.push(format!("{}: missing", key_path)),
+                }
+            }
+        }
+        (Value::Array(actual_arr), Value::Array(expected_arr)) if unordered_arrays => {
+            let mut used = vec![false; actual_arr.len()];
+            if !match_unordered(actual_arr, expected_arr, &mut used, 0) {
+                // report expected elements which have no counterpart at all
+                let mut reported = false;
+                for (idx, expected_value) in expected_arr.iter().enumerate() {
+                    let found = actual_arr.iter().any(|actual_value| {
+                        let mut element_differences = vec![];
+                        json_includes(
+                            actual_value,
+                            expected_value,
+                            true,
+                            "",
+                            &mut element_differences,
+                        );
+                        element_differences.is_empty()
+                    });
+                    if !found {
+                        reported = true;
+                        differences.push(format!(
+                            "{}[{}]: no matching element found for {}",
+                            path, idx, expected_value
+                        ));
+                    }
+                }
+                if !reported {
+                    differences.push(format!(
+                        "{}: expected elements cannot be matched to distinct elements of actual array (actual length {}, expected length {})",
+                        json_includes_path(path), actual_arr.len(), expected_arr.len()
+                    ));
+                }
+            }
+        }
+        (Value::Array(actual_arr), Value::Array(expected_arr)) => {
+            for (idx, expected_value) in expected_arr.iter().enumerate() {
+                let idx_path = format!("{}[{}]", path, idx);
+                match actual_arr.get(idx) {
+                    Some(actual_value) => json_includes(
+                        actual_value,
+                        expected_value,
+                        unordered_arrays,
+                        &idx_path,
+                        differences,
+                    ),
+                    None => differences.push(format!(
+                        "{}: missing (actual array has {} elements)",
+                        idx_path,
+                        actual_arr.len()
+                    )),
+                }
+            }
+        }
+        (Value::Number(actual_num), Value::Number(expected_num)) => {
+            if actual_num.as_f64() != expected_num.as_f64() {
+                differences.push(format!(
+                    "{}: expected {}, actual {}",
+                    json_includes_path(path),
+                    expected_num,
+                    actual_num
+                ));
+            }
+        }
+        _ => {
+            if json_type_name(actual) != json_type_name(expected) {
+                differences.push(format!(
+                    "{}: expected {} {}, actual {} {}",
+                    json_includes_path(path),
+                    json_type_name(expected),
+                    expected,
+                    json_type_name(actual),
+                    actual
+                ));
+            } else if actual != expected {
+                differences.push(format!(
+                    "{}: expected {}, actual {}",
+                    json_includes_path(path),
+                    expected,
+                    actual
+                ));
+            }
+        }
+    }
+}
+
+// backtracking assignment of expected elements to distinct actual elements
+fn match_unordered(
+    actual_arr: &Vec<Value>,
+    expected_arr: &Vec<Value>,
+    used: &mut Vec<bool>,
+    expected_idx: usize,
+) -> bool {
+    if expected_idx == expected_arr.len() {
+        return true;
+    }
+
+    for (idx, actual_value) in actual_arr.iter().enumerate() {
+        if used[idx] {
+            continue;
+        }
+        let mut element_differences = vec![];
+        json_includes(
+            actual_value,
+            &expected_arr[expected_idx],
+            true,
+            "",
+            &mut element_differences,
+        );
+        if element_differences.is_empty() {
+            used[idx] = true;
+            if match_unordered(actual_arr, expected_arr, used, expected_idx + 1) {
+                return true;
+            }
+            used[idx] = false;
+        }
+    }
+
+    false
 }
 
 #[cfg(test)]
@@ -286,7 +512,7 @@ mod tests {
 
         match value {
             Some(val) => assert!(val.contains("nage deli")),
-            _ => assert!(false, r#"value should contain "nage deli""#),
+            _ => panic!(r#"value should contain "nage deli""#),
         }
     }
 
@@ -336,7 +562,7 @@ mod tests {
         if let Some(_value_real) = value_real {
             assert_json_eq!(json!(_value_real), value_expected);
         } else {
-            assert!(false, "unexpected value returned")
+            panic!("unexpected value returned")
         }
     }
 
@@ -356,7 +582,7 @@ mod tests {
         if let Some(_value_real) = value_real {
             assert_json_eq!(json!(_value_real), from_str(value_expected).unwrap());
         } else {
-            assert!(false, "unexpected value returned")
+            panic!("unexpected value returned")
         }
     }
 
@@ -378,7 +604,7 @@ mod tests {
             assert_eq!(result, "__OK__");
             assert_json_eq!(json!(_value_real), from_str(value_expected).unwrap());
         } else {
-            assert!(false, "unexpected value returned")
+            panic!("unexpected value returned")
         }
     }
 
@@ -422,7 +648,7 @@ mod tests {
         if let Some(_value_real) = value_real {
             assert_json_eq!(json!(_value_real), from_str(value_expected).unwrap());
         } else {
-            assert!(false, "unexpected value returned")
+            panic!("unexpected value returned")
         }
     }
 
@@ -442,7 +668,7 @@ mod tests {
             assert_json_eq!(json!(_value_real), from_str(value_expected).unwrap());
             assert_eq!(_value_real.len(), 1);
         } else {
-            assert!(false, "unexpected value returned")
+            panic!("unexpected value returned")
         }
     }
 
@@ -464,10 +690,100 @@ mod tests {
             assert_eq!(result, "__OK__");
             assert_eq!(_value_real.len(), 1);
         } else {
-            assert!(false, "unexpected value returned")
+            panic!("unexpected value returned")
         }
     }
 
+    #[test]
+    fn test_shared_parser() -> Result<()> {
+        let parser =
+            JsonParser::new(r#"{"queryResult": {"action": "track", "parameters": {"id": 1}}}"#);
+        let action = parser.search("queryResult.action")?;
+        let id = parser.search("queryResult.parameters.id")?;
+        assert_eq!(JsonParser::extract_as_string(&action), Some("track"));
+        assert_eq!(JsonParser::extract_as_number(&id), Some(1.0));
+
+        let action_expression = JmespathExpression::compile("queryResult.action")?;
+        let action = parser.search_expression(&action_expression)?;
+        assert_eq!(JsonParser::extract_as_string(&action), Some("track"));
+        assert!(JmespathExpression::compile("queryResult..action").is_err());
+        assert!(JsonParser::new("{").search("queryResult").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_json_includes() {
+        let actual = json!({
+            "action": "track",
+            "parameters": {"tracking_id": "1234567890", "country": "DE", "count": 2},
+            "items": [{"name": "a", "size": 1}, {"name": "b", "size": 2}]
+        });
+
+        let mut differences = vec![];
+        json_includes(
+            &actual,
+            &json!({"parameters": {"tracking_id": "1234567890", "count": 2.0}, "items": [{"name": "a"}]}),
+            false,
+            "",
+            &mut differences,
+        );
+        assert_eq!(differences.len(), 0);
+
+        differences = vec![];
+        json_includes(
+            &actual,
+            &json!({"parameters": {"tracking_id": "1", "zip": "53113"}, "items": [{"name": "b"}, {}, {}]}),
+            false,
+            "",
+            &mut differences,
+        );
+        assert_eq!(
+            differences,
+            vec![
+                ".items[0].name: expected \"b\", actual \"a\"",
+                ".items[2]: missing (actual array has 2 elements)",
+                ".parameters.tracking_id: expected \"1\", actual \"1234567890\"",
+                ".parameters.zip: missing",
+            ]
+        );
+
+        // unordered arrays
+        differences = vec![];
+        json_includes(
+            &actual,
+            &json!({"items": [{"name": "b"}, {"size": 1}]}),
+            true,
+            "",
+            &mut differences,
+        );
+        assert_eq!(differences.len(), 0);
+
+        differences = vec![];
+        json_includes(
+            &actual,
+            &json!({"items": [{"name": "b"}, {"name": "c"}]}),
+            true,
+            "",
+            &mut differences,
+        );
+        assert_eq!(
+            differences,
+            vec![".items[1]: no matching element found for {\"name\":\"c\"}"]
+        );
+
+        // both expected elements match only the same actual element
+        differences = vec![];
+        json_includes(
+            &actual,
+            &json!({"items": [{"name": "a"}, {"size": 1}]}),
+            true,
+            "",
+            &mut differences,
+        );
+        assert_eq!(differences.len(), 1);
+        assert!(differences[0].starts_with(".items: expected elements cannot be matched"));
+    }
+
     #[test]
     fn test_get_jmespath_var_type() {
         let mut parser = JsonParser::new(JSON);
@@ -530,10 +846,7 @@ mod tests {
         let search_result = parser.search("queryResult.outputContexts[0]");
 
         match search_result {
-            Ok(_) => assert!(
-                false,
-                "unexpected value returned by get_jmespath_var_type, expected error!"
-            ),
+            Ok(_) => panic!("unexpected value returned by get_jmespath_var_type, expected error!"),
             Err(err) => match *err.kind {
                 ErrorKind::GenericError(err_msg) => {
                     assert!(
@@ -542,7 +855,7 @@ mod tests {
                         "wrong error message retrieved"
                     );
                 }
-                _ => assert!(false, "Expected generic error"),
+                _ => panic!("Expected generic error"),
             },
         }
     }
@@ -0,0 +1,878 @@
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+use std::convert::TryFrom;
+use std::fmt;
+use std::fs;
+use std::path::Path;
+use std::sync::Arc;
+
+use crate::errors::{new_error, Error, ErrorKind, Result};
+
+// Validator for JSON Schema (draft 7) used by schema operator.
+// Supported keywords: type, enum, const, properties, required, additionalProperties,
+// patternProperties, minProperties, maxProperties, items (single schema or tuple), additionalItems,
+// minItems, maxItems, uniqueItems, minLength, maxLength, pattern, minimum, maximum,
+// exclusiveMinimum, exclusiveMaximum, multipleOf, allOf, anyOf, oneOf, not
+// and local $ref (#/definitions/..., #/$defs/...). Schemas using other validation keywords
+// are rejected when test suite is loaded, annotations (title, description etc.) are ignored.
+const UNSUPPORTED_KEYWORDS: &[&str] = &[
+    "contains",
+    "if",
+    "then",
+    "else",
+    "propertyNames",
+    "dependencies",
+    "dependentRequired",
+    "dependentSchemas",
+    "format",
+    "contentEncoding",
+    "contentMediaType",
+    "unevaluatedProperties",
+    "unevaluatedItems",
+];
+
+fn schema_error(message: String) -> Error {
+    new_error(
+        ErrorKind::InvalidTestAssertionResponseCheckEvaluation,
+        message,
+        None,
+    )
+}
+
+// schema compiled when test suite is loaded, i.e. schema file is read and patterns are compiled once.
+// Serialized as its source (inline schema or path to schema file) so that tests loaded
+// from json report compile the schema again
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct JsonSchema {
+    source: String,
+    root: Arc<Value>,
+    patterns: Arc<HashMap<String, Regex>>,
+}
+
+impl JsonSchema {
+    // schema check value is either inline schema (json object) or path to schema file
+    pub fn compile(source: &str) -> Result<JsonSchema> {
+        JsonSchema::compile_in_dir(source, Path::new(""))
+    }
+
+    // relative path to schema file is resolved against base_dir (directory of suite file),
+    // resolved path is kept as source so that schema of tests loaded from json report is found as well
+    pub fn compile_in_dir(source: &str, base_dir: &Path) -> Result<JsonSchema> {
+        let source = if is_inline(source) {
+            source.to_owned()
+        } else {
+            base_dir.join(source.trim()).to_string_lossy().to_string()
+        };
+        let source = source.as_str();
+        let root = load_schema(source)?;
+        let mut patterns = HashMap::new();
+        compile_node(&root, &root, &mut patterns).map_err(|err| {
+            schema_error(format!("Invalid json schema '{}'. {}", source.trim(), err))
+        })?;
+
+        Ok(JsonSchema {
+            source: source.to_owned(),
+            root: Arc::new(root),
+            patterns: Arc::new(patterns),
+        })
+    }
+
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
+    // returns list of violations (empty if instance is valid), every violation is prefixed by
+    // instance path (JSON pointer) of offending node
+    pub fn validate(&self, instance: &Value) -> Vec<String> {
+        let mut violations = vec![];
+        validate_node(self, &self.root, instance, "", &mut violations);
+        violations
+    }
+
+    fn regex(&self, pattern: &str) -> &Regex {
+        // all patterns are compiled by JsonSchema::compile
+        &self.patterns[pattern]
+    }
+}
+
+impl PartialEq for JsonSchema {
+    fn eq(&self, other: &JsonSchema) -> bool {
+        self.source == other.source
+    }
+}
+
+impl fmt::Debug for JsonSchema {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "JsonSchema({})", self.source)
+    }
+}
+
+impl fmt::Display for JsonSchema {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.source)
+    }
+}
+
+impl TryFrom<String> for JsonSchema {
+    type Error = Error;
+
+    fn try_from(source: String) -> Result<JsonSchema> {
+        JsonSchema::compile(&source)
+    }
+}
+
+impl From<JsonSchema> for String {
+    fn from(schema: JsonSchema) -> String {
+        schema.source
+    }
+}
+
+fn is_inline(source: &str) -> bool {
+    source.trim_start().starts_with('{')
+}
+
+fn load_schema(value: &str) -> Result<Value> {
+    let schema_str = if is_inline(value) {
+        value.to_owned()
+    } else {
+        fs::read_to_string(value.trim()).map_err(|err| {
+            schema_error(format!(
+                "Unable to read json schema file '{}'. Error: {}",
+                value, err
+            ))
+        })?
+    };
+
+    let schema: Value = serde_json::from_str(&schema_str).map_err(|err| {
+        schema_error(format!(
+            "Invalid json schema '{}'. Error: {}",
+            value.trim(),
+            err
+        ))
+    })?;
+
+    match schema {
+        Value::Object(_) | Value::Bool(_) => Ok(schema),
+        _ => Err(schema_error(format!(
+            "Invalid json schema '{}'. Schema must be json object",
+            value.trim()
+        ))),
+    }
+}
+
+fn compile_pattern(
+    pattern: &str,
+    patterns: &mut HashMap<String, Regex>,
+) -> std::result::Result<(), String> {
+    if !patterns.contains_key(pattern) {
+        let regex =
+            Regex::new(pattern).map_err(|err| format!("Invalid pattern '{}': {}", pattern, err))?;
+        patterns.insert(pattern.to_owned(), regex);
+    }
+    Ok(())
+}
+
+// checks keywords and references of (sub)schema and compiles its patterns
+fn compile_node(
+    root: &Value,
+    schema: &Value,
+    patterns: &mut HashMap<String, Regex>,
+) -> std::result::Result<(), String> {
+    let node = schema;
+    let schema = match schema {
+        Value::Object(schema) => schema,
+        _ => return Ok(()),
+    };
+
+    for keyword in UNSUPPORTED_KEYWORDS {
+        if schema.contains_key(*keyword) {
+            return Err(format!("Unsupported keyword '{}'", keyword));
+        }
+    }
+
+    if let Some(reference) = schema.get("$ref") {
+        match reference.as_str() {
+            Some(reference) if resolve_ref(root, reference).is_some() => {
+                check_ref_cycle(root, node, &mut vec![])?
+            }
+            Some(reference) if !reference.starts_with('#') => {
+                return Err(format!(
+                    "Unsupported reference '{}', only local references (#/...) are supported",
+                    reference
+                ))
+            }
+            _ => return Err(format!("Unable to resolve schema reference {}", reference)),
+        }
+    }
+
+    match schema.get("pattern") {
+        Some(Value::String(pattern)) => compile_pattern(pattern, patterns)?,
+        Some(_) => return Err("Keyword 'pattern' must be string".to_string()),
+        None => {}
+    }
+
+    for keyword in &["properties", "patternProperties", "definitions", "$defs"] {
+        if let Some(Value::Object(sub_schemas)) = schema.get(*keyword) {
+            for (key, sub_schema) in sub_schemas {
+                if *keyword == "patternProperties" {
+                    compile_pattern(key, patterns)?;
+                }
+                compile_node(root, sub_schema, patterns)?;
+            }
+        }
+    }
+
+    for keyword in &["allOf", "anyOf", "oneOf"] {
+        if let Some(Value::Array(sub_schemas)) = schema.get(*keyword) {
+            for sub_schema in sub_schemas {
+                compile_node(root, sub_schema, patterns)?;
+            }
+        }
+    }
+
+    match schema.get("items") {
+        Some(Value::Array(sub_schemas)) => {
+            for sub_schema in sub_schemas {
+                compile_node(root, sub_schema, patterns)?;
+            }
+        }
+        Some(sub_schema) => compile_node(root, sub_schema, patterns)?,
+        None => {}
+    }
+
+    for keyword in &["additionalItems", "additionalProperties", "not"] {
+        if let Some(sub_schema) = schema.get(*keyword) {
+            compile_node(root, sub_schema, patterns)?;
+        }
+    }
+    Ok(())
+}
+
+// reference resolving (directly or via allOf/anyOf/oneOf/not) to itself without descending
+// into properties or items of the value would never stop when validating
+fn check_ref_cycle<'a>(
+    root: &'a Value,
+    schema: &'a Value,
+    refs: &mut Vec<&'a str>,
+) -> std::result::Result<(), String> {
+    let schema = match schema {
+        Value::Object(schema) => schema,
+        _ => return Ok(()),
+    };
+
+    if let Some(Value::String(reference)) = schema.get("$ref") {
+        if refs.contains(&reference.as_str()) {
+            return Err(format!(
+                "Schema reference '{}' refers to itself without descending into the value",
+                reference
+            ));
+        }
+        // unresolved references are reported by compile_node
+        if let Some(ref_schema) = resolve_ref(root, reference) {
+            refs.push(reference);
+            check_ref_cycle(root, ref_schema, refs)?;
+            refs.pop();
+        }
+        // in draft 7 all other keywords next to $ref are ignored
+        return Ok(());
+    }
+
+    for keyword in &["allOf", "anyOf", "oneOf"] {
+        if let Some(Value::Array(sub_schemas)) = schema.get(*keyword) {
+            for sub_schema in sub_schemas {
+                check_ref_cycle(root, sub_schema, refs)?;
+            }
+        }
+    }
+    if let Some(sub_schema) = schema.get("not") {
+        check_ref_cycle(root, sub_schema, refs)?;
+    }
+    Ok(())
+}
+
+fn display_path(path: &str) -> &str {
+    if path.is_empty() {
+        "<root>"
+    } else {
+        path
+    }
+}
+
+fn child_path(path: &str, segment: &str) -> String {
+    // JSON pointer escaping
+    format!("{}/{}", path, segment.replace("~", "~0").replace("/", "~1"))
+}
+
+fn type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(num) if num.is_i64() || num.is_u64() => "integer",
+        Value::Number(num) if num.as_f64().map(|n| n.fract() == 0.0) == Some(true) => "integer",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+fn has_type(value: &Value, expected_type: &str) -> bool {
+    let real_type = type_name(value);
+    real_type == expected_type || (expected_type == "number" && real_type == "integer")
+}
+
+fn resolve_ref<'a>(root: &'a Value, reference: &str) -> Option<&'a Value> {
+    if reference == "#" {
+        return Some(root);
+    }
+    if !reference.starts_with("#/") {
+        return None;
+    }
+    let pointer = &reference[1..];
+    root.pointer(pointer)
+}
+
+fn is_valid(root: &JsonSchema, schema: &Value, instance: &Value) -> bool {
+    let mut violations = vec![];
+    validate_node(root, schema, instance, "", &mut violations);
+    violations.is_empty()
+}
+
+fn validate_node(
+    root: &JsonSchema,
+    schema: &Value,
+    instance: &Value,
+    path: &str,
+    violations: &mut Vec<String>,
+) {
+    let schema = match schema {
+        Value::Bool(true) => return,
+        Value::Bool(false) => {
+            violations.push(format!(
+                "{}: value not allowed by schema",
+                display_path(path)
+            ));
+            return;
+        }
+        Value::Object(schema) => schema,
+        _ => return,
+    };
+
+    if let Some(Value::String(reference)) = schema.get("$ref") {
+        match resolve_ref(&root.root, reference) {
+            Some(ref_schema) => validate_node(root, ref_schema, instance, path, violations),
+            None => violations.push(format!(
+                "{}: unable to resolve schema reference '{}'",
+                display_path(path),
+                reference
+            )),
+        }
+        // in draft 7 all other keywords next to $ref are ignored
+        return;
+    }
+
+    validate_type(schema, instance, path, violations);
+    validate_enum_const(schema, instance, path, violations);
+    validate_combinators(root, schema, instance, path, violations);
+
+    match instance {
+        Value::Object(obj) => validate_object(root, schema, obj, path, violations),
+        Value::Array(arr) => validate_array(root, schema, arr, path, violations),
+        Value::String(str_val) => validate_string(root, schema, str_val, path, violations),
+        Value::Number(_) => validate_number(schema, instance, path, violations),
+        _ => {}
+    }
+}
+
+fn validate_type(
+    schema: &Map<String, Value>,
+    instance: &Value,
+    path: &str,
+    violations: &mut Vec<String>,
+) {
+    let types: Vec<&str> = match schema.get("type") {
+        Some(Value::String(type_str)) => vec![type_str],
+        Some(Value::Array(type_arr)) => type_arr.iter().filter_map(|t| t.as_str()).collect(),
+        _ => return,
+    };
+
+    if !types.iter().any(|t| has_type(instance, t)) {
+        violations.push(format!(
+            "{}: expected type {}, got {}",
+            display_path(path),
+            types.join(" or "),
+            type_name(instance)
+        ));
+    }
+}
+
+fn validate_enum_const(
+    schema: &Map<String, Value>,
+    instance: &Value,
+    path: &str,
+    violations: &mut Vec<String>,
+) {
+    if let Some(Value::Array(allowed)) = schema.get("enum") {
+        if !allowed.contains(instance) {
+            violations.push(format!(
+                "{}: value {} is not one of {}",
+                display_path(path),
+                instance,
+                Value::Array(allowed.clone())
+            ));
+        }
+    }
+
+    if let Some(expected) = schema.get("const") {
+        if expected != instance {
+            violations.push(format!(
+                "{}: expected constant {}, got {}",
+                display_path(path),
+                expected,
+                instance
+            ));
+        }
+    }
+}
+
+fn validate_combinators(
+    root: &JsonSchema,
+    schema: &Map<String, Value>,
+    instance: &Value,
+    path: &str,
+    violations: &mut Vec<String>,
+) {
+    if let Some(Value::Array(schemas)) = schema.get("allOf") {
+        for sub_schema in schemas {
+            validate_node(root, sub_schema, instance, path, violations);
+        }
+    }
+
+    if let Some(Value::Array(schemas)) = schema.get("anyOf") {
+        if !schemas.iter().any(|s| is_valid(root, s, instance)) {
+            violations.push(format!(
+                "{}: value does not match any of anyOf schemas",
+                display_path(path)
+            ));
+        }
+    }
+
+    if let Some(Value::Array(schemas)) = schema.get("oneOf") {
+        let matching = schemas
+            .iter()
+            .filter(|s| is_valid(root, s, instance))
+            .count();
+        if matching != 1 {
+            violations.push(format!(
+                "{}: value must match exactly one of oneOf schemas, matches {}",
+                display_path(path),
+                matching
+            ));
+        }
+    }
+
+    if let Some(not_schema) = schema.get("not") {
+        if is_valid(root, not_schema, instance) {
+            violations.push(format!(
+                "{}: value must not match schema {}",
+                display_path(path),
+                not_schema
+            ));
+        }
+    }
+}
+
+fn validate_object(
+    root: &JsonSchema,
+    schema: &Map<String, Value>,
+    obj: &Map<String, Value>,
+    path: &str,
+    violations: &mut Vec<String>,
+) {
+    if let Some(Value::Array(required)) = schema.get("required") {
+        for key in required.iter().filter_map(|k| k.as_str()) {
+            if !obj.contains_key(key) {
+                violations.push(format!(
+                    "{}: missing required property '{}'",
+                    display_path(path),
+                    key
+                ));
+            }
+        }
+    }
+
+    if let Some(min) = schema.get("minProperties").and_then(|v| v.as_u64()) {
+        if (obj.len() as u64) < min {
+            violations.push(format!(
+                "{}: expected at least {} properties, got {}",
+                display_path(path),
+                min,
+                obj.len()
+            ));
+        }
+    }
+
+    if let Some(max) = schema.get("maxProperties").and_then(|v| v.as_u64()) {
+        if (obj.len() as u64) > max {
+            violations.push(format!(
+                "{}: expected at most {} properties, got {}",
+                display_path(path),
+                max,
+                obj.len()
+            ));
+        }
+    }
+
+    let properties = schema.get("properties").and_then(|p| p.as_object());
+    let pattern_properties = schema.get("patternProperties").and_then(|p| p.as_object());
+
+    for (key, value) in obj {
+        let key_path = child_path(path, key);
+        let mut matched = false;
+
+        if let Some(prop_schema) = properties.and_then(|p| p.get(key)) {
+            matched = true;
+            validate_node(root, prop_schema, value, &key_path, violations);
+        }
+
+        if let Some(pattern_properties) = pattern_properties {
+            for (pattern, prop_schema) in pattern_properties {
+                if root.regex(pattern).is_match(key) {
+                    matched = true;
+                    validate_node(root, prop_schema, value, &key_path, violations);
+                }
+            }
+        }
+
+        if matched {
+            continue;
+        }
+
+        match schema.get("additionalProperties") {
+            Some(Value::Bool(false)) => {
+                violations.push(format!("{}: additional property not allowed", key_path))
+            }
+            Some(additional_schema) => {
+                validate_node(root, additional_schema, value, &key_path, violations)
+            }
+            None => {}
+        }
+    }
+}
+
+fn validate_array(
+    root: &JsonSchema,
+    schema: &Map<String, Value>,
+    arr: &[Value],
+    path: &str,
+    violations: &mut Vec<String>,
+) {
+    if let Some(min) = schema.get("minItems").and_then(|v| v.as_u64()) {
+        if (arr.len() as u64) < min {
+            violations.push(format!(
+                "{}: expected at least {} items, got {}",
+                display_path(path),
+                min,
+                arr.len()
+            ));
+        }
+    }
+
+    if let Some(max) = schema.get("maxItems").and_then(|v| v.as_u64()) {
+        if (arr.len() as u64) > max {
+            violations.push(format!(
+                "{}: expected at most {} items, got {}",
+                display_path(path),
+                max,
+                arr.len()
+            ));
+        }
+    }
+
+    if let Some(Value::Bool(true)) = schema.get("uniqueItems") {
+        for (idx, item) in arr.iter().enumerate() {
+            if arr[..idx].contains(item) {
+                violations.push(format!(
+                    "{}: duplicate item, items must be unique",
+                    child_path(path, &idx.to_string())
+                ));
+            }
+        }
+    }
+
+    match schema.get("items") {
+        Some(Value::Array(tuple_schemas)) => {
+            for (idx, item) in arr.iter().enumerate() {
+                let item_path = child_path(path, &idx.to_string());
+                match tuple_schemas.get(idx) {
+                    Some(item_schema) => {
+                        validate_node(root, item_schema, item, &item_path, violations)
+                    }
+                    None => match schema.get("additionalItems") {
+                        Some(Value::Bool(false)) => {
+                            violations.push(format!("{}: additional item not allowed", item_path))
+                        }
+                        Some(additional_schema) => {
+                            validate_node(root, additional_schema, item, &item_path, violations)
+                        }
+                        None => {}
+                    },
+                }
+            }
+        }
+        Some(item_schema) => {
+            for (idx, item) in arr.iter().enumerate() {
+                let item_path = child_path(path, &idx.to_string());
+                validate_node(root, item_schema, item, &item_path, violations);
+            }
+        }
+        None => {}
+    }
+}
+
+fn validate_string(
+    root: &JsonSchema,
+    schema: &Map<String, Value>,
+    str_val: &str,
+    path: &str,
+    violations: &mut Vec<String>,
+) {
+    let len = str_val.chars().count() as u64;
+
+    if let Some(min) = schema.get("minLength").and_then(|v| v.as_u64()) {
+        if len < min {
+            violations.push(format!(
+                "{}: expected at least {} characters, got {}",
+                display_path(path),
+                min,
+                len
+            ));
+        }
+    }
+
+    if let Some(max) = schema.get("maxLength").and_then(|v| v.as_u64()) {
+        if len > max {
+            violations.push(format!(
+                "{}: expected at most {} characters, got {}",
+                display_path(path),
+                max,
+                len
+            ));
+        }
+    }
+
+    if let Some(Value::String(pattern)) = schema.get("pattern") {
+        if !root.regex(pattern).is_match(str_val) {
+            violations.push(format!(
+                "{}: value '{}' does not match pattern '{}'",
+                display_path(path),
+                str_val,
+                pattern
+            ));
+        }
+    }
+}
+
+fn validate_number(
+    schema: &Map<String, Value>,
+    instance: &Value,
+    path: &str,
+    violations: &mut Vec<String>,
+) {
+    let num = match instance.as_f64() {
+        Some(num) => num,
+        None => return,
+    };
+    let limit = |keyword: &str| schema.get(keyword).and_then(|v| v.as_f64());
+
+    if let Some(min) = limit("minimum") {
+        if num < min {
+            violations.push(format!(
+                "{}: {} is less than minimum {}",
+                display_path(path),
+                instance,
+                min
+            ));
+        }
+    }
+
+    if let Some(max) = limit("maximum") {
+        if num > max {
+            violations.push(format!(
+                "{}: {} is greater than maximum {}",
+                display_path(path),
+                instance,
+                max
+            ));
+        }
+    }
+
+    if let Some(min) = limit("exclusiveMinimum") {
+        if num <= min {
+            violations.push(format!(
+                "{}: {} must be greater than {}",
+                display_path(path),
+                instance,
+                min
+            ));
+        }
+    }
+
+    if let Some(max) = limit("exclusiveMaximum") {
+        if num >= max {
+            violations.push(format!(
+                "{}: {} must be less than {}",
+                display_path(path),
+                instance,
+                max
+            ));
+        }
+    }
+
+    if let Some(multiple_of) = limit("multipleOf") {
+        if multiple_of > 0.0 && (num / multiple_of).fract() != 0.0 {
+            violations.push(format!(
+                "{}: {} is not multiple of {}",
+                display_path(path),
+                instance,
+                multiple_of
+            ));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_validate() -> Result<()> {
+        let schema = json!({
+            "type": "object",
+            "required": ["richContent"],
+            "properties": {
+                "richContent": {
+                    "type": "array",
+                    "minItems": 1,
+                    "items": {"$ref": "#/definitions/item"}
+                },
+                "locale": {"type": "string", "pattern": "^[a-z]{2}-[A-Z]{2}$"}
+            },
+            "definitions": {
+                "item": {
+                    "type": "object",
+                    "required": ["type"],
+                    "properties": {
+                        "type": {"enum": ["chips", "info"]},
+                        "title": {"type": "string", "maxLength": 10}
+                    },
+                    "additionalProperties": false
+                }
+            }
+        });
+
+        let schema = JsonSchema::compile(&schema.to_string())?;
+
+        let valid = json!({"richContent": [{"type": "chips"}, {"type": "info", "title": "Tracking"}], "locale": "en-US"});
+        assert_eq!(schema.validate(&valid), Vec::<String>::new());
+
+        let invalid = json!({
+            "richContent": [{"type": "button", "title": 42}, {"title": "Very long title", "icon": "x"}],
+            "locale": "english"
+        });
+        assert_eq!(
+            schema.validate(&invalid),
+            vec![
+                "/locale: value 'english' does not match pattern '^[a-z]{2}-[A-Z]{2}$'",
+                "/richContent/0/title: expected type string, got integer",
+                "/richContent/0/type: value \"button\" is not one of [\"chips\",\"info\"]",
+                "/richContent/1: missing required property 'type'",
+                "/richContent/1/icon: additional property not allowed",
+                "/richContent/1/title: expected at most 10 characters, got 15",
+            ]
+        );
+
+        assert_eq!(
+            schema.validate(&json!([])),
+            vec!["<root>: expected type object, got array"]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_compile_schema() {
+        assert!(JsonSchema::compile(r#"{"type": "object"}"#).is_ok());
+        assert!(JsonSchema::compile(r#"{"type": "#).is_err());
+        assert!(JsonSchema::compile("./does/not/exist/schema.json").is_err());
+
+        // unsupported keywords are rejected also in nested schemas
+        let err = JsonSchema::compile(
+            r#"{"properties": {"tags": {"type": "array", "contains": {"const": "x"}}}}"#,
+        )
+        .err()
+        .unwrap();
+        assert!(err.message.ends_with("Unsupported keyword 'contains'"));
+        assert!(JsonSchema::compile(r#"{"type": "string", "format": "email"}"#).is_err());
+        assert!(JsonSchema::compile(r#"{"if": {"type": "string"}}"#).is_err());
+        // keywords are not looked up inside property names and enum values
+        assert!(JsonSchema::compile(
+            r#"{"properties": {"format": {"enum": [{"if": 1}]}}, "title": "Format"}"#
+        )
+        .is_ok());
+
+        let err = JsonSchema::compile(r#"{"$ref": "http://example.com/schema.json"}"#)
+            .err()
+            .unwrap();
+        assert!(err.message.contains("only local references"));
+        assert!(JsonSchema::compile(r##"{"items": {"$ref": "#/definitions/missing"}}"##).is_err());
+        // references which never descend into the value are rejected, recursive schemas are fine
+        let err = JsonSchema::compile(r##"{"$ref": "#"}"##).err().unwrap();
+        assert_eq!(
+            err.message,
+            r##"Invalid json schema '{"$ref": "#"}'. Schema reference '#' refers to itself without descending into the value"##
+        );
+        assert!(JsonSchema::compile(
+            r##"{"definitions": {"a": {"$ref": "#/definitions/a"}}, "properties": {"x": {"$ref": "#/definitions/a"}}}"##
+        )
+        .is_err());
+        assert!(JsonSchema::compile(
+            r##"{"definitions": {"a": {"anyOf": [{"type": "string"}, {"$ref": "#/definitions/b"}]}, "b": {"not": {"$ref": "#/definitions/a"}}}}"##
+        )
+        .is_err());
+        assert!(JsonSchema::compile(
+            r##"{"definitions": {"node": {"properties": {"children": {"items": {"$ref": "#/definitions/node"}}}}}, "$ref": "#/definitions/node"}"##
+        )
+        .is_ok());
+        assert!(JsonSchema::compile(r#"{"pattern": "[a-"}"#).is_err());
+        assert!(JsonSchema::compile(r#"{"patternProperties": {"(": {}}}"#).is_err());
+    }
+
+    #[test]
+    fn test_compile_schema_in_dir() -> Result<()> {
+        let suite_dir = std::env::temp_dir().join("gdf_testing_test_compile_schema_in_dir");
+        fs::create_dir_all(&suite_dir)?;
+        fs::write(suite_dir.join("schema.json"), r#"{"type": "object"}"#)?;
+
+        let schema = JsonSchema::compile_in_dir("./schema.json", &suite_dir)?;
+        assert_eq!(
+            Path::new(schema.source()),
+            suite_dir.join("./schema.json").as_path()
+        );
+        assert_eq!(schema.validate(&json!({})), Vec::<String>::new());
+
+        // resolved path is kept, i.e. schema is found regardless of current directory
+        assert!(JsonSchema::compile(schema.source()).is_ok());
+        assert!(JsonSchema::compile("./schema.json").is_err());
+
+        let inline = r#"{"type": "string"}"#;
+        assert_eq!(
+            JsonSchema::compile_in_dir(inline, &suite_dir)?.source(),
+            inline
+        );
+        Ok(())
+    }
+}
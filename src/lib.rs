@@ -1,9 +1,23 @@
+pub mod agent_importer;
 pub mod cmdl_parser;
 pub mod errors;
+pub mod expectations;
+pub mod fulfillment;
 pub mod gdf;
+pub mod history;
+pub mod intent_matcher;
 pub mod json_parser;
+pub mod json_schema;
+pub mod recorder;
 pub mod result_reporters;
+pub mod runner;
+pub mod snapshots;
 pub mod suite_executor;
+pub mod suite_keys;
+pub mod suite_loader;
+pub mod suite_model;
+pub mod suite_validator;
 pub mod test_executors;
 pub mod thread_pool;
 pub mod yaml_parser;
+pub mod yaml_positions;
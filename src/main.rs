@@ -1,171 +1,530 @@
 use std::fs;
+use std::path::Path;
 use std::process;
-use std::sync::atomic::{AtomicBool, Ordering};
-use std::sync::Arc;
+use std::sync::atomic::Ordering;
 
-use ctrlc;
+use clap::ArgMatches;
 use indicatif::{ProgressBar, ProgressStyle};
-use yaml_rust::{Yaml, YamlLoader};
 
-use gdf_testing::cmdl_parser::{get_cmd_line_parser, get_cmdl_options};
-use gdf_testing::result_reporters::{HtmlResultReporter, JsonResultReporter, StdoutResultReporter};
-use gdf_testing::suite_executor::TestSuiteExecutor;
-use gdf_testing::thread_pool::ThreadPool;
-use gdf_testing::yaml_parser::TestSuite;
+use gdf_testing::agent_importer::{import_agent, AgentImportOptions};
+use gdf_testing::cmdl_parser::{
+    get_cmd_line_parser, get_cmdl_options, get_diff_cmdl_options, get_history_cmdl_options,
+    get_import_agent_cmdl_options, get_record_cmdl_options, get_schema_cmdl_options,
+    get_validate_cmdl_options,
+};
+use gdf_testing::history::{load_runs, run_count, test_histories};
+use gdf_testing::recorder::{
+    generate_suite_yaml, record_tests, tests_from_report, tests_from_transcript, RecordedCheck,
+};
+use gdf_testing::result_reporters::{
+    DiffResultReporter, HistoryResultReporter, ReportDiff, ResultReporterRegistry,
+    StdoutResultReporter,
+};
+use gdf_testing::runner::{Runner, RunnerEvent, RunnerOptions};
+use gdf_testing::suite_loader::{
+    load_suite, load_suite_spec, suite_from_report, SuiteDefinition, SuiteFormat,
+};
+use gdf_testing::suite_model::suite_schema;
+use gdf_testing::suite_validator::validate_suite;
+use gdf_testing::yaml_parser::{Test, TestSuite};
 
 // cargo run -- --suite-file c:/Users/abezecny/adam/WORK/_DEV/Rust/gdf_testing/examples/sample_vap.yaml
 // cargo run -- --suite-file c:/Users/abezecny/adam/WORK/_DEV/Rust/gdf_testing/examples/sample_gdf.yaml
 // cargo run -- --suite-file c:/Users/abezecny/adam/WORK/_DEV/Rust/gdf_testing/examples/sample_vap.yaml --disable-stdout-report --html-report c:/tmp/report.html --json-report c:/tmp/report.json
-fn main() {
-    env_logger::init();
-    let cmd_line_matches = get_cmd_line_parser().get_matches();
-    let cmd_line_opts = get_cmdl_options(&cmd_line_matches);
+// cargo run -- import-agent c:/tmp/agent.zip --sample-ratio 0.5 --holdout-ratio 0.2 --output c:/tmp/imported.yaml --holdout-output c:/tmp/holdout.yaml
+fn run_import_agent(matches: &ArgMatches) {
+    let cmd_line_opts = get_import_agent_cmdl_options(matches);
 
-    let test_suite_path = *cmd_line_opts.test_suite_file;
-    let yaml_str = fs::read_to_string(test_suite_path);
-    if let Err(some_err) = yaml_str {
+    let mut options = AgentImportOptions::new();
+    options.lang = cmd_line_opts.lang;
+    options.sample_ratio = cmd_line_opts.sample_ratio;
+    options.holdout_ratio = cmd_line_opts.holdout_ratio;
+    if let Some(credentials_file) = cmd_line_opts.credentials_file {
+        options.credentials_file = credentials_file;
+    }
+
+    let import_result = import_agent(*cmd_line_opts.agent_zip, &options);
+    if let Err(some_err) = import_result {
         println!(
-            "Error while reading yaml test suite definition file, terminating. Error detail: {}",
+            "Error while importing DialogFlow agent, terminating. Error detail: {}",
             some_err
         );
         process::exit(1);
     }
-    let yaml_str = yaml_str.unwrap();
+    let import_result = import_result.unwrap();
 
-    // read the yaml file from string
-    let docs = YamlLoader::load_from_str(&yaml_str);
-    if let Err(some_err) = docs {
+    match cmd_line_opts.output_path {
+        Some(output_path) => {
+            if let Err(some_err) = fs::write(*output_path, &import_result.suite_yaml) {
+                println!(
+                    "Error while writing test suite file, terminating. Error detail: {}",
+                    some_err
+                );
+                process::exit(1);
+            }
+            println!(
+                "Imported {} intents, generated {} tests, {} training phrases held out.",
+                import_result.intent_count, import_result.test_count, import_result.holdout_count
+            );
+        }
+        None => print!("{}", import_result.suite_yaml),
+    }
+
+    // warnings go to standard error so that suite printed to standard output stays valid yaml
+    if !import_result.skipped_intents.is_empty() {
+        eprintln!(
+            "Warning: all training phrases held out, no tests generated for intents: {}",
+            import_result.skipped_intents.join(", ")
+        );
+    }
+
+    if let Some(holdout_output_path) = cmd_line_opts.holdout_output_path {
+        match &import_result.holdout_suite_yaml {
+            Some(holdout_suite_yaml) => {
+                if let Err(some_err) = fs::write(*holdout_output_path, holdout_suite_yaml) {
+                    println!(
+                        "Error while writing held out test suite file, terminating. Error detail: {}",
+                        some_err
+                    );
+                    process::exit(1);
+                }
+            }
+            None => eprintln!(
+                "Warning: no training phrases held out, {} not written",
+                holdout_output_path.display()
+            ),
+        }
+    }
+}
+
+// cargo run -- record --suite-file c:/tmp/sample_gdf.yaml --from-transcript c:/tmp/transcript.txt --checks action,parameters --output c:/tmp/recorded.yaml
+fn run_record(matches: &ArgMatches) {
+    let cmd_line_opts = get_record_cmdl_options(matches);
+
+    let format = SuiteFormat::from_path(*cmd_line_opts.suite_file);
+    let suite_spec = fs::read_to_string(*cmd_line_opts.suite_file)
+        .map_err(|err| err.into())
+        .and_then(|suite_str| load_suite_spec(&suite_str, format));
+    if let Err(some_err) = suite_spec {
         println!(
-            "Error while loading yaml test suite definition file, terminating. Error detail: {}",
+            "Error while reading suite-spec from test suite definition file, terminating. Error detail: {}",
             some_err
         );
         process::exit(1);
     }
-    let docs: Vec<Yaml> = docs.unwrap();
-    let yaml: &Yaml = &docs[0];
+    let suite_spec = suite_spec.unwrap();
 
-    //parse yaml string and convert it to test suite struct
-    let suite = TestSuite::from_yaml(yaml);
-    if let Err(some_err) = suite {
+    // clap ensures that exactly one of report/transcript is specified
+    let lang = cmd_line_opts.lang;
+    let tests = match (cmd_line_opts.report_path, cmd_line_opts.transcript_path) {
+        (Some(report_path), _) => fs::read_to_string(*report_path)
+            .map_err(|err| err.into())
+            .and_then(|report_json| tests_from_report(&report_json)),
+        (_, Some(transcript_path)) => fs::read_to_string(*transcript_path)
+            .map_err(|err| err.into())
+            .and_then(|transcript| tests_from_transcript(&transcript, &lang)),
+        _ => unreachable!(),
+    };
+    if let Err(some_err) = tests {
         println!(
-            "Error while parsing yaml test suite definition file, terminating. Error detail: {}",
+            "Error while reading conversations to record, terminating. Error detail: {}",
             some_err
         );
         process::exit(1);
     }
-    let suite: TestSuite = suite.unwrap();
+    let tests = tests.unwrap();
+
+    let checks: Vec<RecordedCheck> = cmd_line_opts
+        .checks
+        .iter()
+        .map(|check| match check.as_str() {
+            "action" => RecordedCheck::Action,
+            _ => RecordedCheck::Parameters,
+        })
+        .collect();
 
-    // create test suite executor and underlying test executor jobs
-    let suite_executor = TestSuiteExecutor::new(suite);
-    if let Err(some_err) = suite_executor {
+    println!("Recording {} conversations...", tests.len());
+    let suite = TestSuite {
+        suite_spec: suite_spec.clone(),
+        tests,
+        classification: false,
+    };
+    let recorded_tests = record_tests(suite, cmd_line_opts.threadpool_size);
+    if let Err(some_err) = recorded_tests {
         println!(
-            "Error while initiating the tests, terminating. Error detail: {}",
+            "Error while recording conversations, terminating. Error detail: {}",
             some_err
         );
         process::exit(1);
     }
-    let suite_executor = suite_executor.unwrap();
+    let recorded_tests = recorded_tests.unwrap();
 
-    // initiate thread pool for processing of test executor jobs
-    let running = Arc::new(AtomicBool::new(true));
-    let pool = ThreadPool::new(cmd_line_opts.threadpool_size, running.clone());
+    let recording_result = generate_suite_yaml(&suite_spec, &recorded_tests, &checks);
+    if let Err(some_err) = recording_result {
+        println!(
+            "Error while generating test suite, terminating. Error detail: {}",
+            some_err
+        );
+        process::exit(1);
+    }
+    let recording_result = recording_result.unwrap();
 
-    // initiate prohress bar for displaying execution progress
-    let test_count = suite_executor.test_executors.len();
-    let sty = ProgressStyle::default_bar()
-        .template("[{elapsed_precise}] {bar:70.yellow/red} {pos:>7}/{len:7} {msg}")
-        .progress_chars("##-");
+    for (test_name, error_message) in &recording_result.failed_tests {
+        println!(
+            "Conversation '{}' was not recorded: {}",
+            test_name, error_message
+        );
+    }
 
-    let pb = ProgressBar::new(test_count as u64);
-    pb.set_style(sty);
+    match cmd_line_opts.output_path {
+        Some(output_path) => {
+            if let Err(some_err) = fs::write(*output_path, &recording_result.suite_yaml) {
+                println!(
+                    "Error while writing test suite file, terminating. Error detail: {}",
+                    some_err
+                );
+                process::exit(1);
+            }
+            println!(
+                "Recorded {} conversations, {} failed.",
+                recording_result.recorded_count,
+                recording_result.failed_tests.len()
+            );
+        }
+        None => print!("{}", recording_result.suite_yaml),
+    }
+}
 
-    // setup CTRL+C handler
-    ctrlc::set_handler(move || {
-        println!("CTRL+C pressed. Waiting for running tests to complete. No more new tests will be started.");
-        running.store(false, Ordering::SeqCst);
-    })
-    .expect("Error setting Ctrl-C handler");
+// relative paths in suite file (csv files, snapshot directory, schema files) are relative to suite file
+fn suite_dir(suite_file: &Path) -> &Path {
+    suite_file.parent().unwrap_or(Path::new(""))
+}
 
-    // kick off execution of all test executor jobs by thread pool
-    for mut test_executor in suite_executor.test_executors {
-        pool.execute(move || loop {
-            let assertion_exec_result = test_executor.execute_next_assertion();
-            if let None = assertion_exec_result {
-                break;
+// cargo run -- validate c:/tmp/sample_gdf.yaml c:/tmp/sample_vap.yaml
+fn run_validate(matches: &ArgMatches) {
+    let cmd_line_opts = get_validate_cmdl_options(matches);
+
+    let mut issue_count = 0;
+    for suite_file in cmd_line_opts.suite_files {
+        let file_name = suite_file.display();
+        let format = SuiteFormat::from_path(*suite_file);
+        let issues = match fs::read_to_string(*suite_file) {
+            Ok(suite_str) => validate_suite(&suite_str, format, suite_dir(*suite_file)),
+            Err(some_err) => {
+                println!("{}: error while reading file: {}", file_name, some_err);
+                issue_count += 1;
+                continue;
             }
-        });
+        };
+
+        for issue in &issues {
+            println!("{}:{}", file_name, issue);
+        }
+        if issues.is_empty() {
+            println!("{}: OK", file_name);
+        }
+        issue_count += issues.len();
     }
 
-    // executed test with results will be returned by threadpool
-    // via mpsc channel and gathered in this vector
-    let mut executed_tests = vec![];
+    if issue_count > 0 {
+        println!("Found {} problems.", issue_count);
+        process::exit(1);
+    }
+}
 
-    println!("Running tests...");
-    // by common sense we should start at zero but there is probably some bug
-    // in indicatif library and it works properly only when we set it initually to 1
-    pb.set_position(1);
-    for i in 0..test_count
-    /*lower bound inclusive, upper bound exclusive!*/
-    {
-        let recv_res = suite_executor.rx.recv();
+// cargo run -- schema --output c:/tmp/gdf_testing.schema.json
+fn run_schema(matches: &ArgMatches) {
+    let cmd_line_opts = get_schema_cmdl_options(matches);
 
-        if let Err(_) = recv_res {
-            println!("test results receiving channel broken, terminating.");
-            process::exit(1);
+    // safe to unwrap, schema is plain json value
+    let schema = serde_json::to_string_pretty(&suite_schema()).unwrap();
+    match cmd_line_opts.output_path {
+        Some(output_path) => {
+            if let Err(some_err) = fs::write(*output_path, &schema) {
+                println!(
+                    "Error while writing json schema file, terminating. Error detail: {}",
+                    some_err
+                );
+                process::exit(1);
+            }
+        }
+        None => println!("{}", schema),
+    }
+}
+
+// exit codes of diff subcommand, CI/CD pipelines can tell regressions from broken comparison
+const DIFF_EXIT_REGRESSIONS: i32 = 1;
+const DIFF_EXIT_ERROR: i32 = 2;
+
+// cargo run -- diff c:/tmp/report_old.json c:/tmp/report_new.json --html-report c:/tmp/diff.html
+fn run_diff(matches: &ArgMatches) {
+    let cmd_line_opts = get_diff_cmdl_options(matches);
+
+    let mut reports = vec![];
+    for report_path in [*cmd_line_opts.old_report, *cmd_line_opts.new_report] {
+        let tests = fs::read_to_string(report_path)
+            .map_err(|err| err.to_string())
+            .and_then(|report_json| {
+                serde_json::from_str::<Vec<Test>>(&report_json).map_err(|err| err.to_string())
+            });
+        match tests {
+            Ok(tests) => reports.push(tests),
+            Err(some_err) => {
+                println!(
+                    "Error while reading json report {}, terminating. Error detail: {}",
+                    report_path.display(),
+                    some_err
+                );
+                process::exit(DIFF_EXIT_ERROR);
+            }
         }
+    }
 
-        let executed_test = recv_res;
+    let diff = ReportDiff::from_tests(&reports[0], &reports[1]);
+    if cmd_line_opts.print_to_std_out {
+        DiffResultReporter::print_report_diff(&diff);
+    }
 
-        if let Err(some_err) = executed_test {
+    if let Some(html_path) = cmd_line_opts.html_report_path {
+        if let Err(some_err) = DiffResultReporter::report_diff(&diff, *html_path) {
             println!(
-                "Error while running the tests, terminating. Error detail: {}",
+                "Error while generating html comparison report. Error detail: {}",
                 some_err
             );
-            process::exit(1);
+            process::exit(DIFF_EXIT_ERROR);
         }
+    }
+
+    // newly failing tests are reported by exit code (e.g. for CI/CD pipelines)
+    if diff.has_regressions() {
+        process::exit(DIFF_EXIT_REGRESSIONS);
+    }
+}
 
-        let executed_test = executed_test.unwrap();
-        let test_result_str = StdoutResultReporter::get_test_result_str(&executed_test);
-        pb.println(format!(
-            "{} Finished test {} ({}/{})",
-            test_result_str,
-            executed_test.name,
-            i + 1,
-            test_count
-        ));
-        pb.inc(1);
-        pb.set_message(&format!("Overall progress"));
-        executed_tests.push(executed_test);
-        // std::thread::sleep(std::time::Duration::from_millis(5000)); // just for nice progress bar debugging! remove from final code!
-    }
-    pb.finish_with_message("All tests executed!");
-
-    let print_to_std_out = cmd_line_opts.print_to_std_out;
-    if print_to_std_out == true {
-        println!("");
-        StdoutResultReporter::report_test_results(&executed_tests);
-        println!("");
-    }
-
-    let html_report_path = cmd_line_opts.html_report_path;
-    if let Some(html_path) = html_report_path {
-        let result = HtmlResultReporter::report_test_results(&executed_tests, *html_path);
-        if let Err(some_error) = result {
+// cargo run -- history c:/tmp/history --last 20 --flaky-only
+fn run_history(matches: &ArgMatches) {
+    let cmd_line_opts = get_history_cmdl_options(matches);
+
+    let runs = run_count(*cmd_line_opts.history_dir).and_then(|total_runs| {
+        load_runs(*cmd_line_opts.history_dir, cmd_line_opts.last_runs)
+            .map(|runs| (total_runs, runs))
+    });
+    if let Err(some_err) = runs {
+        println!(
+            "Error while reading run history, terminating. Error detail: {}",
+            some_err
+        );
+        process::exit(1);
+    }
+    let (total_runs, runs) = runs.unwrap();
+
+    let mut histories = test_histories(&runs, cmd_line_opts.last_runs);
+    let flaky_count = histories
+        .iter()
+        .filter(|history| history.is_flaky())
+        .count();
+    if cmd_line_opts.flaky_only {
+        histories.retain(|history| history.is_flaky());
+    }
+
+    if !histories.is_empty() {
+        HistoryResultReporter::print_test_histories(&histories);
+    }
+    println!(
+        "Runs evaluated: {} (of {}), flaky tests: {}",
+        runs.len(),
+        total_runs,
+        flaky_count
+    );
+}
+
+fn main() {
+    env_logger::init();
+    let cmd_line_matches = get_cmd_line_parser().get_matches();
+
+    if let Some(import_agent_matches) = cmd_line_matches.subcommand_matches("import-agent") {
+        run_import_agent(import_agent_matches);
+        return;
+    }
+
+    if let Some(record_matches) = cmd_line_matches.subcommand_matches("record") {
+        run_record(record_matches);
+        return;
+    }
+
+    if let Some(validate_matches) = cmd_line_matches.subcommand_matches("validate") {
+        run_validate(validate_matches);
+        return;
+    }
+
+    if let Some(schema_matches) = cmd_line_matches.subcommand_matches("schema") {
+        run_schema(schema_matches);
+        return;
+    }
+
+    if let Some(diff_matches) = cmd_line_matches.subcommand_matches("diff") {
+        run_diff(diff_matches);
+        return;
+    }
+
+    if let Some(history_matches) = cmd_line_matches.subcommand_matches("history") {
+        run_history(history_matches);
+        return;
+    }
+
+    let cmd_line_opts = get_cmdl_options(&cmd_line_matches);
+
+    let test_suite_path = *cmd_line_opts.test_suite_file;
+    let suite_str = fs::read_to_string(test_suite_path);
+    if let Err(some_err) = suite_str {
+        println!(
+            "Error while reading test suite definition file, terminating. Error detail: {}",
+            some_err
+        );
+        process::exit(1);
+    }
+    let suite_str = suite_str.unwrap();
+
+    //parse suite file and convert it to test suite struct, all problems found are reported at once
+    let format = cmd_line_opts
+        .format
+        .unwrap_or_else(|| SuiteFormat::from_path(test_suite_path));
+    let suite = load_suite(&suite_str, format, suite_dir(test_suite_path));
+    if let Err(some_err) = suite {
+        // message contains one line per problem found
+        println!(
+            "Error while parsing test suite definition file, terminating. Error detail: {}",
+            some_err.message
+        );
+        process::exit(1);
+    }
+    let suite: TestSuite = match suite.unwrap() {
+        SuiteDefinition::Suite(suite) => suite,
+        // json report contains tests only, suite-spec is taken from separate suite file
+        SuiteDefinition::Report(tests) => {
+            let suite_spec_path = match cmd_line_opts.suite_spec_file {
+                Some(suite_spec_path) => *suite_spec_path,
+                None => {
+                    println!("Suite file is json report of previous run, --suite-spec must be specified as well, terminating.");
+                    process::exit(1);
+                }
+            };
+            let suite_spec = fs::read_to_string(suite_spec_path)
+                .map_err(|err| err.into())
+                .and_then(|spec_str| {
+                    load_suite_spec(&spec_str, SuiteFormat::from_path(suite_spec_path))
+                });
+            if let Err(some_err) = suite_spec {
+                println!(
+                    "Error while reading suite-spec, terminating. Error detail: {}",
+                    some_err.message
+                );
+                process::exit(1);
+            }
+            suite_from_report(tests, suite_spec.unwrap())
+        }
+    };
+    let classification_mode = suite.classification;
+
+    let options = RunnerOptions {
+        threadpool_size: cmd_line_opts.threadpool_size,
+        update_snapshots: cmd_line_opts.update_snapshots,
+        repeat: cmd_line_opts.repeat,
+        min_pass_rate: cmd_line_opts.min_pass_rate,
+    };
+
+    // initiate prohress bar for displaying execution progress
+    let sty = ProgressStyle::default_bar()
+        .template("[{elapsed_precise}] {bar:70.yellow/red} {pos:>7}/{len:7} {msg}")
+        .progress_chars("##-");
+
+    // every repetition of the test is shown as finished test
+    let pb = ProgressBar::new((suite.tests.len() * options.repeat) as u64);
+    pb.set_style(sty);
+
+    let mut runner = Runner::new(options);
+    runner.on_progress(|event| match event {
+        RunnerEvent::Started { .. } => {
+            println!("Running tests...");
+            // by common sense we should start at zero but there is probably some bug
+            // in indicatif library and it works properly only when we set it initually to 1
+            pb.set_position(1);
+        }
+        RunnerEvent::TestFinished {
+            test,
+            finished_count,
+            test_count,
+        } => {
+            let test_result_str = StdoutResultReporter::get_test_result_str(test);
+            pb.println(format!(
+                "{} Finished test {} ({}/{})",
+                test_result_str, test.name, finished_count, test_count
+            ));
+            pb.inc(1);
+            pb.set_message("Overall progress");
+        }
+        RunnerEvent::Finished { .. } => pb.finish_with_message("All tests executed!"),
+    });
+
+    // standard output report is followed by reports specified on command line (in this order)
+    let mut reports = cmd_line_opts.reports.clone();
+    if cmd_line_opts.print_to_std_out {
+        let stdout_report = if classification_mode {
+            "classification"
+        } else {
+            "stdout"
+        };
+        reports.insert(0, stdout_report.to_owned());
+    }
+    let mut reporter_registry = ResultReporterRegistry::with_default_reporters();
+    reporter_registry.use_suite_name(&suite.suite_spec.name);
+    // history is read before the run, current run is appended by history reporter
+    if let Some(history_dir) = &cmd_line_opts.history_dir {
+        if let Err(some_err) = reporter_registry.use_history(**history_dir, &suite.suite_spec.name)
+        {
             println!(
-                "Error while generating html report. Error detail: {}",
-                some_error
+                "Error while reading run history, terminating. Error detail: {}",
+                some_err
             );
             process::exit(1);
         }
     }
+    for report in &reports {
+        match reporter_registry.create(report) {
+            Ok(reporter) => runner.add_reporter(reporter),
+            Err(some_err) => {
+                println!(
+                    "Error while initiating report {}, terminating. Error detail: {}",
+                    report, some_err.message
+                );
+                process::exit(1);
+            }
+        }
+    }
+
+    // setup CTRL+C handler
+    let running = runner.running_flag();
+    ctrlc::set_handler(move || {
+        println!("CTRL+C pressed. Waiting for running tests to complete. No more new tests will be started.");
+        running.store(false, Ordering::SeqCst);
+    })
+    .expect("Error setting Ctrl-C handler");
 
-    let json_report_path = cmd_line_opts.json_report_path;
-    if let Some(json_path) = json_report_path {
-        let result = JsonResultReporter::report_test_results(&executed_tests, *json_path);
-        if let Err(some_error) = result {
+    // executed tests are reported by reporters registered above
+    match runner.run(suite) {
+        // every report is generated even if some of them fail
+        Ok(run_result) => {
+            for some_err in &run_result.reporter_errors {
+                println!("Error while generating report. Error detail: {}", some_err);
+            }
+            if !run_result.reporter_errors.is_empty() {
+                process::exit(1);
+            }
+        }
+        Err(some_err) => {
             println!(
-                "Error while generating json report. Error detail: {}",
-                some_error
+                "Error while running the tests, terminating. Error detail: {}",
+                some_err
             );
             process::exit(1);
         }
@@ -0,0 +1,373 @@
+use serde_json::Value;
+use std::sync::atomic::AtomicBool;
+use std::sync::Arc;
+use yaml_rust::yaml::Hash;
+use yaml_rust::{Yaml, YamlEmitter};
+
+use crate::errors::{new_error_from, ErrorKind, Result};
+use crate::suite_executor::TestSuiteExecutor;
+use crate::thread_pool::ThreadPool;
+use crate::yaml_parser::{
+    Test, TestAssertion, TestAssertionResult, TestSuite, TestSuiteSpec, TestSuiteType,
+};
+
+// additional response checks which can be generated from recorded responses
+#[derive(Debug, PartialEq, Clone)]
+pub enum RecordedCheck {
+    Action,
+    Parameters,
+}
+
+pub struct RecordingResult {
+    pub suite_yaml: String,
+    pub recorded_count: usize,
+    pub failed_tests: Vec<(String, String)>, // test name, error message
+}
+
+fn recorder_error(message: String) -> crate::errors::Error {
+    new_error_from(ErrorKind::GenericError(message))
+}
+
+// reads conversations from json report written by JsonResultReporter
+// expected intents, response checks and results of original run are dropped, only utterances are kept
+pub fn tests_from_report(report_json: &str) -> Result<Vec<Test>> {
+    let tests: Vec<Test> = serde_json::from_str(report_json)?;
+    let mut recorded_tests = vec![];
+    for test in tests {
+        let mut recorded_test = Test::new(test.name, test.desc, test.lang);
+        for assertion in test.assertions {
+            recorded_test
+                .assertions
+                .push(TestAssertion::new(assertion.user_says, vec![], vec![]));
+        }
+        recorded_tests.push(recorded_test);
+    }
+    Ok(recorded_tests)
+}
+
+// reads conversations from plain text transcript:
+//   - every line is one user utterance, optional 'User:' prefix is removed
+//   - lines starting with 'Bot:' are ignored (they document bot responses only)
+//   - empty line starts new conversation
+//   - line starting with '#' names the conversation which follows
+pub fn tests_from_transcript(transcript: &str, lang: &str) -> Result<Vec<Test>> {
+    let mut tests = vec![];
+    let mut test_name: Option<String> = None;
+    let mut assertions: Vec<TestAssertion> = vec![];
+
+    let mut finish_conversation =
+        |test_name: &mut Option<String>, assertions: &mut Vec<TestAssertion>| {
+            if !assertions.is_empty() {
+                let name = test_name
+                    .take()
+                    .unwrap_or(format!("Recorded conversation #{}", tests.len() + 1));
+                let mut test = Test::new(name, None, lang.to_owned());
+                test.assertions.append(assertions);
+                tests.push(test);
+            }
+        };
+
+    for line in transcript.lines() {
+        let line = line.trim();
+        if line.is_empty() {
+            finish_conversation(&mut test_name, &mut assertions);
+            continue;
+        }
+        if line.starts_with("#") {
+            finish_conversation(&mut test_name, &mut assertions);
+            test_name = Some(line.trim_start_matches('#').trim().to_owned());
+            continue;
+        }
+        let lowercase_line = line.to_lowercase();
+        if lowercase_line.starts_with("bot:") {
+            continue;
+        }
+        let utterance = if lowercase_line.starts_with("user:") {
+            line["user:".len()..].trim()
+        } else {
+            line
+        };
+        if !utterance.is_empty() {
+            assertions.push(TestAssertion::new(utterance.to_owned(), vec![], vec![]));
+        }
+    }
+    finish_conversation(&mut test_name, &mut assertions);
+
+    if tests.is_empty() {
+        return Err(recorder_error(
+            "No utterances found in transcript".to_string(),
+        ));
+    }
+    Ok(tests)
+}
+
+// runs all conversations against NLP provider of given suite without checking any expectations
+// returned tests contain raw NLP responses as assertion results
+pub fn record_tests(suite: TestSuite, threadpool_size: usize) -> Result<Vec<Test>> {
+    let suite_executor = TestSuiteExecutor::new(suite)?;
+    let test_count = suite_executor.test_executors.len();
+
+    let running = Arc::new(AtomicBool::new(true));
+    let pool = ThreadPool::new(threadpool_size, running);
+
+    for mut test_executor in suite_executor.test_executors {
+        pool.execute(move || loop {
+            if test_executor.record_next_assertion().is_none() {
+                break;
+            }
+        });
+    }
+
+    let mut recorded_tests = vec![];
+    for _ in 0..test_count {
+        let recorded_test = suite_executor
+            .rx
+            .recv()
+            .map_err(|_| recorder_error("test results receiving channel broken".to_string()))?;
+        recorded_tests.push(recorded_test);
+    }
+
+    // threads finish in random order, keep the order of input conversations
+    recorded_tests.sort_by_key(|test| test.execution_id);
+    Ok(recorded_tests)
+}
+
+fn yaml_str(val: &str) -> Yaml {
+    Yaml::String(val.to_owned())
+}
+
+fn response_check_yaml(expression: &str, operator: &str, value: &str) -> Yaml {
+    let mut response_check = Hash::new();
+    response_check.insert(yaml_str("expression"), yaml_str(expression));
+    response_check.insert(yaml_str("operator"), yaml_str(operator));
+    response_check.insert(yaml_str("value"), yaml_str(value));
+    Yaml::Hash(response_check)
+}
+
+// converts recorded NLP response into assertion yaml (botRespondsWith + optional response checks)
+fn assertion_yaml(
+    assertion: &TestAssertion,
+    response: &str,
+    suite_type: &TestSuiteType,
+    checks: &[RecordedCheck],
+) -> Result<Yaml> {
+    let response: Value = serde_json::from_str(response)?;
+    let expression_prefix = match suite_type {
+        TestSuiteType::DialogFlow | TestSuiteType::Custom(_) => "queryResult",
+        TestSuiteType::DHLVAP => "dfResponse.queryResult",
+    };
+    let query_result = match suite_type {
+        TestSuiteType::DialogFlow | TestSuiteType::Custom(_) => &response["queryResult"],
+        TestSuiteType::DHLVAP => &response["dfResponse"]["queryResult"],
+    };
+
+    let intent_name = query_result["intent"]["displayName"]
+        .as_str()
+        .ok_or(recorder_error(format!(
+            "No intent name received for utterance '{}'",
+            assertion.user_says
+        )))?;
+
+    let mut response_checks = vec![];
+    if checks.contains(&RecordedCheck::Action) {
+        if let Some(action) = query_result["action"].as_str() {
+            if !action.is_empty() {
+                response_checks.push(response_check_yaml(
+                    &format!("{}.action", expression_prefix),
+                    "equals",
+                    action,
+                ));
+            }
+        }
+    }
+    if checks.contains(&RecordedCheck::Parameters) {
+        if let Some(parameters) = query_result["parameters"].as_object() {
+            if !parameters.is_empty() {
+                response_checks.push(response_check_yaml(
+                    &format!("{}.parameters", expression_prefix),
+                    "jsonequals",
+                    &serde_json::to_string(&query_result["parameters"])?,
+                ));
+            }
+        }
+    }
+
+    let mut assertion_yaml = Hash::new();
+    assertion_yaml.insert(yaml_str("userSays"), yaml_str(&assertion.user_says));
+    assertion_yaml.insert(yaml_str("botRespondsWith"), yaml_str(intent_name));
+    if !response_checks.is_empty() {
+        assertion_yaml.insert(yaml_str("responseChecks"), Yaml::Array(response_checks));
+    }
+    Ok(Yaml::Hash(assertion_yaml))
+}
+
+fn test_yaml(test: &Test, suite_type: &TestSuiteType, checks: &[RecordedCheck]) -> Result<Yaml> {
+    let mut assertions = vec![];
+    for assertion in &test.assertions {
+        match &assertion.test_assertion_result {
+            Some(TestAssertionResult::Ok(response)) => {
+                assertions.push(assertion_yaml(assertion, response, suite_type, checks)?)
+            }
+            Some(TestAssertionResult::KoIntentNameMismatch(err))
+            | Some(TestAssertionResult::KoConfidenceMismatch(err))
+            | Some(TestAssertionResult::KoResponseCheckError(err, _))
+            | Some(TestAssertionResult::KoBackendError(err)) => {
+                return Err(err.clone());
+            }
+            None => {
+                return Err(recorder_error(format!(
+                    "Utterance '{}' was not executed",
+                    assertion.user_says
+                )))
+            }
+        }
+    }
+
+    let mut test_yaml = Hash::new();
+    test_yaml.insert(yaml_str("name"), yaml_str(&test.name));
+    if let Some(desc) = &test.desc {
+        test_yaml.insert(yaml_str("desc"), yaml_str(desc));
+    }
+    test_yaml.insert(yaml_str("lang"), yaml_str(&test.lang));
+    test_yaml.insert(yaml_str("assertions"), Yaml::Array(assertions));
+    Ok(Yaml::Hash(test_yaml))
+}
+
+// generates test suite yaml from recorded conversations, conversations which failed
+// (e.g. because of NLP provider error or missing intent) are skipped and reported in result
+pub fn generate_suite_yaml(
+    suite_spec: &TestSuiteSpec,
+    recorded_tests: &[Test],
+    checks: &[RecordedCheck],
+) -> Result<RecordingResult> {
+    let mut tests = vec![];
+    let mut failed_tests = vec![];
+    for test in recorded_tests {
+        match test_yaml(test, &suite_spec.suite_type, checks) {
+            Ok(yaml) => tests.push(yaml),
+            Err(err) => failed_tests.push((test.name.clone(), err.message)),
+        }
+    }
+
+    if tests.is_empty() {
+        return Err(recorder_error(
+            "No conversation was recorded successfully".to_string(),
+        ));
+    }
+
+    let recorded_count = tests.len();
+    let mut suite = Hash::new();
+    suite.insert(yaml_str("suite-spec"), suite_spec.to_yaml());
+    suite.insert(yaml_str("tests"), Yaml::Array(tests));
+
+    let mut suite_yaml = String::new();
+    YamlEmitter::new(&mut suite_yaml)
+        .dump(&Yaml::Hash(suite))
+        .map_err(|err| recorder_error(format!("Unable to generate test suite yaml: {:?}", err)))?;
+    suite_yaml.push('\n');
+
+    Ok(RecordingResult {
+        suite_yaml,
+        recorded_count,
+        failed_tests,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::errors::new_service_call_error;
+    use std::collections::HashMap;
+    use yaml_rust::YamlLoader;
+
+    const RESPONSE: &str = r#"{
+        "queryResult": {
+            "action": "express_track",
+            "parameters": {"tracking_id": "1234567891"},
+            "intent": {"displayName": "Tracking|CS|3|ID valid|Gen"}
+        }
+    }"#;
+
+    #[test]
+    fn test_tests_from_transcript() -> Result<()> {
+        const TRANSCRIPT: &str = "
+        # Hello - track
+        User: Hello
+        Bot: Hi, how can I help you?
+        track a package
+
+        it is 1234567891
+        ";
+
+        let tests = tests_from_transcript(TRANSCRIPT, "en")?;
+        assert_eq!(tests.len(), 2);
+        assert_eq!(tests[0].name, "Hello - track");
+        assert_eq!(tests[0].assertions.len(), 2);
+        assert_eq!(tests[0].assertions[0].user_says, "Hello");
+        assert_eq!(tests[0].assertions[1].user_says, "track a package");
+        assert_eq!(tests[1].name, "Recorded conversation #2");
+        assert_eq!(tests[1].assertions[0].user_says, "it is 1234567891");
+        Ok(())
+    }
+
+    #[test]
+    fn test_generate_suite_yaml() -> Result<()> {
+        let mut config = HashMap::new();
+        config.insert("credentials_file".to_owned(), "/path/to/cred".to_owned());
+        let suite_spec = TestSuiteSpec::new(
+            "Recorded suite".to_owned(),
+            TestSuiteType::DialogFlow,
+            config,
+        );
+
+        let mut test_ok = Test::new("Tracking".to_owned(), None, "es".to_owned());
+        let mut assertion = TestAssertion::new("it is 1234567891".to_owned(), vec![], vec![]);
+        assertion.test_assertion_result = Some(TestAssertionResult::Ok(RESPONSE.to_owned()));
+        test_ok.assertions.push(assertion);
+
+        let mut test_ko = Test::new("Broken".to_owned(), None, "en".to_owned());
+        let mut assertion = TestAssertion::new("hello".to_owned(), vec![], vec![]);
+        assertion.test_assertion_result =
+            Some(TestAssertionResult::KoBackendError(new_service_call_error(
+                ErrorKind::GDFInvocationError,
+                "service unavailable".to_owned(),
+                None,
+                None,
+            )));
+        test_ko.assertions.push(assertion);
+
+        let result = generate_suite_yaml(
+            &suite_spec,
+            &[test_ok, test_ko],
+            &[RecordedCheck::Action, RecordedCheck::Parameters],
+        )?;
+        assert_eq!(result.recorded_count, 1);
+        assert_eq!(
+            result.failed_tests,
+            [("Broken".to_owned(), "service unavailable".to_owned())]
+        );
+
+        // generated yaml must be valid test suite
+        let docs = YamlLoader::load_from_str(&result.suite_yaml)?;
+        let suite = TestSuite::from_yaml(&docs[0])?;
+        assert_eq!(suite.tests.len(), 1);
+        assert_eq!(suite.tests[0].lang, "es");
+        let assertion = &suite.tests[0].assertions[0];
+        assert_eq!(assertion.bot_responds_with, ["Tracking|CS|3|ID valid|Gen"]);
+        assert_eq!(assertion.response_checks.len(), 2);
+        assert_eq!(
+            assertion.response_checks[0].expression,
+            "queryResult.action"
+        );
+        assert_eq!(
+            assertion.response_checks[1].expression,
+            "queryResult.parameters"
+        );
+
+        // recorded checks must pass against recorded response
+        for response_check in &assertion.response_checks {
+            TestSuiteExecutor::process_assertion_response_check(response_check, RESPONSE)?;
+        }
+        Ok(())
+    }
+}
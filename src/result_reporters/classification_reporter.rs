@@ -0,0 +1,551 @@
+use ansi_term::Colour::{Green, Red};
+use prettytable::{cell, row}; // macros
+use prettytable::{Cell, Row, Table};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs::File;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use crate::errors::Result;
+use crate::intent_matcher::matches_any;
+use crate::json_parser::JsonParser;
+use crate::result_reporters::{escape_html, html_page, ResultReporter};
+use crate::yaml_parser::{Test, TestAssertion, TestAssertionResult};
+
+// label used in confusion matrix when no intent was detected (e.g. backend call failed)
+const NO_INTENT: &str = "N/A";
+
+// displayed instead of precision, recall or accuracy which are undefined (division by zero)
+const UNDEFINED_RATIO: &str = "-";
+
+const MASTER_CONTAINER: &str = r#"
+        <div class="p-1"><!-- padding 1 -->
+            <h4>Summary</h4>
+            <table class="table table-bordered w-auto">
+            <tbody>
+                <tr><th scope="row">Utterances</th><td>{__total__}</td></tr>
+                <tr><th scope="row">Correctly classified</th><td>{__correct__}</td></tr>
+                <tr><th scope="row">Accuracy</th><td>{__accuracy__}</td></tr>
+                <tr><th scope="row">Without expected intent (not classified)</th><td>{__unlabeled__}</td></tr>
+            </tbody>
+            </table>
+            <h4>Per intent precision / recall</h4>
+            <table class="table table-bordered table-sm">
+            <thead>
+            <tr>
+                <th scope="col">Intent</th>
+                <th scope="col">Precision</th>
+                <th scope="col">Recall</th>
+                <th scope="col">True positives</th>
+                <th scope="col">False positives</th>
+                <th scope="col">False negatives</th>
+            </tr>
+            </thead>
+            <tbody>
+                {__intent_rows__}
+            </tbody>
+            </table>
+            <h4>Confusion matrix (rows: expected intent, columns: detected intent)</h4>
+            <div class="table-responsive">
+            <table class="table table-bordered table-sm">
+            <thead>
+            <tr>
+                <th scope="col"></th>
+                {__matrix_header__}
+            </tr>
+            </thead>
+            <tbody>
+                {__matrix_rows__}
+            </tbody>
+            </table>
+            </div>
+            <h4>Misclassified utterances</h4>
+            <table class="table table-bordered table-sm">
+            <thead>
+            <tr>
+                <th scope="col">Utterance</th>
+                <th scope="col">Expected intent</th>
+                <th scope="col">Detected intent</th>
+            </tr>
+            </thead>
+            <tbody>
+                {__misclassified_rows__}
+            </tbody>
+            </table>
+        </div>
+"#;
+
+const INTENT_ROW: &str = r#"
+    <tr>
+        <td>{__intent__}</td>
+        <td>{__precision__}</td>
+        <td>{__recall__}</td>
+        <td>{__tp__}</td>
+        <td>{__fp__}</td>
+        <td>{__fn__}</td>
+    </tr>
+"#;
+
+const MISCLASSIFIED_ROW: &str = r#"
+    <tr>
+        <td>{__utterance__}</td>
+        <td>{__expected__}</td>
+        <td>{__detected__}</td>
+    </tr>
+"#;
+
+#[derive(Debug, PartialEq)]
+pub struct IntentClassificationStats {
+    pub intent: String,
+    pub true_positives: usize,
+    pub false_positives: usize,
+    pub false_negatives: usize,
+}
+
+impl IntentClassificationStats {
+    // returns None if intent was never detected (precision is undefined)
+    pub fn precision(&self) -> Option<f64> {
+        let detected = self.true_positives + self.false_positives;
+        if detected == 0 {
+            None
+        } else {
+            Some(self.true_positives as f64 / detected as f64)
+        }
+    }
+
+    // returns None if intent was never expected (recall is undefined)
+    pub fn recall(&self) -> Option<f64> {
+        let expected = self.true_positives + self.false_negatives;
+        if expected == 0 {
+            None
+        } else {
+            Some(self.true_positives as f64 / expected as f64)
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Misclassification {
+    pub utterance: String,
+    pub expected: String,
+    pub detected: String,
+}
+
+#[derive(Debug)]
+pub struct ClassificationResults {
+    pub total: usize,
+    pub correct: usize,
+    // executed assertions with botDoesNotRespondWith only, i.e. without expected label.
+    // They are not part of accuracy, per intent stats nor confusion matrix
+    pub unlabeled: usize,
+    pub intents: Vec<IntentClassificationStats>,
+    // expected intent -> detected intent -> count
+    pub confusion_matrix: BTreeMap<String, BTreeMap<String, usize>>,
+    pub misclassified: Vec<Misclassification>,
+}
+
+impl ClassificationResults {
+    // every test is expected to be single turn test generated from classification section of test suite
+    pub fn from_tests(tests: &[Test]) -> ClassificationResults {
+        let mut total = 0;
+        let mut correct = 0;
+        let mut unlabeled = 0;
+        let mut confusion_matrix: BTreeMap<String, BTreeMap<String, usize>> = BTreeMap::new();
+        let mut misclassified = vec![];
+
+        for test in tests {
+            let assertion = match test.assertions.first() {
+                Some(assertion) => assertion,
+                None => continue,
+            };
+
+            if assertion.test_assertion_result.is_none() {
+                continue; // test was not executed at all (e.g. CTRL+C was pressed)
+            }
+
+            let detected = get_detected_intent(assertion).unwrap_or(NO_INTENT.to_owned());
+
+            // when multiple intents are acceptable the detected one is used as expected label
+            // (rules like re:/prefix: are matched against the detected intent as well)
+            let expected = if matches_any(&assertion.bot_responds_with, &detected) {
+                detected.clone()
+            } else if let Some(rule) = assertion.bot_responds_with.first() {
+                rule.to_string()
+            } else {
+                // assertion with botDoesNotRespondWith only has no expected label
+                unlabeled += 1;
+                continue;
+            };
+
+            total += 1;
+            if expected == detected {
+                correct += 1;
+            } else {
+                misclassified.push(Misclassification {
+                    utterance: assertion.user_says.clone(),
+                    expected: expected.clone(),
+                    detected: detected.clone(),
+                });
+            }
+
+            *confusion_matrix
+                .entry(expected)
+                .or_default()
+                .entry(detected)
+                .or_insert(0) += 1;
+        }
+
+        let mut intent_names: BTreeSet<String> = BTreeSet::new();
+        for (expected, detected_map) in &confusion_matrix {
+            intent_names.insert(expected.clone());
+            for detected in detected_map.keys() {
+                intent_names.insert(detected.clone());
+            }
+        }
+        intent_names.remove(NO_INTENT);
+
+        let mut intents = vec![];
+        for intent in intent_names {
+            let mut stats = IntentClassificationStats {
+                intent: intent.clone(),
+                true_positives: 0,
+                false_positives: 0,
+                false_negatives: 0,
+            };
+            for (expected, detected_map) in &confusion_matrix {
+                for (detected, count) in detected_map {
+                    if *expected == intent && *detected == intent {
+                        stats.true_positives += count;
+                    } else if *expected == intent {
+                        stats.false_negatives += count;
+                    } else if *detected == intent {
+                        stats.false_positives += count;
+                    }
+                }
+            }
+            intents.push(stats);
+        }
+
+        ClassificationResults {
+            total,
+            correct,
+            unlabeled,
+            intents,
+            confusion_matrix,
+            misclassified,
+        }
+    }
+
+    pub fn accuracy(&self) -> Option<f64> {
+        if self.total == 0 {
+            None
+        } else {
+            Some(self.correct as f64 / self.total as f64)
+        }
+    }
+
+    // all intents appearing in confusion matrix (either as expected or detected intent)
+    pub fn matrix_labels(&self) -> Vec<String> {
+        let mut labels: BTreeSet<String> = BTreeSet::new();
+        for (expected, detected_map) in &self.confusion_matrix {
+            labels.insert(expected.clone());
+            for detected in detected_map.keys() {
+                labels.insert(detected.clone());
+            }
+        }
+        labels.into_iter().collect()
+    }
+
+    pub fn matrix_value(&self, expected: &str, detected: &str) -> usize {
+        match self.confusion_matrix.get(expected) {
+            Some(detected_map) => *detected_map.get(detected).unwrap_or(&0),
+            None => 0,
+        }
+    }
+}
+
+// intent name is retrieved either from successful response or from backend response attached to intent mismatch error
+// both direct DialogFlow responses and DHL VAP responses (dfResponse wrapper) are supported
+pub(crate) fn get_detected_intent(assertion: &TestAssertion) -> Option<String> {
+    let response = match assertion.test_assertion_result.as_ref()? {
+        TestAssertionResult::Ok(response) => response,
+        TestAssertionResult::KoIntentNameMismatch(err)
+        | TestAssertionResult::KoConfidenceMismatch(err)
+        | TestAssertionResult::KoResponseCheckError(err, _) => err.backend_response.as_ref()?,
+        TestAssertionResult::KoBackendError(_) => return None,
+    };
+
+    let parser = JsonParser::new(response);
+    for expression in &[
+        "queryResult.intent.displayName",
+        "dfResponse.queryResult.intent.displayName",
+    ] {
+        if let Ok(search_result) = parser.search(expression) {
+            if let Some(intent_name) = JsonParser::extract_as_string(&search_result) {
+                return Some(intent_name.to_owned());
+            }
+        }
+    }
+    None
+}
+
+fn format_ratio(ratio: Option<f64>) -> String {
+    match ratio {
+        Some(ratio) => format!("{:.2}%", ratio * 100.0),
+        None => UNDEFINED_RATIO.to_owned(),
+    }
+}
+
+// results are printed to standard output when file path is not specified
+pub struct ClassificationResultReporter {
+    file_path: Option<PathBuf>,
+}
+
+impl ResultReporter for ClassificationResultReporter {
+    fn end(&mut self, tests: &[Test]) -> Result<()> {
+        match &self.file_path {
+            Some(file_path) => ClassificationResultReporter::report_test_results(tests, file_path),
+            None => {
+                println!();
+                ClassificationResultReporter::print_test_results(tests);
+                println!();
+                Ok(())
+            }
+        }
+    }
+}
+
+impl ClassificationResultReporter {
+    pub fn new(file_path: Option<&Path>) -> Self {
+        ClassificationResultReporter {
+            file_path: file_path.map(|file_path| file_path.to_path_buf()),
+        }
+    }
+
+    pub fn print_test_results(tests: &[Test]) {
+        let results = ClassificationResults::from_tests(tests);
+
+        let mut summary_table = Table::new();
+        summary_table.add_row(row!["Utterances", results.total]);
+        summary_table.add_row(row!["Correctly classified", results.correct]);
+        summary_table.add_row(row!["Accuracy", format_ratio(results.accuracy())]);
+        summary_table.add_row(row![
+            "Without expected intent (not classified)",
+            results.unlabeled
+        ]);
+        summary_table.printstd();
+
+        let mut intents_table = Table::new();
+        intents_table.add_row(row![
+            "Intent",
+            "Precision",
+            "Recall",
+            "True positives",
+            "False positives",
+            "False negatives"
+        ]);
+        for stats in &results.intents {
+            intents_table.add_row(row![
+                stats.intent,
+                format_ratio(stats.precision()),
+                format_ratio(stats.recall()),
+                stats.true_positives,
+                stats.false_positives,
+                stats.false_negatives
+            ]);
+        }
+        intents_table.printstd();
+
+        // confusion matrix columns are numbered to keep the table narrow, see legend in first column
+        let labels = results.matrix_labels();
+        let mut matrix_table = Table::new();
+        let mut header = vec![Cell::new("Expected \\ Detected")];
+        for (idx, _) in labels.iter().enumerate() {
+            header.push(Cell::new(&format!("#{}", idx + 1)));
+        }
+        matrix_table.add_row(Row::new(header));
+        for (idx, expected) in labels.iter().enumerate() {
+            let mut cells = vec![Cell::new(&format!("#{} {}", idx + 1, expected))];
+            for detected in &labels {
+                let count = results.matrix_value(expected, detected);
+                let count_str = match count {
+                    0 => String::from("."),
+                    _ if expected == detected => Green.paint(count.to_string()).to_string(),
+                    _ => Red.paint(count.to_string()).to_string(),
+                };
+                cells.push(Cell::new(&count_str));
+            }
+            matrix_table.add_row(Row::new(cells));
+        }
+        matrix_table.printstd();
+
+        if !results.misclassified.is_empty() {
+            let mut misclassified_table = Table::new();
+            misclassified_table.add_row(row!["Utterance", "Expected intent", "Detected intent"]);
+            for misclassification in &results.misclassified {
+                misclassified_table.add_row(row![
+                    misclassification.utterance,
+                    misclassification.expected,
+                    misclassification.detected
+                ]);
+            }
+            misclassified_table.printstd();
+        }
+    }
+
+    pub fn report_test_results(tests: &[Test], file_path: &Path) -> Result<()> {
+        let results = ClassificationResults::from_tests(tests);
+
+        let mut intent_rows: Vec<String> = vec![];
+        for stats in &results.intents {
+            intent_rows.push(
+                INTENT_ROW
+                    .to_string()
+                    .replace("{__intent__}", &escape_html(&stats.intent))
+                    .replace("{__precision__}", &format_ratio(stats.precision()))
+                    .replace("{__recall__}", &format_ratio(stats.recall()))
+                    .replace("{__tp__}", &stats.true_positives.to_string())
+                    .replace("{__fp__}", &stats.false_positives.to_string())
+                    .replace("{__fn__}", &stats.false_negatives.to_string()),
+            );
+        }
+
+        let labels = results.matrix_labels();
+        let matrix_header: Vec<String> = labels
+            .iter()
+            .map(|label| format!("<th scope=\"col\">{}</th>", escape_html(label)))
+            .collect();
+        let mut matrix_rows: Vec<String> = vec![];
+        for expected in &labels {
+            let mut cells = vec![format!("<th scope=\"row\">{}</th>", escape_html(expected))];
+            for detected in &labels {
+                let count = results.matrix_value(expected, detected);
+                let css_class = match count {
+                    0 => "",
+                    _ if expected == detected => "table-success",
+                    _ => "table-danger",
+                };
+                cells.push(format!("<td class=\"{}\">{}</td>", css_class, count));
+            }
+            matrix_rows.push(format!("<tr>{}</tr>", cells.join("")));
+        }
+
+        let mut misclassified_rows: Vec<String> = vec![];
+        for misclassification in &results.misclassified {
+            misclassified_rows.push(
+                MISCLASSIFIED_ROW
+                    .to_string()
+                    .replace(
+                        "{__utterance__}",
+                        &escape_html(&misclassification.utterance),
+                    )
+                    .replace("{__expected__}", &escape_html(&misclassification.expected))
+                    .replace("{__detected__}", &escape_html(&misclassification.detected)),
+            );
+        }
+
+        let report_body = MASTER_CONTAINER
+            .to_string()
+            .replace("{__total__}", &results.total.to_string())
+            .replace("{__correct__}", &results.correct.to_string())
+            .replace("{__accuracy__}", &format_ratio(results.accuracy()))
+            .replace("{__unlabeled__}", &results.unlabeled.to_string())
+            .replace("{__intent_rows__}", &intent_rows.join(""))
+            .replace("{__matrix_header__}", &matrix_header.join(""))
+            .replace("{__matrix_rows__}", &matrix_rows.join(""))
+            .replace("{__misclassified_rows__}", &misclassified_rows.join(""));
+        let html_report = html_page("Intent Classification Report", &report_body);
+
+        let mut file = File::create(file_path)?;
+        file.write_all(html_report.as_bytes())?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::errors::{new_service_call_error, ErrorKind};
+    use crate::intent_matcher::IntentRule;
+
+    fn response(intent_name: &str) -> String {
+        format!(
+            r#"{{"queryResult": {{"intent": {{"displayName": "{}"}}}}}}"#,
+            intent_name
+        )
+    }
+
+    fn classification_test(utterance: &str, expected: &str, detected: &str) -> Test {
+        let mut test = Test::new(utterance.to_owned(), None, "en".to_owned());
+        let mut assertion = TestAssertion::new(
+            utterance.to_owned(),
+            vec![IntentRule::intent_name(expected)],
+            vec![],
+        );
+        if expected == detected {
+            assertion.test_assertion_result = Some(TestAssertionResult::Ok(response(detected)));
+        } else {
+            assertion.test_assertion_result = Some(TestAssertionResult::KoIntentNameMismatch(
+                new_service_call_error(
+                    ErrorKind::InvalidTestAssertionEvaluation,
+                    "Wrong intent name received".to_owned(),
+                    None,
+                    Some(response(detected)),
+                ),
+            ));
+        }
+        test.assertions.push(assertion);
+        test
+    }
+
+    #[test]
+    fn test_classification_results() {
+        let mut tests = vec![
+            classification_test("hi", "Welcome", "Welcome"),
+            classification_test("hello", "Welcome", "Welcome"),
+            classification_test("track", "Tracking", "Tracking"),
+            classification_test("where is my parcel", "Tracking", "Welcome"),
+            classification_test("agent", "Representative", "Tracking"),
+            classification_test("bye", "", "Goodbye"),
+        ];
+        // only forbidden intents are specified, i.e. there is no expected label
+        tests[5].assertions[0].bot_responds_with.clear();
+        tests[5].assertions[0].bot_does_not_respond_with = vec![IntentRule::intent_name("Welcome")];
+
+        let results = ClassificationResults::from_tests(&tests);
+        assert_eq!(results.total, 5);
+        assert_eq!(results.unlabeled, 1);
+        assert_eq!(results.correct, 3);
+        assert_eq!(results.accuracy(), Some(0.6));
+        assert_eq!(
+            results.matrix_labels(),
+            ["Representative", "Tracking", "Welcome"]
+        );
+        assert_eq!(results.matrix_value("Tracking", "Welcome"), 1);
+        assert_eq!(results.matrix_value("Welcome", "Welcome"), 2);
+        assert_eq!(results.matrix_value("Welcome", "Tracking"), 0);
+
+        assert_eq!(
+            results.intents[1],
+            IntentClassificationStats {
+                intent: "Tracking".to_owned(),
+                true_positives: 1,
+                false_positives: 1,
+                false_negatives: 1,
+            }
+        );
+        assert_eq!(results.intents[0].precision(), None);
+        assert_eq!(results.intents[0].recall(), Some(0.0));
+        assert_eq!(results.intents[2].precision(), Some(2.0 / 3.0));
+        assert_eq!(results.intents[2].recall(), Some(1.0));
+
+        assert_eq!(
+            results.misclassified[0],
+            Misclassification {
+                utterance: "where is my parcel".to_owned(),
+                expected: "Tracking".to_owned(),
+                detected: "Welcome".to_owned(),
+            }
+        );
+    }
+}
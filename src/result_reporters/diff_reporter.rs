@@ -0,0 +1,578 @@
+use ansi_term::Colour::{Green, Red, Yellow};
+use prettytable::Table;
+use prettytable::{cell, row}; // macros
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::Write;
+use std::path::Path;
+
+use crate::errors::Result;
+use crate::result_reporters::classification_reporter::get_detected_intent;
+use crate::result_reporters::{escape_html, html_page};
+use crate::yaml_parser::{Test, TestAssertionResult, TestResult};
+
+const MASTER_CONTAINER: &str = r#"
+        <div class="p-1"><!-- padding 1 -->
+            <h4>Summary</h4>
+            <table class="table table-bordered w-auto">
+            <tbody>
+                <tr><th scope="row">Newly failing tests</th><td>{__newly_failing__}</td></tr>
+                <tr><th scope="row">Newly passing tests</th><td>{__newly_passing__}</td></tr>
+                <tr><th scope="row">Intent changes</th><td>{__intent_changes__}</td></tr>
+                <tr><th scope="row">Added tests</th><td>{__added__}</td></tr>
+                <tr><th scope="row">Removed tests</th><td>{__removed__}</td></tr>
+            </tbody>
+            </table>
+            <h4>Newly failing tests</h4>
+            <table class="table table-bordered table-sm">
+            <thead>
+            <tr>
+                <th scope="col">Test</th>
+                <th scope="col">Old result</th>
+                <th scope="col">New result</th>
+                <th scope="col">Error</th>
+            </tr>
+            </thead>
+            <tbody>
+                {__newly_failing_rows__}
+            </tbody>
+            </table>
+            <h4>Newly passing tests</h4>
+            <table class="table table-bordered table-sm">
+            <thead>
+            <tr>
+                <th scope="col">Test</th>
+                <th scope="col">Old result</th>
+                <th scope="col">New result</th>
+                <th scope="col">Error</th>
+            </tr>
+            </thead>
+            <tbody>
+                {__newly_passing_rows__}
+            </tbody>
+            </table>
+            <h4>Intent changes per turn</h4>
+            <table class="table table-bordered table-sm">
+            <thead>
+            <tr>
+                <th scope="col">Test</th>
+                <th scope="col">Turn</th>
+                <th scope="col">User says</th>
+                <th scope="col">Old intent</th>
+                <th scope="col">New intent</th>
+                <th scope="col">New test result</th>
+            </tr>
+            </thead>
+            <tbody>
+                {__intent_change_rows__}
+            </tbody>
+            </table>
+            <h4>Added / removed tests</h4>
+            <table class="table table-bordered table-sm w-auto">
+            <tbody>
+                {__added_removed_rows__}
+            </tbody>
+            </table>
+        </div>
+"#;
+
+const STATUS_CHANGE_ROW: &str = r#"
+    <tr class="{__css_class__}">
+        <td>{__test__}</td>
+        <td>{__old__}</td>
+        <td>{__new__}</td>
+        <td>{__error__}</td>
+    </tr>
+"#;
+
+const INTENT_CHANGE_ROW: &str = r#"
+    <tr class="{__css_class__}">
+        <td>{__test__}</td>
+        <td>{__turn__}</td>
+        <td>{__user_says__}</td>
+        <td>{__old__}</td>
+        <td>{__new__}</td>
+        <td>{__result__}</td>
+    </tr>
+"#;
+
+// label of test result used in comparison tables
+fn result_label(test_result: &Option<TestResult>) -> &'static str {
+    match test_result {
+        Some(TestResult::Ok) => "OK",
+        Some(TestResult::Ko) => "KO",
+        None => "Not executed",
+    }
+}
+
+fn is_ok(test: &Test) -> bool {
+    matches!(test.test_result, Some(TestResult::Ok))
+}
+
+fn is_ko(test: &Test) -> bool {
+    matches!(test.test_result, Some(TestResult::Ko))
+}
+
+// test which passed in one report and did not pass in the other one
+#[derive(Debug, PartialEq)]
+pub struct TestStatusChange {
+    pub test_name: String,
+    pub old_result: String,
+    pub new_result: String,
+    pub error: Option<String>, // error of new test run (if failed)
+}
+
+// different intent detected for the same turn, test may still pass when
+// both intents are allowed by botRespondsWith
+#[derive(Debug, PartialEq)]
+pub struct IntentChange {
+    pub test_name: String,
+    pub turn: usize, // 1-based
+    pub user_says: String,
+    pub old_intent: String,
+    pub new_intent: String,
+    pub test_passes: bool,
+}
+
+// comparison of two json reports, tests are matched by name
+#[derive(Debug, PartialEq)]
+pub struct ReportDiff {
+    pub newly_failing: Vec<TestStatusChange>,
+    pub newly_passing: Vec<TestStatusChange>,
+    pub intent_changes: Vec<IntentChange>,
+    pub added_tests: Vec<String>,
+    pub removed_tests: Vec<String>,
+}
+
+// tests with duplicate names are matched in order of their occurrence
+fn tests_by_key(tests: &[Test]) -> Vec<((String, usize), &Test)> {
+    let mut occurrences: HashMap<&str, usize> = HashMap::new();
+    tests
+        .iter()
+        .map(|test| {
+            let occurrence = occurrences.entry(&test.name).or_insert(0);
+            *occurrence += 1;
+            ((test.name.clone(), *occurrence), test)
+        })
+        .collect()
+}
+
+impl ReportDiff {
+    pub fn from_tests(old_tests: &[Test], new_tests: &[Test]) -> ReportDiff {
+        let mut diff = ReportDiff {
+            newly_failing: vec![],
+            newly_passing: vec![],
+            intent_changes: vec![],
+            added_tests: vec![],
+            removed_tests: vec![],
+        };
+
+        let old_tests_by_key: HashMap<(String, usize), &Test> =
+            tests_by_key(old_tests).into_iter().collect();
+        let new_tests_by_key = tests_by_key(new_tests);
+
+        for (key, new_test) in &new_tests_by_key {
+            let old_test = match old_tests_by_key.get(key) {
+                Some(old_test) => old_test,
+                None => {
+                    diff.added_tests.push(new_test.name.clone());
+                    continue;
+                }
+            };
+
+            let status_change = TestStatusChange {
+                test_name: new_test.name.clone(),
+                old_result: result_label(&old_test.test_result).to_owned(),
+                new_result: result_label(&new_test.test_result).to_owned(),
+                error: match new_test.get_test_error().map(|(_, test_error)| test_error) {
+                    Some(TestAssertionResult::KoIntentNameMismatch(err))
+                    | Some(TestAssertionResult::KoConfidenceMismatch(err))
+                    | Some(TestAssertionResult::KoResponseCheckError(err, _))
+                    | Some(TestAssertionResult::KoBackendError(err)) => Some(err.message.clone()),
+                    _ => None,
+                },
+            };
+            if is_ko(new_test) && !is_ko(old_test) {
+                diff.newly_failing.push(status_change);
+            } else if is_ok(new_test) && !is_ok(old_test) {
+                diff.newly_passing.push(status_change);
+            }
+
+            // turns not executed in one of the runs (no intent detected) are skipped, turns are
+            // compared only while user says the same (test may have been edited between the runs)
+            for (idx, (old_assertion, new_assertion)) in old_test
+                .assertions
+                .iter()
+                .zip(new_test.assertions.iter())
+                .enumerate()
+            {
+                if old_assertion.user_says != new_assertion.user_says {
+                    break;
+                }
+                if let (Some(old_intent), Some(new_intent)) = (
+                    get_detected_intent(old_assertion),
+                    get_detected_intent(new_assertion),
+                ) {
+                    if old_intent != new_intent {
+                        diff.intent_changes.push(IntentChange {
+                            test_name: new_test.name.clone(),
+                            turn: idx + 1,
+                            user_says: new_assertion.user_says.clone(),
+                            old_intent,
+                            new_intent,
+                            test_passes: is_ok(new_test),
+                        });
+                    }
+                }
+            }
+        }
+
+        let new_keys: Vec<&(String, usize)> = new_tests_by_key.iter().map(|(key, _)| key).collect();
+        for (key, old_test) in tests_by_key(old_tests) {
+            if !new_keys.contains(&&key) {
+                diff.removed_tests.push(old_test.name.clone());
+            }
+        }
+
+        diff
+    }
+
+    pub fn has_regressions(&self) -> bool {
+        !self.newly_failing.is_empty()
+    }
+}
+
+fn colored_result(result: &str) -> String {
+    match result {
+        "OK" => Green.paint(result).to_string(),
+        "KO" => Red.paint(result).to_string(),
+        _ => Yellow.paint(result).to_string(),
+    }
+}
+
+fn status_change_css_class(status_change: &TestStatusChange) -> &'static str {
+    match status_change.new_result.as_str() {
+        "OK" => "table-success",
+        "KO" => "table-danger",
+        _ => "table-warning",
+    }
+}
+
+pub struct DiffResultReporter;
+
+impl DiffResultReporter {
+    fn print_status_changes(title: &str, status_changes: &Vec<TestStatusChange>) {
+        if status_changes.is_empty() {
+            return;
+        }
+        println!("{}", title);
+        let mut table = Table::new();
+        table.add_row(row!["Test", "Old result", "New result", "Error"]);
+        for status_change in status_changes {
+            table.add_row(row![
+                status_change.test_name,
+                colored_result(&status_change.old_result),
+                colored_result(&status_change.new_result),
+                status_change.error.as_ref().unwrap_or(&String::new())
+            ]);
+        }
+        table.printstd();
+    }
+
+    pub fn print_report_diff(diff: &ReportDiff) {
+        let mut summary_table = Table::new();
+        summary_table.add_row(row!["Newly failing tests", diff.newly_failing.len()]);
+        summary_table.add_row(row!["Newly passing tests", diff.newly_passing.len()]);
+        summary_table.add_row(row!["Intent changes", diff.intent_changes.len()]);
+        summary_table.add_row(row!["Added tests", diff.added_tests.len()]);
+        summary_table.add_row(row!["Removed tests", diff.removed_tests.len()]);
+        summary_table.printstd();
+
+        DiffResultReporter::print_status_changes("Newly failing tests", &diff.newly_failing);
+        DiffResultReporter::print_status_changes("Newly passing tests", &diff.newly_passing);
+
+        if !diff.intent_changes.is_empty() {
+            println!("Intent changes per turn");
+            let mut table = Table::new();
+            table.add_row(row![
+                "Test",
+                "Turn",
+                "User says",
+                "Old intent",
+                "New intent",
+                "New test result"
+            ]);
+            for intent_change in &diff.intent_changes {
+                table.add_row(row![
+                    intent_change.test_name,
+                    intent_change.turn,
+                    intent_change.user_says,
+                    intent_change.old_intent,
+                    intent_change.new_intent,
+                    if intent_change.test_passes {
+                        colored_result("OK")
+                    } else {
+                        colored_result("KO")
+                    }
+                ]);
+            }
+            table.printstd();
+        }
+
+        if !diff.added_tests.is_empty() || !diff.removed_tests.is_empty() {
+            let mut table = Table::new();
+            for test_name in &diff.added_tests {
+                table.add_row(row!["Added", test_name]);
+            }
+            for test_name in &diff.removed_tests {
+                table.add_row(row!["Removed", test_name]);
+            }
+            table.printstd();
+        }
+    }
+
+    fn status_change_rows(status_changes: &[TestStatusChange]) -> String {
+        status_changes
+            .iter()
+            .map(|status_change| {
+                STATUS_CHANGE_ROW
+                    .to_string()
+                    .replace("{__css_class__}", status_change_css_class(status_change))
+                    .replace("{__test__}", &escape_html(&status_change.test_name))
+                    .replace("{__old__}", &status_change.old_result)
+                    .replace("{__new__}", &status_change.new_result)
+                    .replace(
+                        "{__error__}",
+                        &escape_html(status_change.error.as_ref().unwrap_or(&String::new()))
+                            .replace("\n", "<br>"),
+                    )
+            })
+            .collect()
+    }
+
+    pub fn report_diff(diff: &ReportDiff, file_path: &Path) -> Result<()> {
+        let intent_change_rows: Vec<String> = diff
+            .intent_changes
+            .iter()
+            .map(|intent_change| {
+                INTENT_CHANGE_ROW
+                    .to_string()
+                    .replace(
+                        "{__css_class__}",
+                        if intent_change.test_passes {
+                            "table-warning"
+                        } else {
+                            "table-danger"
+                        },
+                    )
+                    .replace("{__test__}", &escape_html(&intent_change.test_name))
+                    .replace("{__turn__}", &intent_change.turn.to_string())
+                    .replace("{__user_says__}", &escape_html(&intent_change.user_says))
+                    .replace("{__old__}", &escape_html(&intent_change.old_intent))
+                    .replace("{__new__}", &escape_html(&intent_change.new_intent))
+                    .replace(
+                        "{__result__}",
+                        if intent_change.test_passes {
+                            "OK"
+                        } else {
+                            "KO"
+                        },
+                    )
+            })
+            .collect();
+
+        let mut added_removed_rows: Vec<String> = vec![];
+        for test_name in &diff.added_tests {
+            added_removed_rows.push(format!(
+                "<tr><th scope=\"row\">Added</th><td>{}</td></tr>",
+                escape_html(test_name)
+            ));
+        }
+        for test_name in &diff.removed_tests {
+            added_removed_rows.push(format!(
+                "<tr><th scope=\"row\">Removed</th><td>{}</td></tr>",
+                escape_html(test_name)
+            ));
+        }
+
+        let report_body = MASTER_CONTAINER
+            .to_string()
+            .replace("{__newly_failing__}", &diff.newly_failing.len().to_string())
+            .replace("{__newly_passing__}", &diff.newly_passing.len().to_string())
+            .replace(
+                "{__intent_changes__}",
+                &diff.intent_changes.len().to_string(),
+            )
+            .replace("{__added__}", &diff.added_tests.len().to_string())
+            .replace("{__removed__}", &diff.removed_tests.len().to_string())
+            .replace(
+                "{__newly_failing_rows__}",
+                &DiffResultReporter::status_change_rows(&diff.newly_failing),
+            )
+            .replace(
+                "{__newly_passing_rows__}",
+                &DiffResultReporter::status_change_rows(&diff.newly_passing),
+            )
+            .replace("{__intent_change_rows__}", &intent_change_rows.join(""))
+            .replace("{__added_removed_rows__}", &added_removed_rows.join(""));
+        let html_report = html_page("Test Results Comparison", &report_body);
+
+        let mut file = File::create(file_path)?;
+        file.write_all(html_report.as_bytes())?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::errors::{new_service_call_error, ErrorKind};
+    use crate::intent_matcher::IntentRule;
+    use crate::yaml_parser::TestAssertion;
+
+    fn response(intent_name: &str) -> String {
+        format!(
+            r#"{{"queryResult": {{"intent": {{"displayName": "{}"}}}}}}"#,
+            intent_name
+        )
+    }
+
+    // single turn test, expected intents are separated by comma
+    fn executed_test(name: &str, expected: &str, detected: &str) -> Test {
+        let mut test = Test::new(name.to_owned(), None, "en".to_owned());
+        let expected: Vec<IntentRule> = expected.split(',').map(IntentRule::intent_name).collect();
+        let mut assertion = TestAssertion::new(name.to_owned(), expected.clone(), vec![]);
+        if expected.iter().any(|rule| rule.matches(detected)) {
+            assertion.test_assertion_result = Some(TestAssertionResult::Ok(response(detected)));
+            test.test_result = Some(TestResult::Ok);
+        } else {
+            assertion.test_assertion_result = Some(TestAssertionResult::KoIntentNameMismatch(
+                new_service_call_error(
+                    ErrorKind::InvalidTestAssertionEvaluation,
+                    format!("Wrong intent name received: {}", detected),
+                    None,
+                    Some(response(detected)),
+                ),
+            ));
+            test.test_result = Some(TestResult::Ko);
+        }
+        test.assertions.push(assertion);
+        test
+    }
+
+    #[test]
+    fn test_report_diff() {
+        let old_tests = vec![
+            executed_test("hi", "Welcome", "Welcome"),
+            executed_test("track", "Tracking", "Tracking"),
+            executed_test("agent", "Representative", "Tracking"),
+            executed_test("bye", "Goodbye,Thanks", "Goodbye"),
+            executed_test("removed", "Welcome", "Welcome"),
+        ];
+        let new_tests = vec![
+            executed_test("hi", "Welcome", "Welcome"),
+            executed_test("track", "Tracking", "Welcome"),
+            executed_test("agent", "Representative", "Representative"),
+            executed_test("bye", "Goodbye,Thanks", "Thanks"),
+            executed_test("added", "Welcome", "Welcome"),
+        ];
+
+        let diff = ReportDiff::from_tests(&old_tests, &new_tests);
+        assert_eq!(
+            diff.newly_failing,
+            vec![TestStatusChange {
+                test_name: "track".to_owned(),
+                old_result: "OK".to_owned(),
+                new_result: "KO".to_owned(),
+                error: Some("Wrong intent name received: Welcome".to_owned()),
+            }]
+        );
+        assert_eq!(diff.newly_passing.len(), 1);
+        assert_eq!(diff.newly_passing[0].test_name, "agent");
+        assert!(diff.has_regressions());
+
+        let intent_changes: Vec<(&str, &str, &str, bool)> = diff
+            .intent_changes
+            .iter()
+            .map(|change| {
+                (
+                    change.test_name.as_str(),
+                    change.old_intent.as_str(),
+                    change.new_intent.as_str(),
+                    change.test_passes,
+                )
+            })
+            .collect();
+        assert_eq!(
+            intent_changes,
+            vec![
+                ("track", "Tracking", "Welcome", false),
+                ("agent", "Tracking", "Representative", true),
+                // still passing thanks to multiple botRespondsWith values
+                ("bye", "Goodbye", "Thanks", true),
+            ]
+        );
+        assert_eq!(diff.added_tests, vec!["added"]);
+        assert_eq!(diff.removed_tests, vec!["removed"]);
+
+        let diff = ReportDiff::from_tests(&old_tests, &old_tests);
+        assert!(!diff.has_regressions());
+        assert_eq!(diff.intent_changes.len(), 0);
+    }
+
+    // passing test with given (user says, detected intent) turns
+    fn multi_turn_test(name: &str, turns: Vec<(&str, &str)>) -> Test {
+        let mut test = Test::new(name.to_owned(), None, "en".to_owned());
+        for (user_says, detected) in turns {
+            let mut assertion = TestAssertion::new(
+                user_says.to_owned(),
+                vec![IntentRule::intent_name(detected)],
+                vec![],
+            );
+            assertion.test_assertion_result = Some(TestAssertionResult::Ok(response(detected)));
+            test.assertions.push(assertion);
+        }
+        test.test_result = Some(TestResult::Ok);
+        test
+    }
+
+    #[test]
+    fn test_report_diff_edited_turns() {
+        // turn was inserted into the test between the runs, following turns are not paired
+        let old_tests = vec![multi_turn_test(
+            "<i>track</i>",
+            vec![("<b>hi</b>", "Welcome"), ("track", "Tracking")],
+        )];
+        let new_tests = vec![multi_turn_test(
+            "<i>track</i>",
+            vec![
+                ("<b>hi</b>", "Tracking"),
+                ("hello", "Welcome"),
+                ("track", "Tracking"),
+            ],
+        )];
+
+        let diff = ReportDiff::from_tests(&old_tests, &new_tests);
+        assert_eq!(
+            diff.intent_changes,
+            vec![IntentChange {
+                test_name: "<i>track</i>".to_owned(),
+                turn: 1,
+                user_says: "<b>hi</b>".to_owned(),
+                old_intent: "Welcome".to_owned(),
+                new_intent: "Tracking".to_owned(),
+                test_passes: true,
+            }]
+        );
+
+        let report_path =
+            std::env::temp_dir().join(format!("gdf_testing_diff_{}.html", std::process::id()));
+        DiffResultReporter::report_diff(&diff, &report_path).unwrap();
+        let html = std::fs::read_to_string(&report_path).unwrap();
+        std::fs::remove_file(&report_path).unwrap();
+        assert!(html.contains("<title>Test Results Comparison</title>"));
+        assert!(html.contains("<td>&lt;i&gt;track&lt;/i&gt;</td>"));
+        assert!(html.contains("<td>&lt;b&gt;hi&lt;/b&gt;</td>"));
+        assert!(!html.contains("<b>hi</b>"));
+    }
+}
@@ -0,0 +1,88 @@
+use ansi_term::Colour::{Green, Red, Yellow};
+use prettytable::Table;
+use prettytable::{cell, row}; // macros
+use std::path::{Path, PathBuf};
+
+use crate::errors::Result;
+use crate::history::{append_run, TestHistory, Trend};
+use crate::result_reporters::ResultReporter;
+use crate::yaml_parser::Test;
+
+// appends executed tests as new run into run history directory (see history module)
+pub struct HistoryResultReporter {
+    history_dir: PathBuf,
+    suite_name: String,
+}
+
+impl HistoryResultReporter {
+    pub fn new(history_dir: &Path) -> Self {
+        HistoryResultReporter {
+            history_dir: history_dir.to_path_buf(),
+            suite_name: String::new(),
+        }
+    }
+
+    // tests are stored together with suite name, i.e. tests with the same name
+    // in different suites sharing history directory have separate histories
+    pub fn with_suite_name(mut self, suite_name: &str) -> Self {
+        self.suite_name = suite_name.to_owned();
+        self
+    }
+
+    fn colored_sparkline(history: &TestHistory) -> String {
+        history
+            .sparkline()
+            .chars()
+            .zip(history.results.iter())
+            .map(|(bar, passed)| {
+                let bar = bar.to_string();
+                if *passed {
+                    Green.paint(bar).to_string()
+                } else {
+                    Red.paint(bar).to_string()
+                }
+            })
+            .collect()
+    }
+
+    pub fn print_test_histories(histories: &[TestHistory]) {
+        let mut table = Table::new();
+        table.add_row(row![
+            "Suite",
+            "Test",
+            "Runs",
+            "Pass rate",
+            "History",
+            "Trend",
+            "Flaky"
+        ]);
+        for history in histories {
+            let trend = match history.trend() {
+                Trend::Improving => Green.paint(history.trend().to_string()),
+                Trend::Degrading => Red.paint(history.trend().to_string()),
+                Trend::Stable => history.trend().to_string().into(),
+            };
+            table.add_row(row![
+                history.suite_name,
+                history.test_name,
+                history.results.len(),
+                format!("{:.0}%", history.pass_rate() * 100.0),
+                HistoryResultReporter::colored_sparkline(history),
+                trend,
+                if history.is_flaky() {
+                    Yellow.paint("yes").to_string()
+                } else {
+                    String::from("no")
+                }
+            ]);
+        }
+        table.printstd();
+    }
+}
+
+impl ResultReporter for HistoryResultReporter {
+    fn end(&mut self, tests: &[Test]) -> Result<()> {
+        append_run(&self.history_dir, &self.suite_name, tests)?;
+        Ok(())
+    }
+}
@@ -1,33 +1,20 @@
-use crate::errors::Result;
-use crate::yaml_parser::{Test, TestAssertionResult};
+use crate::errors::{Error, Result};
+use crate::history::{test_histories, HistoryRun, TestHistory};
+use crate::result_reporters::{escape_html, html_page, ResultReporter, TestResultsSummary};
+use crate::yaml_parser::{
+    Test, TestAssertion, TestAssertionResponseCheckValue, TestAssertionResult,
+};
 use std::fs::File;
 use std::io::Write;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 const MASTER_CONTAINER: &str = r#"
-    <!doctype html>
-    <html lang="en">
-        <head>
-        <!-- Required meta tags -->
-        <meta charset="utf-8">
-        <meta name="viewport" content="width=device-width, initial-scale=1, shrink-to-fit=no">
-        <!-- Bootstrap CSS -->
-        <link rel="stylesheet" href="https://maxcdn.bootstrapcdn.com/bootstrap/4.0.0/css/bootstrap.min.css" integrity="sha384-Gn5384xqQ1aoWXA+058RXPxPg6fy4IWvTNh0E263XmFcJlSAwiGgFAW/dAiS6JXm" crossorigin="anonymous">
-        <title>Dialog Testing Report</title>
-        </head>
-        <body>
-        <!-- Optional JavaScript -->
-        <!-- jQuery first, then Popper.js, then Bootstrap JS -->
-        <script src="https://code.jquery.com/jquery-3.2.1.slim.min.js" integrity="sha384-KJ3o2DKtIkvYIK3UENzmM7KCkRr/rE9/Qpg6aAZGJwFDMVNA/GpGFF93hXpG5KkN" crossorigin="anonymous"></script>
-        <script src="https://cdnjs.cloudflare.com/ajax/libs/popper.js/1.12.9/umd/popper.min.js" integrity="sha384-ApNbgh9B+Y1QKtv3Rn7W3mgPxhU9K/ScQsAP7hUibX39j7fakFPskvXusvfa0b4Q" crossorigin="anonymous"></script>
-        <script src="https://maxcdn.bootstrapcdn.com/bootstrap/4.0.0/js/bootstrap.min.js" integrity="sha384-JZR6Spejh4U02d8jOt6vLEHfe/JQGiRRSQQxSfFWpi1MquVdAyjUar5+76PVCmYl" crossorigin="anonymous"></script>
         <div class="p-1"><!-- padding 1 -->
+            {__summary__}
             <div id="accordion">
                 {__report_body__}
             </div>
         </div>
-        </body>
-    </html>  
 "#;
 
 const OK_SVG: &str = r#"
@@ -45,6 +32,13 @@ const KO_SVG: &str = r#"
     </svg>
 "#;
 
+const WARN_SVG: &str = r#"
+    <svg class="bi bi-exclamation-triangle text-warning" width="1em" height="1em" viewBox="0 0 16 16" fill="currentColor" xmlns="http://www.w3.org/2000/svg">
+        <path fill-rule="evenodd" d="M7.938 2.016a.146.146 0 00-.054.057L1.027 13.74a.176.176 0 00-.002.183c.016.03.037.05.054.06.015.01.034.017.066.017h13.713a.12.12 0 00.066-.017.163.163 0 00.055-.06.176.176 0 00-.003-.183L8.12 2.073a.146.146 0 00-.054-.057A.13.13 0 008.002 2a.13.13 0 00-.064.016zm1.044-.45a1.13 1.13 0 00-1.96 0L.165 13.233c-.457.778.091 1.767.98 1.767h13.713c.889 0 1.438-.99.98-1.767L8.982 1.566z" clip-rule="evenodd"/>
+        <path d="M7.002 12a1 1 0 112 0 1 1 0 01-2 0zM7.1 5.995a.905.905 0 111.8 0l-.35 3.507a.552.552 0 01-1.1 0L7.1 5.995z"/>
+    </svg>
+"#;
+
 #[allow(dead_code)]
 const UNKNOWN_SVG: &str = r#"
     <span><svg class="bi bi-x-circle text-danger" width="1em" height="1em" viewBox="0 0 16 16" fill="currentColor" xmlns="http://www.w3.org/2000/svg">
@@ -67,6 +61,8 @@ const ACCORDION_ITEM: &str = r##"
         <div class="card-body">
             {__card_body_test__}
             {__card_body_err_msg__}
+            {__card_body_warnings__}
+            {__card_body_repetitions__}
         </div>
     </div>
     </div>
@@ -77,11 +73,48 @@ const TEST_ASSERTION_CHECK_ERROR_MSG: &str = r#"
 {__err_msg__}
 "#;
 
+const TEST_WARNINGS_MSG: &str = r#"
+<b>Warnings:</b></br>
+{__warnings__}
+"#;
+
+const TEST_REPETITIONS_TABLE: &str = r#"
+    <table class="table table-bordered table-sm w-auto">
+    <thead>
+    <tr>
+        <th colspan="3" scope="col">Intents observed in {__count__} repetitions:</th>
+    </tr>
+    <tr>
+        <th scope="col">Turn</th>
+        <th scope="col">User says</th>
+        <th scope="col">Intents</th>
+    </tr>
+    </thead>
+    <tbody>
+        {__rows__}
+    </tbody>
+    </table>
+"#;
+
+const SUMMARY: &str = r#"
+    <div class="alert alert-secondary">{__summary__}</div>
+"#;
+
 const TEST_ASSERTION_INTENT_MISMATCH_ERROR_MSG: &str = r#"
 <b>Intent name mismatch:</b></br>
 {__err_msg__}
 "#;
 
+const TEST_ASSERTION_BACKEND_ERROR_MSG: &str = r#"
+<b>NLP provider call failed:</b></br>
+{__err_msg__}
+"#;
+
+const TEST_ASSERTION_CONFIDENCE_ERROR_MSG: &str = r#"
+<b>Intent confidence check failed:</b></br>
+{__err_msg__}
+"#;
+
 const TEST_RESULT_TABLE: &str = r#"
     <table class="table table-bordered">
     <thead>
@@ -108,6 +141,7 @@ const ASSERTION_ROW: &str = r##"
     <td>{__bot_responds_with__}</td>
     <td>
         {__intent_name_match_status__}
+        {__confidence__}
     </td>
     <td>
         {__assertion_checks_table__}
@@ -154,21 +188,227 @@ const ASSERTION_CHECK_TABLE: &str = r#"
     </table>
 "#;
 
-pub struct HtmlResultReporter;
+// number of runs (including current one) displayed in history sparkline
+pub(crate) const HISTORY_SPARKLINE_RUNS: usize = 20;
+
+pub struct HtmlResultReporter {
+    file_path: PathBuf,
+    previous_runs: Option<Vec<HistoryRun>>,
+    suite_name: String,
+}
+
+impl ResultReporter for HtmlResultReporter {
+    fn end(&mut self, tests: &[Test]) -> Result<()> {
+        match &self.previous_runs {
+            Some(previous_runs) => {
+                let mut runs = previous_runs.clone();
+                runs.push(HistoryRun::new(&self.suite_name, tests));
+                // histories of other suites stored in the same history directory are not displayed
+                let mut histories = test_histories(&runs, HISTORY_SPARKLINE_RUNS);
+                histories.retain(|history| history.suite_name == self.suite_name);
+                HtmlResultReporter::report_test_results_with_history(
+                    tests,
+                    &histories,
+                    &self.file_path,
+                )
+            }
+            None => HtmlResultReporter::report_test_results(tests, &self.file_path),
+        }
+    }
+}
 
 impl HtmlResultReporter {
-    pub fn report_test_results(tests: &Vec<Test>, file_path: &Path) -> Result<()> {
+    pub fn new(file_path: &Path) -> Self {
+        HtmlResultReporter {
+            file_path: file_path.to_path_buf(),
+            previous_runs: None,
+            suite_name: String::new(),
+        }
+    }
+
+    // every test header is followed by sparkline with results of previous runs and the current one,
+    // only last HISTORY_SPARKLINE_RUNS - 1 previous runs are displayed
+    pub fn with_history(mut self, previous_runs: Vec<HistoryRun>, suite_name: &str) -> Self {
+        self.previous_runs = Some(previous_runs);
+        self.suite_name = suite_name.to_owned();
+        self
+    }
+
+    // pass rate of repeatedly executed test displayed in test header
+    fn get_repetitions_badge_html(test: &Test) -> String {
+        match &test.repetitions {
+            Some(repetitions) => format!(
+                r#"<span class="badge badge-secondary ml-2">{}</span>"#,
+                repetitions.pass_rate_display()
+            ),
+            None => String::new(),
+        }
+    }
+
+    fn get_repetitions_html(test: &Test) -> String {
+        let repetitions = match &test.repetitions {
+            Some(repetitions) => repetitions,
+            None => return String::new(),
+        };
+
+        let rows: Vec<String> = test
+            .assertions
+            .iter()
+            .enumerate()
+            .map(|(turn, assertion)| {
+                format!(
+                    "<tr><td>{}</td><td>{}</td><td>{}</td></tr>",
+                    turn + 1,
+                    escape_html(&assertion.user_says),
+                    escape_html(&repetitions.intent_distribution_display(turn))
+                )
+            })
+            .collect();
+        TEST_REPETITIONS_TABLE
+            .to_string()
+            .replace("{__count__}", &repetitions.count.to_string())
+            .replace("{__rows__}", &rows.join(""))
+    }
+
+    // one bar per run, tall green bar for passed run, short red bar for failed one
+    fn get_history_sparkline_html(history: &TestHistory) -> String {
+        let bars: Vec<String> = history
+            .results
+            .iter()
+            .enumerate()
+            .map(|(idx, passed)| {
+                let (height, color) = if *passed {
+                    (12, "#28a745")
+                } else {
+                    (4, "#dc3545")
+                };
+                format!(
+                    r#"<rect x="{}" y="{}" width="3" height="{}" fill="{}"/>"#,
+                    idx * 4,
+                    12 - height,
+                    height,
+                    color
+                )
+            })
+            .collect();
+
+        format!(
+            r#"<svg class="ml-2" width="{}" height="12" xmlns="http://www.w3.org/2000/svg"><title>Passed {} of last {} runs</title>{}</svg>"#,
+            history.results.len() * 4,
+            history.passed(),
+            history.results.len(),
+            bars.join("")
+        )
+    }
+
+    // observed intent detection confidence displayed below intent match status
+    fn get_confidence_html(assertion: &TestAssertion) -> String {
+        match assertion.detected_confidence {
+            Some(_) => format!(
+                "</br><span>{}</span>",
+                assertion.detected_confidence_display()
+            ),
+            None => String::new(),
+        }
+    }
+
+    // passed checks are marked with warning icon when they failed with warning severity
+    fn get_check_status_svg(assertion: &TestAssertion, idx: usize) -> &'static str {
+        match assertion.response_check_warning(idx) {
+            Some(_) => WARN_SVG,
+            None => OK_SVG,
+        }
+    }
+
+    fn get_intent_status_svg(assertion: &TestAssertion) -> &'static str {
+        if assertion.has_intent_warning() {
+            WARN_SVG
+        } else {
+            OK_SVG
+        }
+    }
+
+    fn get_warnings_html(test: &Test) -> String {
+        let warnings: Vec<String> = test
+            .assertions
+            .iter()
+            .flat_map(|assertion| assertion.warnings.iter())
+            .map(|warning| warning.message().replace("\n", "</br>"))
+            .collect();
+
+        if warnings.is_empty() {
+            return String::new();
+        }
+        TEST_WARNINGS_MSG
+            .to_string()
+            .replace("{__warnings__}", &warnings.join("</br>"))
+    }
+
+    // check group is rendered as tree with status of every nested check recorded during execution,
+    // group without recorded rows (e.g. test loaded from older history) is rendered as single row
+    fn get_check_group_rows(
+        assertion: &TestAssertion,
+        idx: usize,
+        status_svg: &str,
+    ) -> Vec<String> {
+        let tree_rows = match assertion.check_group_rows(idx) {
+            Some(tree_rows) => tree_rows,
+            None => {
+                let response_check = &assertion.response_checks[idx];
+                return vec![ASSERTION_CHECK_ROW
+                    .to_string()
+                    .replace("{__expression__}", &response_check.expression)
+                    .replace("{__operator__}", &response_check.operator.to_string())
+                    .replace("{__value__}", &response_check.value.to_string())
+                    .replace("{__status__}", status_svg)];
+            }
+        };
+        tree_rows
+            .iter()
+            .map(|tree_row| {
+                ASSERTION_CHECK_ROW
+                    .to_string()
+                    .replace(
+                        "{__expression__}",
+                        &("&nbsp;&nbsp;&nbsp;&nbsp;".repeat(tree_row.depth) + &tree_row.expression),
+                    )
+                    .replace("{__operator__}", &tree_row.operator)
+                    .replace("{__value__}", &tree_row.value)
+                    .replace("{__status__}", if tree_row.ok { OK_SVG } else { KO_SVG })
+            })
+            .collect()
+    }
+
+    fn get_backend_response_html(err: &Error) -> String {
+        match &err.backend_response {
+            Some(backend_response) => String::from("<span>") + backend_response + "</span>",
+            None => String::from("<span>No response received</span>"),
+        }
+    }
+
+    pub fn report_test_results(tests: &[Test], file_path: &Path) -> Result<()> {
+        HtmlResultReporter::report_test_results_with_history(tests, &[], file_path)
+    }
+
+    // histories are matched to tests by name, tests without history have no sparkline
+    pub fn report_test_results_with_history(
+        tests: &[Test],
+        histories: &[TestHistory],
+        file_path: &Path,
+    ) -> Result<()> {
         let mut test_tables: Vec<String> = vec![];
 
         for (test_idx, test) in tests.iter().enumerate() {
-            let test_result = test.get_test_error();
+            let test_result = test.get_test_error().map(|(_, test_error)| test_error);
 
             // create header row for each test accordion element
             let mut test_header_html = String::new();
             match test_result {
                 Some(some_test_result) => {
                     match some_test_result {
-                        TestAssertionResult::KoIntentNameMismatch(_) => {
+                        TestAssertionResult::KoIntentNameMismatch(_)
+                        | TestAssertionResult::KoConfidenceMismatch(_)
+                        | TestAssertionResult::KoBackendError(_) => {
                             test_header_html =
                                 format!("Test #{} ({}){}", test_idx + 1, test.name.clone(), KO_SVG);
                         }
@@ -180,12 +420,29 @@ impl HtmlResultReporter {
                         }
                     }
                 }
+                None if test.has_warnings() => {
+                    test_header_html = format!(
+                        "Test #{} ({}){}{}",
+                        test_idx + 1,
+                        test.name.clone(),
+                        OK_SVG,
+                        WARN_SVG
+                    );
+                }
                 None => {
                     test_header_html =
                         format!("Test #{} ({}){}", test_idx + 1, test.name.clone(), OK_SVG);
                 }
             } // match test_result
 
+            test_header_html.push_str(&HtmlResultReporter::get_repetitions_badge_html(test));
+            if let Some(history) = histories
+                .iter()
+                .find(|history| history.test_name == test.name)
+            {
+                test_header_html.push_str(&HtmlResultReporter::get_history_sparkline_html(history));
+            }
+
             // now prepare assertion rows for final assertion table
             let mut test_table_assertions_html: Vec<String> = vec![];
 
@@ -195,13 +452,18 @@ impl HtmlResultReporter {
 
                 let mut test_table_assertion_resp_checks:Vec<String> = vec![];
 
-                for response_check in &assertion.response_checks {
+                for (idx, response_check) in assertion.response_checks.iter().enumerate() {
+
+                  if let TestAssertionResponseCheckValue::GroupVal(_) = response_check.value {
+                    test_table_assertion_resp_checks.extend(HtmlResultReporter::get_check_group_rows(assertion, idx, HtmlResultReporter::get_check_status_svg(assertion, idx)));
+                    continue;
+                  }
 
                   let assertion_check_row =  ASSERTION_CHECK_ROW.to_string()
                   .replace("{__expression__}", &response_check.expression)
                   .replace("{__operator__}", &response_check.operator.to_string())
                   .replace("{__value__}", &response_check.value.to_string())
-                  .replace("{__status__}", OK_SVG);
+                  .replace("{__status__}", HtmlResultReporter::get_check_status_svg(assertion, idx));
 
                   test_table_assertion_resp_checks.push(assertion_check_row);
                 }
@@ -211,8 +473,9 @@ impl HtmlResultReporter {
               let backend_response = String::from("<span>") + response + "</span>";
               let assertion_html = ASSERTION_ROW.to_string()
               .replace("{__user_says__}", &assertion.user_says)
-              .replace("{__bot_responds_with__}", &assertion.bot_responds_with.join("</br>"))
-              .replace("{__intent_name_match_status__}", OK_SVG)
+              .replace("{__bot_responds_with__}", &assertion.bot_responds_with_display("</br>"))
+              .replace("{__intent_name_match_status__}", HtmlResultReporter::get_intent_status_svg(assertion))
+              .replace("{__confidence__}", &HtmlResultReporter::get_confidence_html(assertion))
               .replace("{__assertion_checks_table__}", match assertion.response_checks.len() {
                 0 => "<span>No response checks</span>",
                 // _ => OK_SVG // do not display assertion response check table when assertion result is OK (same as std out report)
@@ -223,12 +486,13 @@ impl HtmlResultReporter {
               .replace("{__json_raw_response__}", &backend_response);
               test_table_assertions_html.push(assertion_html);
             },
-            TestAssertionResult::KoIntentNameMismatch(err) => {
-              let backend_response = String::from("<span>") + err.backend_response.as_ref().unwrap() + "</span>";
+            TestAssertionResult::KoIntentNameMismatch(err) | TestAssertionResult::KoConfidenceMismatch(err) => {
+              let backend_response = HtmlResultReporter::get_backend_response_html(err);
               let assertion_html = ASSERTION_ROW.to_string()
               .replace("{__user_says__}", &assertion.user_says)
-              .replace("{__bot_responds_with__}", &assertion.bot_responds_with.join("</br>"))
+              .replace("{__bot_responds_with__}", &assertion.bot_responds_with_display("</br>"))
               .replace("{__intent_name_match_status__}", KO_SVG)
+              .replace("{__confidence__}", &HtmlResultReporter::get_confidence_html(assertion))
               .replace("{__assertion_checks_table__}", "<span>not executed</span>")
               .replace("{__test_id__}", &test_idx.to_string())
               .replace("{__assertion_id__}", &assertion_idx.to_string())
@@ -236,17 +500,34 @@ impl HtmlResultReporter {
               test_table_assertions_html.push(assertion_html);
               break; // do not continue with any other assertion!
             },
+            TestAssertionResult::KoBackendError(_) => {
+              let assertion_html = ASSERTION_ROW.to_string()
+              .replace("{__user_says__}", &assertion.user_says)
+              .replace("{__bot_responds_with__}", &assertion.bot_responds_with_display("</br>"))
+              .replace("{__intent_name_match_status__}", KO_SVG)
+              .replace("{__confidence__}", "")
+              .replace("{__assertion_checks_table__}", "<span>not executed</span>")
+              .replace("{__test_id__}", &test_idx.to_string())
+              .replace("{__assertion_id__}", &assertion_idx.to_string())
+              .replace("{__json_raw_response__}", "<span>No response received</span>");
+              test_table_assertions_html.push(assertion_html);
+              break; // do not continue with any other assertion!
+            },
             TestAssertionResult::KoResponseCheckError(err, assertion_check_idx) => {
               let mut test_table_assertion_resp_checks:Vec<String> = vec![];
 
               for idx in 0..*assertion_check_idx + 1 {
                 let response_check = &assertion.response_checks[idx];
 
-                let res_str;
-                if idx == *assertion_check_idx {
-                  res_str = KO_SVG;
+                let res_str = if idx == *assertion_check_idx {
+                  KO_SVG
                 } else {
-                  res_str = OK_SVG;
+                  HtmlResultReporter::get_check_status_svg(assertion, idx)
+                };
+
+                if let TestAssertionResponseCheckValue::GroupVal(_) = response_check.value {
+                  test_table_assertion_resp_checks.extend(HtmlResultReporter::get_check_group_rows(assertion, idx, res_str));
+                  continue;
                 }
 
                 let assertion_check_row =  ASSERTION_CHECK_ROW.to_string()
@@ -258,11 +539,12 @@ impl HtmlResultReporter {
                 test_table_assertion_resp_checks.push(assertion_check_row);
               }
 
-              let backend_response = String::from("<span>") + err.backend_response.as_ref().unwrap() + "</span>";
+              let backend_response = HtmlResultReporter::get_backend_response_html(err);
               let assertion_html = ASSERTION_ROW.to_string()
               .replace("{__user_says__}", &assertion.user_says)
-              .replace("{__bot_responds_with__}", &assertion.bot_responds_with.join("</br>"))
-              .replace("{__intent_name_match_status__}", OK_SVG)
+              .replace("{__bot_responds_with__}", &assertion.bot_responds_with_display("</br>"))
+              .replace("{__intent_name_match_status__}", HtmlResultReporter::get_intent_status_svg(assertion))
+              .replace("{__confidence__}", &HtmlResultReporter::get_confidence_html(assertion))
               .replace("{__assertion_checks_table__}", &ASSERTION_CHECK_TABLE.to_string().replace("{__rows__}", &test_table_assertion_resp_checks.join("")))
               .replace("{__test_id__}", &test_idx.to_string())
               .replace("{__assertion_id__}", &assertion_idx.to_string())
@@ -285,7 +567,18 @@ impl HtmlResultReporter {
                                 .replace("{__err_msg__}", &err.message);
                         }
                         TestAssertionResult::KoResponseCheckError(err, _) => {
+                            // multiline messages (e.g. lists of differences) are kept on separate lines
                             test_err_msg = TEST_ASSERTION_CHECK_ERROR_MSG
+                                .to_string()
+                                .replace("{__err_msg__}", &err.message.replace("\n", "</br>"));
+                        }
+                        TestAssertionResult::KoConfidenceMismatch(err) => {
+                            test_err_msg = TEST_ASSERTION_CONFIDENCE_ERROR_MSG
+                                .to_string()
+                                .replace("{__err_msg__}", &err.message);
+                        }
+                        TestAssertionResult::KoBackendError(err) => {
+                            test_err_msg = TEST_ASSERTION_BACKEND_ERROR_MSG
                                 .to_string()
                                 .replace("{__err_msg__}", &err.message);
                         }
@@ -307,15 +600,31 @@ impl HtmlResultReporter {
                 .replace("{__test_header__}", &test_header_html)
                 .replace("{__card_body_test__}", &test_table)
                 .replace("{__test_id__}", &test_idx.to_string())
-                .replace("{__card_body_err_msg__}", &test_err_msg);
+                .replace("{__card_body_err_msg__}", &test_err_msg)
+                .replace(
+                    "{__card_body_warnings__}",
+                    &HtmlResultReporter::get_warnings_html(test),
+                )
+                .replace(
+                    "{__card_body_repetitions__}",
+                    &HtmlResultReporter::get_repetitions_html(test),
+                );
 
             test_tables.push(test_accordion);
         } // for test in tests
 
-        let html_report = MASTER_CONTAINER
+        let report_body = MASTER_CONTAINER
             .to_string()
+            .replace(
+                "{__summary__}",
+                &SUMMARY.replace(
+                    "{__summary__}",
+                    &TestResultsSummary::from_tests(tests).to_string(),
+                ),
+            )
             .replace("{__report_body__}", &test_tables.join(""))
-            .replace("<<json>>=", "&lt;&lt;json&gt;&gt;="); // use html entities for json equals operator to display it properly in html
+            .replace("<<json>>", "&lt;&lt;json&gt;&gt;"); // use html entities for json operators to display them properly in html
+        let html_report = html_page("Dialog Testing Report", &report_body);
 
         let mut file = File::create(file_path)?;
         file.write_all(html_report.as_bytes())?;
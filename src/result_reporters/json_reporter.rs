@@ -1,16 +1,102 @@
 use crate::errors::Result;
+use crate::result_reporters::ResultReporter;
 use crate::yaml_parser::Test;
 use std::fs::File;
 use std::io::Write;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
-pub struct JsonResultReporter;
+pub struct JsonResultReporter {
+    file_path: PathBuf,
+}
 
 impl JsonResultReporter {
-    pub fn report_test_results(tests: &Vec<Test>, file_path: &Path) -> Result<()> {
+    pub fn new(file_path: &Path) -> Self {
+        JsonResultReporter {
+            file_path: file_path.to_path_buf(),
+        }
+    }
+
+    pub fn report_test_results(tests: &[Test], file_path: &Path) -> Result<()> {
         let tests_json = serde_json::to_string_pretty(tests)?;
         let mut file = File::create(file_path)?;
         file.write_all(tests_json.as_bytes())?;
         Ok(())
     }
 }
+
+impl ResultReporter for JsonResultReporter {
+    fn end(&mut self, tests: &[Test]) -> Result<()> {
+        JsonResultReporter::report_test_results(tests, &self.file_path)
+    }
+}
+
+// streaming reporter, every finished test is written immediately as one line of json (JSON Lines)
+pub struct JsonLinesResultReporter {
+    file_path: PathBuf,
+    file: Option<File>,
+}
+
+impl JsonLinesResultReporter {
+    pub fn new(file_path: &Path) -> Self {
+        JsonLinesResultReporter {
+            file_path: file_path.to_path_buf(),
+            file: None,
+        }
+    }
+}
+
+impl ResultReporter for JsonLinesResultReporter {
+    fn begin(&mut self, _test_count: usize) -> Result<()> {
+        self.file = Some(File::create(&self.file_path)?);
+        Ok(())
+    }
+
+    fn test_finished(&mut self, test: &Test) -> Result<()> {
+        if self.file.is_none() {
+            self.begin(0)?;
+        }
+        // safe to unwrap, file was created above
+        let file = self.file.as_mut().unwrap();
+        writeln!(file, "{}", serde_json::to_string(test)?)?;
+        file.flush()?;
+        Ok(())
+    }
+
+    fn end(&mut self, _tests: &[Test]) -> Result<()> {
+        if self.file.is_none() {
+            self.begin(0)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn test_json_lines_reporter() -> Result<()> {
+        let file_path = std::env::temp_dir().join("gdf_testing_json_lines_reporter.jsonl");
+        let tests = vec![
+            Test::new("Hello".to_owned(), None, "en".to_owned()),
+            Test::new("Bye".to_owned(), None, "en".to_owned()),
+        ];
+
+        let mut reporter = JsonLinesResultReporter::new(&file_path);
+        reporter.begin(tests.len())?;
+        reporter.test_finished(&tests[1])?;
+        // finished tests are available before execution ends
+        assert_eq!(fs::read_to_string(&file_path)?.lines().count(), 1);
+        reporter.test_finished(&tests[0])?;
+        reporter.end(&tests)?;
+
+        let names: Vec<String> = fs::read_to_string(&file_path)?
+            .lines()
+            .map(|line| serde_json::from_str::<Test>(line).unwrap().name)
+            .collect();
+        assert_eq!(names, vec!["Bye", "Hello"]);
+        fs::remove_file(&file_path)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,265 @@
+use std::fs::File;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use crate::errors::Result;
+use crate::result_reporters::classification_reporter::get_detected_intent;
+use crate::result_reporters::{escape_html, ResultReporter, TestResultsSummary};
+use crate::yaml_parser::{Test, TestAssertionResult};
+
+// compact report suitable for pull/merge request comments (GitHub, GitLab): summary table
+// followed by collapsible section for every failing test
+pub struct MarkdownResultReporter {
+    file_path: PathBuf,
+}
+
+impl ResultReporter for MarkdownResultReporter {
+    fn end(&mut self, tests: &[Test]) -> Result<()> {
+        MarkdownResultReporter::report_test_results(tests, &self.file_path)
+    }
+}
+
+// table cells cannot contain pipes and new lines
+fn table_cell(text: &str) -> String {
+    text.replace("|", "\\|").replace("\n", "<br>")
+}
+
+// code span with text containing backticks must be delimited by longer backtick string
+fn code_cell(text: &str) -> String {
+    let delimiter = if text.contains('`') { "``" } else { "`" };
+    format!("{} {} {}", delimiter, table_cell(text), delimiter)
+}
+
+// code block fence must be longer than any backtick run of the text (at least three backticks)
+fn code_block(text: &str) -> String {
+    let longest_run = text
+        .split(|c| c != '`')
+        .map(|run| run.len())
+        .max()
+        .unwrap_or(0);
+    let fence = "`".repeat((longest_run + 1).max(3));
+    format!("{}\n{}\n{}", fence, text, fence)
+}
+
+impl MarkdownResultReporter {
+    pub fn new(file_path: &Path) -> Self {
+        MarkdownResultReporter {
+            file_path: file_path.to_path_buf(),
+        }
+    }
+
+    fn get_summary_md(tests: &[Test]) -> String {
+        let summary = TestResultsSummary::from_tests(tests);
+        let headline = if summary.total == 0 {
+            "### :warning: No tests executed".to_string()
+        } else if summary.ko > 0 {
+            format!("### :x: {} of {} tests failed", summary.ko, summary.total)
+        } else if summary.not_executed > 0 {
+            format!(
+                "### :warning: {} of {} tests not executed",
+                summary.not_executed, summary.total
+            )
+        } else {
+            format!("### :white_check_mark: All {} tests passed", summary.total)
+        };
+
+        format!(
+            "{}\n\n| Tests | OK | KO | Not executed | With warnings |\n|---:|---:|---:|---:|---:|\n| {} | {} | {} | {} | {} |\n",
+            headline,
+            summary.total,
+            summary.ok,
+            summary.ko,
+            summary.not_executed,
+            summary.with_warnings
+        )
+    }
+
+    // failing turn with expected vs. actual intent and failing check (if any)
+    fn get_failed_test_md(test_idx: usize, test: &Test) -> Option<String> {
+        let (assertion_idx, test_error) = test.get_test_error()?;
+        let assertion = &test.assertions[assertion_idx];
+
+        let mut rows = vec![
+            format!(
+                "| Turn | {} of {} |",
+                assertion_idx + 1,
+                test.assertions.len()
+            ),
+            format!("| User says | {} |", code_cell(&assertion.user_says)),
+            format!(
+                "| Expected intent | {} |",
+                code_cell(&assertion.bot_responds_with_display(", "))
+            ),
+            format!(
+                "| Actual intent | {} |",
+                get_detected_intent(assertion)
+                    .map(|intent| code_cell(&intent))
+                    .unwrap_or(String::from("N/A"))
+            ),
+        ];
+
+        // repeatedly executed test shows pass rate and all intents observed in failing turn
+        if let Some(repetitions) = &test.repetitions {
+            rows.push(format!(
+                "| Repetitions | {} |",
+                table_cell(&repetitions.pass_rate_display())
+            ));
+            rows.push(format!(
+                "| Observed intents | {} |",
+                code_cell(&repetitions.intent_distribution_display(assertion_idx))
+            ));
+        }
+
+        let err = match test_error {
+            TestAssertionResult::KoIntentNameMismatch(err)
+            | TestAssertionResult::KoConfidenceMismatch(err)
+            | TestAssertionResult::KoBackendError(err) => err,
+            TestAssertionResult::KoResponseCheckError(err, check_idx) => {
+                if let Some(response_check) = assertion.response_checks.get(*check_idx) {
+                    rows.push(format!(
+                        "| Failing check | {} |",
+                        code_cell(&response_check.to_string())
+                    ));
+                }
+                err
+            }
+            TestAssertionResult::Ok(_) => return None, // get_test_error never returns Ok
+        };
+
+        Some(format!(
+            "<details>\n<summary>:x: Test #{} ({})</summary>\n\n| | |\n|---|---|\n{}\n\n{}\n\n</details>\n",
+            test_idx + 1,
+            escape_html(&test.name),
+            rows.join("\n"),
+            code_block(&err.message)
+        ))
+    }
+
+    pub fn get_report_md(tests: &[Test]) -> String {
+        let mut sections = vec![MarkdownResultReporter::get_summary_md(tests)];
+        for (test_idx, test) in tests.iter().enumerate() {
+            if let Some(failed_test_md) = MarkdownResultReporter::get_failed_test_md(test_idx, test)
+            {
+                sections.push(failed_test_md);
+            }
+        }
+        sections.join("\n")
+    }
+
+    pub fn report_test_results(tests: &[Test], file_path: &Path) -> Result<()> {
+        let mut file = File::create(file_path)?;
+        file.write_all(MarkdownResultReporter::get_report_md(tests).as_bytes())?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::errors::{new_service_call_error, ErrorKind};
+    use crate::intent_matcher::IntentRule;
+    use crate::yaml_parser::{
+        TestAssertion, TestAssertionResponseCheck, TestAssertionResponseCheckOperator,
+        TestAssertionResponseCheckValue, TestRepetitions, TestResult,
+    };
+    use std::collections::BTreeMap;
+
+    const RESPONSE: &str =
+        r#"{"queryResult": {"action": "track", "intent": {"displayName": "Tracking|CS|3"}}}"#;
+
+    fn test_with_result(name: &str, assertion_result: TestAssertionResult) -> Test {
+        let mut test = Test::new(name.to_owned(), None, "en".to_owned());
+        let mut hello = TestAssertion::new(
+            "hello".to_owned(),
+            vec![IntentRule::intent_name("Welcome")],
+            vec![],
+        );
+        hello.test_assertion_result = Some(TestAssertionResult::Ok(RESPONSE.to_owned()));
+        let mut track = TestAssertion::new(
+            "track 123".to_owned(),
+            vec![IntentRule::intent_name("Tracking|CS|3")],
+            vec![TestAssertionResponseCheck::new(
+                "queryResult.action".to_owned(),
+                TestAssertionResponseCheckOperator::Equals,
+                TestAssertionResponseCheckValue::StrVal("express_track".to_owned()),
+            )],
+        );
+        test.test_result = match assertion_result {
+            TestAssertionResult::Ok(_) => Some(TestResult::Ok),
+            _ => Some(TestResult::Ko),
+        };
+        track.test_assertion_result = Some(assertion_result);
+        test.assertions = vec![hello, track];
+        test
+    }
+
+    #[test]
+    fn test_markdown_report() {
+        let tests = vec![
+            test_with_result("Tracking OK", TestAssertionResult::Ok(RESPONSE.to_owned())),
+            test_with_result(
+                "Tracking KO",
+                TestAssertionResult::KoResponseCheckError(
+                    new_service_call_error(
+                        ErrorKind::InvalidTestAssertionResponseCheckEvaluation,
+                        "Expected value: express_track, real value: track".to_owned(),
+                        None,
+                        Some(RESPONSE.to_owned()),
+                    ),
+                    0,
+                ),
+            ),
+        ];
+
+        let report = MarkdownResultReporter::get_report_md(&tests);
+        assert!(report.starts_with(
+            "### :x: 1 of 2 tests failed\n\n| Tests | OK | KO | Not executed | With warnings |\n|---:|---:|---:|---:|---:|\n| 2 | 1 | 1 | 0 | 0 |\n"
+        ));
+        assert!(!report.contains("Tracking OK"));
+        assert!(report.contains("<summary>:x: Test #2 (Tracking KO)</summary>"));
+        assert!(report.contains("| Turn | 2 of 2 |\n| User says | ` track 123 ` |"));
+        assert!(report.contains("| Expected intent | ` Tracking\\|CS\\|3 ` |"));
+        assert!(report.contains("| Actual intent | ` Tracking\\|CS\\|3 ` |"));
+        assert!(report.contains("| Failing check | ` queryResult.action = express_track ` |"));
+        assert!(report.contains("```\nExpected value: express_track, real value: track\n```"));
+
+        let mut repeated_test = tests[1].clone();
+        let mut intents = BTreeMap::new();
+        intents.insert("Welcome".to_owned(), 1);
+        intents.insert("Tracking|CS|3".to_owned(), 2);
+        repeated_test.repetitions = Some(TestRepetitions {
+            count: 3,
+            passed: 1,
+            intents: vec![BTreeMap::new(), intents],
+        });
+        let report = MarkdownResultReporter::get_report_md(&[repeated_test]);
+        assert!(report.contains("| Repetitions | passed 1 of 3 repetitions (33%) |"));
+        assert!(report.contains("| Observed intents | ` Tracking\\|CS\\|3 (2), Welcome (1) ` |"));
+
+        let report = MarkdownResultReporter::get_report_md(&[tests[0].clone()]);
+        assert!(report.starts_with("### :white_check_mark: All 1 tests passed"));
+        assert!(!report.contains("<details>"));
+
+        let report = MarkdownResultReporter::get_report_md(&[]);
+        assert!(report.starts_with("### :warning: No tests executed"));
+    }
+
+    #[test]
+    fn test_markdown_escaping() {
+        let mut test = test_with_result(
+            "Tracking <b>KO</b>",
+            TestAssertionResult::KoBackendError(new_service_call_error(
+                ErrorKind::GenericError("N/A".to_owned()),
+                "Unexpected response:\n```\n{}\n```".to_owned(),
+                None,
+                None,
+            )),
+        );
+        test.assertions[0].test_assertion_result = None;
+
+        let report = MarkdownResultReporter::get_report_md(&[test]);
+        assert!(report.contains("<summary>:x: Test #1 (Tracking &lt;b&gt;KO&lt;/b&gt;)</summary>"));
+        assert!(report.contains("| Turn | 2 of 2 |"));
+        assert!(report.contains("````\nUnexpected response:\n```\n{}\n```\n````"));
+    }
+}
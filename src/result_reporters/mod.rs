@@ -1,7 +1,282 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use crate::errors::{new_error_from, Error, ErrorKind, Result};
+use crate::history::load_runs;
+use crate::yaml_parser::Test;
+
+mod classification_reporter;
+mod diff_reporter;
+mod history_reporter;
 mod html_reporter;
 mod json_reporter;
+mod markdown_reporter;
 mod stdout_reporter;
+mod summary;
 
+pub(crate) use classification_reporter::get_detected_intent;
+pub use classification_reporter::ClassificationResultReporter;
+pub use diff_reporter::{DiffResultReporter, IntentChange, ReportDiff, TestStatusChange};
+pub use history_reporter::HistoryResultReporter;
 pub use html_reporter::HtmlResultReporter;
-pub use json_reporter::JsonResultReporter;
+pub use json_reporter::{JsonLinesResultReporter, JsonResultReporter};
+pub use markdown_reporter::MarkdownResultReporter;
 pub use stdout_reporter::StdoutResultReporter;
+pub use summary::TestResultsSummary;
+
+// reporter is notified about every finished test (in order of completion), streaming reporters
+// write results immediately, the others write the whole report at the end of execution
+pub trait ResultReporter {
+    fn begin(&mut self, _test_count: usize) -> Result<()> {
+        Ok(())
+    }
+
+    fn test_finished(&mut self, _test: &Test) -> Result<()> {
+        Ok(())
+    }
+
+    // all executed tests in order of test suite
+    fn end(&mut self, tests: &[Test]) -> Result<()>;
+}
+
+// creates reporter writing into given file, path is None when report is printed to standard output
+pub type ResultReporterFactory =
+    Box<dyn Fn(Option<&Path>) -> Result<Box<dyn ResultReporter>> + Send + Sync>;
+
+// text inserted into html reports (test names, utterances, intents, error messages) can contain markup
+pub(crate) fn escape_html(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+// bootstrap page shared by all html reports
+const HTML_PAGE: &str = r#"
+    <!doctype html>
+    <html lang="en">
+        <head>
+        <!-- Required meta tags -->
+        <meta charset="utf-8">
+        <meta name="viewport" content="width=device-width, initial-scale=1, shrink-to-fit=no">
+        <!-- Bootstrap CSS -->
+        <link rel="stylesheet" href="https://maxcdn.bootstrapcdn.com/bootstrap/4.0.0/css/bootstrap.min.css" integrity="sha384-Gn5384xqQ1aoWXA+058RXPxPg6fy4IWvTNh0E263XmFcJlSAwiGgFAW/dAiS6JXm" crossorigin="anonymous">
+        <title>{__title__}</title>
+        </head>
+        <body>
+        <!-- Optional JavaScript -->
+        <!-- jQuery first, then Popper.js, then Bootstrap JS -->
+        <script src="https://code.jquery.com/jquery-3.2.1.slim.min.js" integrity="sha384-KJ3o2DKtIkvYIK3UENzmM7KCkRr/rE9/Qpg6aAZGJwFDMVNA/GpGFF93hXpG5KkN" crossorigin="anonymous"></script>
+        <script src="https://cdnjs.cloudflare.com/ajax/libs/popper.js/1.12.9/umd/popper.min.js" integrity="sha384-ApNbgh9B+Y1QKtv3Rn7W3mgPxhU9K/ScQsAP7hUibX39j7fakFPskvXusvfa0b4Q" crossorigin="anonymous"></script>
+        <script src="https://maxcdn.bootstrapcdn.com/bootstrap/4.0.0/js/bootstrap.min.js" integrity="sha384-JZR6Spejh4U02d8jOt6vLEHfe/JQGiRRSQQxSfFWpi1MquVdAyjUar5+76PVCmYl" crossorigin="anonymous"></script>
+        {__body__}
+        </body>
+    </html>
+"#;
+
+// wraps already escaped report body into html page
+pub(crate) fn html_page(title: &str, body: &str) -> String {
+    HTML_PAGE
+        .replace("{__title__}", &escape_html(title))
+        .replace("{__body__}", body)
+}
+
+fn reporter_error(message: String) -> Error {
+    new_error_from(ErrorKind::GenericError(message))
+}
+
+fn required_path(kind: &str, path: Option<&Path>) -> Result<PathBuf> {
+    path.map(|path| path.to_path_buf()).ok_or_else(|| {
+        reporter_error(format!(
+            "Report {} requires output file, use {}=<FILE>",
+            kind, kind
+        ))
+    })
+}
+
+// reporters by kind, selected by report specification <kind>=<path> (see --report option)
+pub struct ResultReporterRegistry {
+    factories: HashMap<String, ResultReporterFactory>,
+}
+
+impl Default for ResultReporterRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ResultReporterRegistry {
+    pub fn new() -> Self {
+        ResultReporterRegistry {
+            factories: HashMap::new(),
+        }
+    }
+
+    pub fn with_default_reporters() -> Self {
+        let mut registry = ResultReporterRegistry::new();
+        registry.register("stdout", |_| Ok(Box::new(StdoutResultReporter)));
+        registry.register("html", |path| {
+            Ok(Box::new(HtmlResultReporter::new(&required_path(
+                "html", path,
+            )?)))
+        });
+        registry.register("json", |path| {
+            Ok(Box::new(JsonResultReporter::new(&required_path(
+                "json", path,
+            )?)))
+        });
+        registry.register("jsonl", |path| {
+            Ok(Box::new(JsonLinesResultReporter::new(&required_path(
+                "jsonl", path,
+            )?)))
+        });
+        registry.register("md", |path| {
+            Ok(Box::new(MarkdownResultReporter::new(&required_path(
+                "md", path,
+            )?)))
+        });
+        registry.register("history", |path| {
+            Ok(Box::new(HistoryResultReporter::new(&required_path(
+                "history", path,
+            )?)))
+        });
+        // without path classification results are printed to standard output
+        registry.register("classification", |path| {
+            Ok(Box::new(ClassificationResultReporter::new(path)))
+        });
+        registry
+    }
+
+    // history reporter stores results of the run under suite name,
+    // tests with the same name in different suites have separate histories
+    pub fn use_suite_name(&mut self, suite_name: &str) {
+        let suite_name = suite_name.to_owned();
+        self.register("history", move |path| {
+            Ok(Box::new(
+                HistoryResultReporter::new(&required_path("history", path)?)
+                    .with_suite_name(&suite_name),
+            ))
+        });
+    }
+
+    // html report shows history of every test of the suite (previous runs followed by current one),
+    // history is read before the run and only runs displayed in the report are loaded
+    pub fn use_history(&mut self, history_dir: &Path, suite_name: &str) -> Result<()> {
+        let previous_runs = load_runs(history_dir, html_reporter::HISTORY_SPARKLINE_RUNS - 1)?;
+        let suite_name = suite_name.to_owned();
+        self.register("html", move |path| {
+            Ok(Box::new(
+                HtmlResultReporter::new(&required_path("html", path)?)
+                    .with_history(previous_runs.clone(), &suite_name),
+            ))
+        });
+        Ok(())
+    }
+
+    // registering existing kind replaces its reporter
+    pub fn register<F>(&mut self, kind: &str, factory: F)
+    where
+        F: Fn(Option<&Path>) -> Result<Box<dyn ResultReporter>> + Send + Sync + 'static,
+    {
+        self.factories.insert(kind.to_owned(), Box::new(factory));
+    }
+
+    pub fn kinds(&self) -> Vec<&str> {
+        let mut kinds: Vec<&str> = self.factories.keys().map(|kind| kind.as_str()).collect();
+        kinds.sort();
+        kinds
+    }
+
+    // report specification is either <kind>=<path> or just <kind>
+    pub fn create(&self, report_spec: &str) -> Result<Box<dyn ResultReporter>> {
+        let (kind, path) = match report_spec.find('=') {
+            Some(idx) => (
+                &report_spec[..idx],
+                Some(Path::new(&report_spec[idx + 1..])),
+            ),
+            None => (report_spec, None),
+        };
+
+        match self.factories.get(kind) {
+            Some(factory) => factory(path),
+            None => Err(reporter_error(format!(
+                "Unknown report kind '{}'. Available kinds: {}",
+                kind,
+                self.kinds().join(", ")
+            ))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct CountingReporter {
+        finished: usize,
+    }
+
+    impl ResultReporter for CountingReporter {
+        fn test_finished(&mut self, _test: &Test) -> Result<()> {
+            self.finished += 1;
+            Ok(())
+        }
+
+        fn end(&mut self, _tests: &[Test]) -> Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_escape_html() {
+        assert_eq!(
+            escape_html(r#"<b>"Tom" & 'Jerry'</b>"#),
+            "&lt;b&gt;&quot;Tom&quot; &amp; &#39;Jerry&#39;&lt;/b&gt;"
+        );
+        assert_eq!(escape_html("plain text"), "plain text");
+    }
+
+    #[test]
+    fn test_reporter_registry() {
+        let mut registry = ResultReporterRegistry::with_default_reporters();
+        assert_eq!(
+            registry.kinds(),
+            vec![
+                "classification",
+                "history",
+                "html",
+                "json",
+                "jsonl",
+                "md",
+                "stdout"
+            ]
+        );
+        assert!(registry.create("stdout").is_ok());
+        assert!(registry.create("classification").is_ok());
+        assert!(registry.create("html=c:/tmp/report.html").is_ok());
+
+        let err = registry.create("json").err().unwrap();
+        assert_eq!(
+            err.message,
+            "GenericError: Report json requires output file, use json=<FILE>"
+        );
+        let err = registry.create("xml=c:/tmp/report.xml").err().unwrap();
+        assert_eq!(
+            err.message,
+            "GenericError: Unknown report kind 'xml'. Available kinds: classification, history, html, json, jsonl, md, stdout"
+        );
+
+        registry.register("counting", |_| {
+            Ok(Box::new(CountingReporter { finished: 0 }))
+        });
+        assert!(registry.create("counting").is_ok());
+    }
+}
@@ -2,10 +2,24 @@ use ansi_term::Colour::{Green, Red, Yellow};
 use prettytable::Table;
 use prettytable::{cell, row}; // macros
 
-use crate::yaml_parser::{Test, TestAssertionResult, TestResult};
+use crate::errors::Result;
+use crate::result_reporters::{ResultReporter, TestResultsSummary};
+use crate::yaml_parser::{
+    Test, TestAssertion, TestAssertionResponseCheckValue, TestAssertionResult, TestResult,
+};
 
 pub struct StdoutResultReporter;
 
+impl ResultReporter for StdoutResultReporter {
+    fn end(&mut self, tests: &[Test]) -> Result<()> {
+        println!();
+        StdoutResultReporter::report_test_results(tests);
+        StdoutResultReporter::print_summary(tests);
+        println!();
+        Ok(())
+    }
+}
+
 impl StdoutResultReporter {
     fn get_ok_str() -> String {
         Green.paint("OK").to_string()
@@ -23,14 +37,77 @@ impl StdoutResultReporter {
         Yellow.paint("??").to_string()
     }
 
+    fn get_warn_str() -> String {
+        Yellow.paint("WARN").to_string()
+    }
+
     fn get_not_executed_str() -> String {
         Yellow.paint("Not executed").to_string()
     }
 
+    // warning messages of all assertions, displayed in test header row
+    fn get_warnings_str(test: &Test) -> String {
+        test.assertions
+            .iter()
+            .flat_map(|assertion| assertion.warnings.iter())
+            .map(|warning| {
+                "\n".to_owned()
+                    + &Yellow
+                        .paint(format!("Warning: {}", warning.message()))
+                        .to_string()
+            })
+            .collect()
+    }
+
+    // pass rate of repeatedly executed test followed by intents observed in every turn
+    fn get_repetitions_str(test: &Test) -> String {
+        match &test.repetitions {
+            Some(repetitions) => {
+                let mut repetitions_str = format!(" - {}", repetitions.pass_rate_display());
+                for (turn, assertion) in test.assertions.iter().enumerate() {
+                    repetitions_str.push_str(&format!(
+                        "\nTurn {} ({}): {}",
+                        turn + 1,
+                        assertion.user_says,
+                        repetitions.intent_distribution_display(turn)
+                    ));
+                }
+                repetitions_str
+            }
+            None => String::new(),
+        }
+    }
+
+    // status of response check which was evaluated before assertion failed (or all checks of passed assertion)
+    fn get_check_status_str(assertion: &TestAssertion, idx: usize) -> String {
+        match assertion.response_check_warning(idx) {
+            Some(_) => StdoutResultReporter::get_warn_str(),
+            None => StdoutResultReporter::get_ok_str(),
+        }
+    }
+
+    // intent match status followed by observed intent detection confidence
+    fn get_intent_status_str(status: String, assertion: &TestAssertion) -> String {
+        let status = if assertion.has_intent_warning() {
+            StdoutResultReporter::get_warn_str()
+        } else {
+            status
+        };
+        match assertion.detected_confidence {
+            Some(_) => status + "\n" + &assertion.detected_confidence_display(),
+            None => status,
+        }
+    }
+
     pub fn get_test_result_str(test: &Test) -> String {
         let test_result_str;
         if let Some(test_result) = &test.test_result {
             match test_result {
+                TestResult::Ok if test.has_warnings() => {
+                    test_result_str = StdoutResultReporter::get_ok_str()
+                        + " "
+                        + &StdoutResultReporter::get_warn_str()
+                }
                 TestResult::Ok => test_result_str = StdoutResultReporter::get_ok_str(),
                 TestResult::Ko => test_result_str = StdoutResultReporter::get_ko_str(),
             }
@@ -41,20 +118,45 @@ impl StdoutResultReporter {
         test_result_str
     }
 
-    pub fn report_test_results(tests: &Vec<Test>) {
+    // passed assertion with failed warning checks lists status of every check
+    fn get_warning_checks_str(assertion: &TestAssertion) -> String {
+        assertion
+            .response_checks
+            .iter()
+            .enumerate()
+            .map(|(idx, response_check)| {
+                format!(
+                    "{} {}",
+                    StdoutResultReporter::get_check_status_str(assertion, idx),
+                    response_check
+                )
+            })
+            .collect::<Vec<String>>()
+            .join("\n")
+    }
+
+    pub fn print_summary(tests: &[Test]) {
+        println!("{}", TestResultsSummary::from_tests(tests));
+    }
+
+    pub fn report_test_results(tests: &[Test]) {
         let mut test_tables: Vec<Table> = vec![];
 
         for test in tests {
             let mut test_table = Table::new();
-            let test_result_str = StdoutResultReporter::get_test_result_str(test);
+            let test_result_str = StdoutResultReporter::get_test_result_str(test)
+                + &StdoutResultReporter::get_repetitions_str(test)
+                + &StdoutResultReporter::get_warnings_str(test);
 
-            let test_result = test.get_test_error();
+            let test_result = test.get_test_error().map(|(_, test_error)| test_error);
 
             // add header row with test name status string (OK/KO) + potential error message (either intent name mismatch or assertion check error)
             match test_result {
                 Some(some_test_result) => {
                     match some_test_result {
-                        TestAssertionResult::KoIntentNameMismatch(err) => {
+                        TestAssertionResult::KoIntentNameMismatch(err)
+                        | TestAssertionResult::KoConfidenceMismatch(err)
+                        | TestAssertionResult::KoBackendError(err) => {
                             test_table.add_row(row![
                                 test.name.clone() + " - " + &test_result_str + "\n" + &err.message
                             ]);
@@ -88,11 +190,12 @@ impl StdoutResultReporter {
             test_table_assertions.add_row(
               row![
                 assertion.user_says.clone(),
-                assertion.bot_responds_with.join("\n"),
-                StdoutResultReporter::get_ok_str(),
-                match assertion.response_checks.len() {
-                  0 => StdoutResultReporter::get_na_str(),
-                  _ => StdoutResultReporter::get_ok_str()
+                assertion.bot_responds_with_display("\n"),
+                StdoutResultReporter::get_intent_status_str(StdoutResultReporter::get_ok_str(), assertion),
+                match (assertion.response_checks.len(), assertion.warnings.len()) {
+                  (0, _) => StdoutResultReporter::get_na_str(),
+                  (_, 0) => StdoutResultReporter::get_ok_str(),
+                  _ => StdoutResultReporter::get_warning_checks_str(assertion)
                 },
                 "" // if everything is OK do not include backed response in std out report,
                    // it will be collapsed in html report
@@ -100,14 +203,26 @@ impl StdoutResultReporter {
               ]
             );
           },
-          TestAssertionResult::KoIntentNameMismatch(err) => {
+          TestAssertionResult::KoIntentNameMismatch(err) | TestAssertionResult::KoConfidenceMismatch(err) => {
+            test_table_assertions.add_row(
+              row![
+                assertion.user_says.clone(),
+                assertion.bot_responds_with_display("\n"), 
+                StdoutResultReporter::get_intent_status_str(StdoutResultReporter::get_ko_str(), assertion),
+                StdoutResultReporter::get_not_executed_str(),
+                err.backend_response.as_deref().unwrap_or("") // TBD: make this configurable!
+              ]
+            );
+            break; // do not continue with any other assertion!
+          },
+          TestAssertionResult::KoBackendError(_) => {
             test_table_assertions.add_row(
               row![
                 assertion.user_says.clone(),
-                assertion.bot_responds_with.join("\n"), 
+                assertion.bot_responds_with_display("\n"),
                 StdoutResultReporter::get_ko_str(),
                 StdoutResultReporter::get_not_executed_str(),
-                err.backend_response.as_ref().unwrap() // TBD: make this configurable!
+                "" // NLP provider call failed, there is no response
               ]
             );
             break; // do not continue with any other assertion!
@@ -120,11 +235,25 @@ impl StdoutResultReporter {
             for idx in 0..*assertion_check_idx + 1 {
               let response_check = &assertion.response_checks[idx];
 
-              let res_str;
-              if idx == *assertion_check_idx {
-                res_str = StdoutResultReporter::get_ko_str()
+              let res_str = if idx == *assertion_check_idx {
+                StdoutResultReporter::get_ko_str()
               } else {
-                res_str = StdoutResultReporter::get_ok_str()
+                StdoutResultReporter::get_check_status_str(assertion, idx)
+              };
+
+              if let (TestAssertionResponseCheckValue::GroupVal(_), Some(tree_rows)) = (&response_check.value, assertion.check_group_rows(idx)) {
+                // check group is rendered as tree with status of every nested check recorded during execution
+                for tree_row in tree_rows {
+                  test_table_assertion_resp_checks.add_row(
+                    row![
+                      "  ".repeat(tree_row.depth) + &tree_row.expression,
+                      tree_row.operator,
+                      tree_row.value,
+                      if tree_row.ok { StdoutResultReporter::get_ok_str() } else { StdoutResultReporter::get_ko_str() }
+                    ]
+                  );
+                }
+                continue;
               }
 
               test_table_assertion_resp_checks.add_row(
@@ -140,10 +269,10 @@ impl StdoutResultReporter {
             test_table_assertions.add_row(
               row![
                 assertion.user_says.clone(),
-                assertion.bot_responds_with.join("\n"), 
-                StdoutResultReporter::get_ok_str(),
+                assertion.bot_responds_with_display("\n"), 
+                StdoutResultReporter::get_intent_status_str(StdoutResultReporter::get_ok_str(), assertion),
                 test_table_assertion_resp_checks,
-                err.backend_response.as_ref().unwrap() // TBD: make this configurable!
+                err.backend_response.as_deref().unwrap_or("") // TBD: make this configurable!
               ]
 
             );
@@ -402,11 +531,11 @@ mod tests {
         table_test3.add_row(row![table_assertion3]);
 
         table_test1.printstd();
-        println!("");
-        println!("");
+        println!();
+        println!();
         table_test2.printstd();
-        println!("");
-        println!("");
+        println!();
+        println!();
         table_test3.printstd();
     }
 }
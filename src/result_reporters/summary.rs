@@ -0,0 +1,96 @@
+use std::fmt;
+
+use crate::yaml_parser::{Test, TestResult};
+
+// overall counts of executed tests, tests with warnings are counted as OK as well
+#[derive(Debug, PartialEq)]
+pub struct TestResultsSummary {
+    pub total: usize,
+    pub ok: usize,
+    pub ko: usize,
+    pub not_executed: usize,
+    pub with_warnings: usize, // number of tests with at least one warning
+    pub warnings: usize,      // number of all warnings
+}
+
+impl TestResultsSummary {
+    pub fn from_tests(tests: &[Test]) -> TestResultsSummary {
+        let mut summary = TestResultsSummary {
+            total: tests.len(),
+            ok: 0,
+            ko: 0,
+            not_executed: 0,
+            with_warnings: 0,
+            warnings: 0,
+        };
+
+        for test in tests {
+            match test.test_result {
+                Some(TestResult::Ok) => summary.ok += 1,
+                Some(TestResult::Ko) => summary.ko += 1,
+                None => summary.not_executed += 1,
+            }
+
+            if test.has_warnings() {
+                summary.with_warnings += 1;
+            }
+            for assertion in &test.assertions {
+                summary.warnings += assertion.warnings.len();
+            }
+        }
+
+        summary
+    }
+}
+
+impl fmt::Display for TestResultsSummary {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "Tests: {}, OK: {}, KO: {}, not executed: {}, with warnings: {} (warnings: {})",
+            self.total, self.ok, self.ko, self.not_executed, self.with_warnings, self.warnings
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::errors::{new_error, ErrorKind};
+    use crate::yaml_parser::{TestAssertion, TestAssertionWarning};
+
+    #[test]
+    fn test_summary() {
+        let mut ok_test = Test::new("ok".to_owned(), None, "en".to_owned());
+        ok_test.test_result = Some(TestResult::Ok);
+
+        let mut warning_test = Test::new("warning".to_owned(), None, "en".to_owned());
+        let mut assertion = TestAssertion::new("hello".to_owned(), vec![], vec![]);
+        for idx in 0..2 {
+            assertion
+                .warnings
+                .push(TestAssertionWarning::ResponseCheckError(
+                    new_error(
+                        ErrorKind::InvalidTestAssertionResponseCheckEvaluation,
+                        "foo".to_owned(),
+                        None,
+                    ),
+                    idx,
+                ));
+        }
+        warning_test.assertions.push(assertion);
+        warning_test.test_result = Some(TestResult::Ok);
+
+        let mut ko_test = Test::new("ko".to_owned(), None, "en".to_owned());
+        ko_test.test_result = Some(TestResult::Ko);
+
+        let not_executed_test = Test::new("not executed".to_owned(), None, "en".to_owned());
+
+        let summary =
+            TestResultsSummary::from_tests(&[ok_test, warning_test, ko_test, not_executed_test]);
+        assert_eq!(
+            summary.to_string(),
+            "Tests: 4, OK: 2, KO: 1, not executed: 1, with warnings: 1 (warnings: 2)"
+        );
+    }
+}
@@ -0,0 +1,679 @@
+use std::collections::{BTreeMap, HashMap};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc;
+use std::sync::Arc;
+
+use crate::errors::{new_error_from, Error, ErrorKind, Result};
+use crate::result_reporters::{get_detected_intent, ResultReporter};
+use crate::suite_executor::{TestExecutorRegistry, TestSuiteExecutor};
+use crate::test_executors::TestExecutor;
+use crate::thread_pool::ThreadPool;
+use crate::yaml_parser::{Test, TestRepetitions, TestResult, TestSuite, TestSuiteSpec};
+
+#[derive(Debug, Clone)]
+pub struct RunnerOptions {
+    pub threadpool_size: usize,
+    pub update_snapshots: bool,
+    // every test is executed repeatedly (each time as new conversation) and passes
+    // when ratio of passed repetitions reaches min_pass_rate. Repetitions run concurrently,
+    // i.e. they can only compare snapshots, repeat cannot be combined with update_snapshots
+    pub repeat: usize,
+    pub min_pass_rate: f64,
+}
+
+impl Default for RunnerOptions {
+    fn default() -> Self {
+        RunnerOptions {
+            threadpool_size: 4,
+            update_snapshots: false,
+            repeat: 1,
+            min_pass_rate: 1.0,
+        }
+    }
+}
+
+// progress of suite execution reported to callbacks registered by Runner::on_progress,
+// every repetition of repeatedly executed test is reported as separate test
+#[derive(Debug)]
+pub enum RunnerEvent<'a> {
+    Started {
+        test_count: usize,
+    },
+    TestFinished {
+        test: &'a Test,
+        finished_count: usize,
+        test_count: usize,
+    },
+    Finished {
+        finished_count: usize,
+        test_count: usize,
+    },
+}
+
+// executed tests together with errors of reporters. Failing reporter stops neither the execution
+// nor other reporters, its errors are returned in order of occurrence
+#[derive(Debug)]
+pub struct RunResult {
+    pub tests: Vec<Test>,
+    pub reporter_errors: Vec<Error>,
+}
+
+// every reporter is called even if preceding reporters failed
+fn call_reporters<'r, F>(
+    reporters: &mut Vec<Box<dyn ResultReporter + 'r>>,
+    errors: &mut Vec<Error>,
+    mut call: F,
+) where
+    F: FnMut(&mut (dyn ResultReporter + 'r)) -> Result<()>,
+{
+    for reporter in reporters.iter_mut() {
+        if let Err(err) = call(reporter.as_mut()) {
+            errors.push(err);
+        }
+    }
+}
+
+type ProgressCallback<'a> = Box<dyn FnMut(&RunnerEvent) + 'a>;
+
+// runs test suite in thread pool and returns executed tests, used by command line interface
+// as well as by applications embedding gdf_testing library
+pub struct Runner<'a> {
+    options: RunnerOptions,
+    registry: TestExecutorRegistry,
+    callbacks: Vec<ProgressCallback<'a>>,
+    reporters: Vec<Box<dyn ResultReporter + 'a>>,
+    running: Arc<AtomicBool>,
+}
+
+impl<'a> Runner<'a> {
+    pub fn new(options: RunnerOptions) -> Self {
+        Runner {
+            options,
+            registry: TestExecutorRegistry::new(),
+            callbacks: vec![],
+            reporters: vec![],
+            running: Arc::new(AtomicBool::new(true)),
+        }
+    }
+
+    // see TestExecutorRegistry
+    pub fn register_executor_factory<F>(&mut self, suite_type: &str, factory: F)
+    where
+        F: Fn(&TestSuiteSpec, Test, mpsc::Sender<Test>) -> Result<Box<dyn TestExecutor + Send>>
+            + Send
+            + Sync
+            + 'static,
+    {
+        self.registry.register(suite_type, factory);
+    }
+
+    pub fn on_progress<F>(&mut self, callback: F)
+    where
+        F: FnMut(&RunnerEvent) + 'a,
+    {
+        self.callbacks.push(Box::new(callback));
+    }
+
+    // reporters are notified in order of registration, after progress callbacks. Repeatedly
+    // executed test is reported once, when all its repetitions are finished
+    pub fn add_reporter(&mut self, reporter: Box<dyn ResultReporter + 'a>) {
+        self.reporters.push(reporter);
+    }
+
+    // setting the flag to false stops execution (e.g. on CTRL+C), running tests are completed
+    // but no more new tests are started. Run then returns tests finished so far
+    pub fn running_flag(&self) -> Arc<AtomicBool> {
+        self.running.clone()
+    }
+
+    fn notify(&mut self, event: RunnerEvent) {
+        for callback in self.callbacks.iter_mut() {
+            callback(&event);
+        }
+    }
+
+    pub fn run(&mut self, suite: TestSuite) -> Result<RunResult> {
+        if self.options.threadpool_size == 0 {
+            return Err(new_error_from(ErrorKind::GenericError(
+                "Thread pool size must be greater than zero".to_owned(),
+            )));
+        }
+
+        // concurrent repetitions would rewrite the same snapshot files
+        if self.options.repeat > 1 && self.options.update_snapshots {
+            return Err(new_error_from(ErrorKind::GenericError(
+                "Snapshots cannot be updated while tests are executed repeatedly".to_owned(),
+            )));
+        }
+
+        let mut suite = suite;
+        if self.options.update_snapshots {
+            suite.set_update_snapshots(true);
+        }
+
+        // repetitions of the same test follow each other, i.e. execution_id / repeat
+        // is index of test within the suite. Every executor starts new conversation
+        let repeat = self.options.repeat.max(1);
+        let min_pass_rate = self.options.min_pass_rate;
+        if repeat > 1 {
+            suite.tests = suite
+                .tests
+                .iter()
+                .flat_map(|test| std::iter::repeat_n(test, repeat).cloned())
+                .collect();
+        }
+
+        // create test suite executor and underlying test executor jobs
+        let suite_executor = TestSuiteExecutor::with_registry(suite, &self.registry)?;
+        let test_count = suite_executor.test_executors.len();
+        let pool = ThreadPool::new(self.options.threadpool_size, self.running.clone());
+
+        self.notify(RunnerEvent::Started { test_count });
+        let mut reporter_errors = vec![];
+        call_reporters(&mut self.reporters, &mut reporter_errors, |reporter| {
+            reporter.begin(test_count / repeat)
+        });
+
+        // kick off execution of all test executor jobs by thread pool
+        for mut test_executor in suite_executor.test_executors {
+            pool.execute(move || loop {
+                let assertion_exec_result = test_executor.execute_next_assertion();
+                if assertion_exec_result.is_none() {
+                    break;
+                }
+            });
+        }
+
+        // executed test with results will be returned by threadpool via mpsc channel
+        let mut executed_tests = vec![];
+        let mut pending_repetitions: HashMap<usize, Vec<Test>> = HashMap::new();
+        for i in 0..test_count {
+            let executed_test = match suite_executor.rx.recv() {
+                Ok(executed_test) => executed_test,
+                // jobs dropped by stopped thread pool close the channel
+                Err(_) if !self.running.load(Ordering::SeqCst) => break,
+                Err(_) => {
+                    return Err(new_error_from(ErrorKind::GenericError(
+                        "test results receiving channel broken".to_string(),
+                    )))
+                }
+            };
+            self.notify(RunnerEvent::TestFinished {
+                test: &executed_test,
+                finished_count: i + 1,
+                test_count,
+            });
+
+            let test_idx = executed_test.execution_id.unwrap_or(0) / repeat;
+            let repetitions = pending_repetitions.entry(test_idx).or_insert(vec![]);
+            repetitions.push(executed_test);
+            if repetitions.len() < repeat {
+                continue;
+            }
+
+            // safe to unwrap, repetitions were inserted above
+            let repetitions = pending_repetitions.remove(&test_idx).unwrap();
+            let executed_test = aggregate_repetitions(repetitions, repeat, min_pass_rate);
+            call_reporters(&mut self.reporters, &mut reporter_errors, |reporter| {
+                reporter.test_finished(&executed_test)
+            });
+            executed_tests.push(executed_test);
+        }
+        let finished_count = executed_tests.len() * repeat
+            + pending_repetitions
+                .values()
+                .map(|repetitions| repetitions.len())
+                .sum::<usize>();
+
+        // stopped execution leaves some tests with incomplete repetitions, they are evaluated
+        // from repetitions finished so far
+        let mut incomplete_tests: Vec<(usize, Vec<Test>)> =
+            pending_repetitions.into_iter().collect();
+        incomplete_tests.sort_by_key(|(test_idx, _)| *test_idx);
+        for (_, repetitions) in incomplete_tests {
+            let executed_test = aggregate_repetitions(repetitions, repeat, min_pass_rate);
+            call_reporters(&mut self.reporters, &mut reporter_errors, |reporter| {
+                reporter.test_finished(&executed_test)
+            });
+            executed_tests.push(executed_test);
+        }
+
+        self.notify(RunnerEvent::Finished {
+            finished_count,
+            test_count,
+        });
+
+        // threads finish in random order, keep the order of suite
+        executed_tests.sort_by_key(|test| test.execution_id);
+        call_reporters(&mut self.reporters, &mut reporter_errors, |reporter| {
+            reporter.end(&executed_tests)
+        });
+        Ok(RunResult {
+            tests: executed_tests,
+            reporter_errors,
+        })
+    }
+}
+
+fn is_ok(test: &Test) -> bool {
+    matches!(test.test_result, Some(TestResult::Ok))
+}
+
+// merges repetitions of one test. Assertion results are taken from the first repetition
+// with the same result as the aggregated one (i.e. failing repetition is shown for failed test)
+fn aggregate_repetitions(repetitions: Vec<Test>, repeat: usize, min_pass_rate: f64) -> Test {
+    let mut repetitions = repetitions;
+    if repeat == 1 {
+        // safe to unwrap, there is always at least one repetition
+        return repetitions.pop().unwrap();
+    }
+
+    let mut intents: Vec<BTreeMap<String, usize>> = vec![];
+    for test in &repetitions {
+        for (turn, assertion) in test.assertions.iter().enumerate() {
+            if intents.len() <= turn {
+                intents.push(BTreeMap::new());
+            }
+            if let Some(intent) = get_detected_intent(assertion) {
+                let count = intents[turn].entry(intent).or_insert(0);
+                *count += 1;
+            }
+        }
+    }
+    let test_repetitions = TestRepetitions {
+        count: repetitions.len(),
+        passed: repetitions.iter().filter(|test| is_ok(test)).count(),
+        intents,
+    };
+    let passes = test_repetitions.pass_rate() >= min_pass_rate;
+
+    let idx = repetitions
+        .iter()
+        .position(|test| is_ok(test) == passes)
+        .unwrap_or(0);
+    let mut test = repetitions.swap_remove(idx);
+    test.execution_id = test.execution_id.map(|execution_id| execution_id / repeat);
+    test.test_result = Some(if passes {
+        TestResult::Ok
+    } else {
+        TestResult::Ko
+    });
+    test.repetitions = Some(test_repetitions);
+    test
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_executors::{IntentExpressions, NlpResponse};
+    use crate::yaml_parser::{TestAssertion, TestAssertionResult, TestResult};
+    use yaml_rust::YamlLoader;
+
+    // executor of custom suite type, bot answers every utterance by intent of the same name.
+    // Only required methods are implemented (warnings, check groups and confidence are not kept)
+    struct EchoTestExecutor {
+        test: Test,
+        next_assertion: usize,
+        tx: mpsc::Sender<Test>,
+    }
+
+    impl TestExecutor for EchoTestExecutor {
+        fn move_to_next_assertion(&mut self) {
+            self.next_assertion += 1;
+        }
+
+        fn move_behind_last_assertion(&mut self) {
+            self.next_assertion = self.test.assertions.len();
+        }
+
+        fn get_assertions(&self) -> &Vec<TestAssertion> {
+            &self.test.assertions
+        }
+
+        fn set_test_result(&mut self, test_result: TestResult) {
+            self.test.test_result = Some(test_result);
+        }
+
+        fn set_test_assertion_result(&mut self, test_assertion_result: TestAssertionResult) {
+            let idx = self.get_next_assertion_no();
+            self.test.assertions[idx].test_assertion_result = Some(test_assertion_result);
+        }
+
+        fn get_next_assertion_no(&self) -> usize {
+            self.next_assertion
+        }
+
+        fn send_test_results(&self) -> Result<()> {
+            self.tx.send(self.test.clone())?;
+            Ok(())
+        }
+
+        fn invoke_nlp(&self, assertion: &TestAssertion) -> Result<NlpResponse> {
+            let response = serde_json::json!({
+                "queryResult": { "intent": { "displayName": assertion.user_says } }
+            })
+            .to_string();
+            Ok(NlpResponse::new(
+                assertion,
+                response,
+                &IntentExpressions::new("queryResult"),
+            ))
+        }
+    }
+
+    struct RecordingReporter<'a> {
+        calls: &'a mut Vec<String>,
+    }
+
+    impl<'a> ResultReporter for RecordingReporter<'a> {
+        fn begin(&mut self, test_count: usize) -> Result<()> {
+            self.calls.push(format!("begin {}", test_count));
+            Ok(())
+        }
+
+        fn test_finished(&mut self, _test: &Test) -> Result<()> {
+            self.calls.push("test".to_string());
+            Ok(())
+        }
+
+        fn end(&mut self, tests: &[Test]) -> Result<()> {
+            let names: Vec<&str> = tests.iter().map(|test| test.name.as_str()).collect();
+            self.calls.push(format!("end {}", names.join(",")));
+            Ok(())
+        }
+    }
+
+    struct FailingReporter;
+
+    impl ResultReporter for FailingReporter {
+        fn begin(&mut self, _test_count: usize) -> Result<()> {
+            Err(new_error_from(ErrorKind::GenericError(
+                "begin failed".to_string(),
+            )))
+        }
+
+        fn test_finished(&mut self, _test: &Test) -> Result<()> {
+            Ok(())
+        }
+
+        fn end(&mut self, _tests: &[Test]) -> Result<()> {
+            Err(new_error_from(ErrorKind::GenericError(
+                "end failed".to_string(),
+            )))
+        }
+    }
+
+    // suite of custom type, executed by executor registered for type name
+    fn echo_suite() -> Result<TestSuite> {
+        const YAML: &str = "
+        suite-spec:
+            name: 'Echo'
+            type: 'Echo'
+            config:
+              - echo_delay: '0'
+        tests:
+            - name: 'Hello'
+              assertions:
+                - userSays: 'Hello'
+                  botRespondsWith: 'Hello'
+            - name: 'Bye'
+              assertions:
+                - userSays: 'Bye'
+                  botRespondsWith: 'Thanks'
+            - name: 'Thanks'
+              assertions:
+                - userSays: 'Thanks'
+                  botRespondsWith: 'Thanks'
+        ";
+        let docs = YamlLoader::load_from_str(YAML)?;
+        let suite = TestSuite::from_yaml(&docs[0])?;
+        assert_eq!(suite.suite_spec.suite_type.name(), "Echo");
+        Ok(suite)
+    }
+
+    #[test]
+    fn test_run_custom_suite_type() -> Result<()> {
+        let mut events = vec![];
+        let mut reporter_calls = vec![];
+        let executed_tests = {
+            let mut runner = Runner::new(RunnerOptions::default());
+            runner.register_executor_factory("Echo", |_suite_spec, test, tx| {
+                Ok(Box::new(EchoTestExecutor {
+                    test,
+                    next_assertion: 0,
+                    tx,
+                }))
+            });
+            runner.on_progress(|event| {
+                events.push(match event {
+                    RunnerEvent::Started { test_count } => format!("started {}", test_count),
+                    RunnerEvent::TestFinished { finished_count, .. } => {
+                        format!("finished {}", finished_count)
+                    }
+                    RunnerEvent::Finished { finished_count, .. } => {
+                        format!("done {}", finished_count)
+                    }
+                })
+            });
+            runner.add_reporter(Box::new(RecordingReporter {
+                calls: &mut reporter_calls,
+            }));
+            runner.run(echo_suite()?)?.tests
+        };
+
+        assert_eq!(
+            events,
+            vec![
+                "started 3",
+                "finished 1",
+                "finished 2",
+                "finished 3",
+                "done 3"
+            ]
+        );
+        assert_eq!(
+            reporter_calls,
+            vec!["begin 3", "test", "test", "test", "end Hello,Bye,Thanks"]
+        );
+        let results: Vec<(&str, bool)> = executed_tests
+            .iter()
+            .map(|test| {
+                let ok = matches!(test.test_result, Some(TestResult::Ok));
+                (test.name.as_str(), ok)
+            })
+            .collect();
+        assert_eq!(
+            results,
+            vec![("Hello", true), ("Bye", false), ("Thanks", true)]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_run_failing_reporter() -> Result<()> {
+        let mut reporter_calls = vec![];
+        let run_result = {
+            let mut runner = Runner::new(RunnerOptions::default());
+            runner.register_executor_factory("Echo", |_suite_spec, test, tx| {
+                Ok(Box::new(EchoTestExecutor {
+                    test,
+                    next_assertion: 0,
+                    tx,
+                }))
+            });
+            runner.add_reporter(Box::new(FailingReporter));
+            runner.add_reporter(Box::new(RecordingReporter {
+                calls: &mut reporter_calls,
+            }));
+            runner.run(echo_suite()?)?
+        };
+
+        // reporter registered after failing one is called as usual
+        assert_eq!(
+            reporter_calls,
+            vec!["begin 3", "test", "test", "test", "end Hello,Bye,Thanks"]
+        );
+        assert_eq!(run_result.tests.len(), 3);
+        let messages: Vec<&str> = run_result
+            .reporter_errors
+            .iter()
+            .map(|err| err.message.as_str())
+            .collect();
+        assert_eq!(
+            messages,
+            vec!["GenericError: begin failed", "GenericError: end failed"]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_run_repeated() -> Result<()> {
+        let options = RunnerOptions {
+            repeat: 3,
+            ..RunnerOptions::default()
+        };
+        let mut finished_events = 0;
+        let mut reporter_calls = vec![];
+        let executed_tests = {
+            let mut runner = Runner::new(options);
+            runner.register_executor_factory("Echo", |_suite_spec, test, tx| {
+                Ok(Box::new(EchoTestExecutor {
+                    test,
+                    next_assertion: 0,
+                    tx,
+                }))
+            });
+            runner.on_progress(|event| {
+                if let RunnerEvent::TestFinished { .. } = event {
+                    finished_events += 1;
+                }
+            });
+            runner.add_reporter(Box::new(RecordingReporter {
+                calls: &mut reporter_calls,
+            }));
+            runner.run(echo_suite()?)?.tests
+        };
+
+        // progress is reported per repetition, reporters get aggregated tests
+        assert_eq!(finished_events, 9);
+        assert_eq!(
+            reporter_calls,
+            vec!["begin 3", "test", "test", "test", "end Hello,Bye,Thanks"]
+        );
+        let execution_ids: Vec<Option<usize>> = executed_tests
+            .iter()
+            .map(|test| test.execution_id)
+            .collect();
+        assert_eq!(execution_ids, vec![Some(0), Some(1), Some(2)]);
+
+        let bye = &executed_tests[1];
+        assert!(!is_ok(bye));
+        let repetitions = bye.repetitions.as_ref().unwrap();
+        assert_eq!(
+            repetitions.pass_rate_display(),
+            "passed 0 of 3 repetitions (0%)"
+        );
+        assert_eq!(repetitions.intent_distribution_display(0), "Bye (3)");
+        assert!(is_ok(&executed_tests[2]));
+        Ok(())
+    }
+
+    #[test]
+    fn test_run_repeated_update_snapshots() -> Result<()> {
+        let options = RunnerOptions {
+            repeat: 3,
+            update_snapshots: true,
+            ..RunnerOptions::default()
+        };
+        let mut runner = Runner::new(options);
+        match runner.run(echo_suite()?) {
+            Err(err) => assert_eq!(
+                err.message,
+                "GenericError: Snapshots cannot be updated while tests are executed repeatedly"
+            ),
+            Ok(_) => panic!("repeated run with snapshot update must fail"),
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_custom_executor_defaults() -> Result<()> {
+        let (tx, rx) = mpsc::channel();
+        let mut executor = EchoTestExecutor {
+            test: echo_suite()?.tests[0].clone(),
+            next_assertion: 0,
+            tx,
+        };
+        // executor without call_nlp cannot record conversations
+        while executor.record_next_assertion().is_some() {}
+        let test = rx.recv().unwrap();
+        match &test.assertions[0].test_assertion_result {
+            Some(TestAssertionResult::KoBackendError(err)) => assert_eq!(
+                err.message,
+                "GenericError: Test executor does not support recording of conversations"
+            ),
+            other => panic!("Expected backend error, got {:?}", other),
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_run_empty_threadpool() -> Result<()> {
+        let options = RunnerOptions {
+            threadpool_size: 0,
+            ..RunnerOptions::default()
+        };
+        let mut runner = Runner::new(options);
+        match runner.run(echo_suite()?) {
+            Err(err) => assert_eq!(
+                err.message,
+                "GenericError: Thread pool size must be greater than zero"
+            ),
+            Ok(_) => panic!("run without threads must fail"),
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_aggregate_repetitions() {
+        let repetitions: Vec<Test> = vec![TestResult::Ko, TestResult::Ok, TestResult::Ok]
+            .into_iter()
+            .enumerate()
+            .map(|(idx, test_result)| {
+                let mut test = Test::new("Hello".to_owned(), None, "en".to_owned());
+                test.desc = Some(format!("repetition {}", idx));
+                test.execution_id = Some(6 + idx);
+                test.test_result = Some(test_result);
+                test
+            })
+            .collect();
+
+        let test = aggregate_repetitions(repetitions.clone(), 3, 0.6);
+        assert!(is_ok(&test));
+        assert_eq!(test.desc, Some("repetition 1".to_owned()));
+        assert_eq!(test.execution_id, Some(2));
+        assert_eq!(test.repetitions.as_ref().unwrap().passed, 2);
+
+        // failed test shows failed repetition
+        let test = aggregate_repetitions(repetitions, 3, 1.0);
+        assert!(!is_ok(&test));
+        assert_eq!(test.desc, Some("repetition 0".to_owned()));
+        assert_eq!(
+            test.repetitions.unwrap().pass_rate_display(),
+            "passed 2 of 3 repetitions (67%)"
+        );
+    }
+
+    #[test]
+    fn test_run_unregistered_suite_type() -> Result<()> {
+        let mut runner = Runner::new(RunnerOptions::default());
+        match runner.run(echo_suite()?) {
+            Err(err) => assert_eq!(
+                err.message,
+                "GenericError: No test executor registered for suite type Echo"
+            ),
+            Ok(_) => panic!("Expected error for unregistered suite type!"),
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,290 @@
+use assert_json_diff::assert_json_eq_no_panic;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fs;
+use std::path::Path;
+
+use crate::errors::{new_service_call_error, ErrorKind, Result};
+use crate::yaml_parser::TestSuiteType;
+
+// value stored in snapshot instead of volatile values (response ids, session names etc.)
+const IGNORED_VALUE: &str = "__ignored__";
+
+// snapshot directory used when suite-spec does not specify any
+pub const DEFAULT_SNAPSHOT_DIR: &str = "./snapshots";
+
+// volatile paths which are ignored always (on top of paths specified in suite-spec)
+pub fn default_ignore_paths(suite_type: &TestSuiteType) -> Vec<String> {
+    let prefix = match suite_type {
+        TestSuiteType::DialogFlow | TestSuiteType::Custom(_) => "",
+        TestSuiteType::DHLVAP => "dfResponse.",
+    };
+    vec![
+        format!("{}responseId", prefix),
+        format!("{}queryResult.outputContexts[*].name", prefix),
+        format!("{}queryResult.diagnosticInfo", prefix),
+    ]
+}
+
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub struct SnapshotSpec {
+    pub file: String,
+    pub ignore_paths: Vec<String>,
+    pub update: bool, // if true snapshot is rewritten by actual response instead of being compared
+}
+
+#[derive(Debug, PartialEq)]
+enum PathSegment {
+    Field(String),
+    Index(usize),
+    Wildcard,
+}
+
+// ignore paths support subset of JMESPath: field names (optionally quoted), [n] and [*]
+fn parse_ignore_path(path: &str) -> std::result::Result<Vec<PathSegment>, String> {
+    let mut segments = vec![];
+    let chars: Vec<char> = path.trim().chars().collect();
+    let mut idx = 0;
+    let unsupported = || {
+        format!("Unsupported snapshot ignore path '{}'. Only field names, [index] and [*] are supported", path)
+    };
+
+    while idx < chars.len() {
+        match chars[idx] {
+            '.' if !segments.is_empty() => idx += 1,
+            '[' => {
+                let end = chars[idx..]
+                    .iter()
+                    .position(|c| *c == ']')
+                    .ok_or(unsupported())?;
+                let index: String = chars[idx + 1..idx + end].iter().collect();
+                if index == "*" {
+                    segments.push(PathSegment::Wildcard);
+                } else {
+                    segments.push(PathSegment::Index(
+                        index.parse::<usize>().map_err(|_| unsupported())?,
+                    ));
+                }
+                idx += end + 1;
+                continue;
+            }
+            '"' => {
+                let end = chars[idx + 1..]
+                    .iter()
+                    .position(|c| *c == '"')
+                    .ok_or(unsupported())?;
+                segments.push(PathSegment::Field(
+                    chars[idx + 1..idx + 1 + end].iter().collect(),
+                ));
+                idx += end + 2;
+                continue;
+            }
+            c if c.is_alphanumeric() || c == '_' => {
+                let end = chars[idx..]
+                    .iter()
+                    .position(|c| !(c.is_alphanumeric() || *c == '_'))
+                    .unwrap_or(chars.len() - idx);
+                segments.push(PathSegment::Field(chars[idx..idx + end].iter().collect()));
+                idx += end;
+                continue;
+            }
+            _ => return Err(unsupported()),
+        }
+        // dot must be followed by field name
+        match chars.get(idx) {
+            Some(c) if c.is_alphanumeric() || *c == '_' || *c == '"' => {}
+            _ => return Err(unsupported()),
+        }
+    }
+
+    if segments.is_empty() {
+        return Err(unsupported());
+    }
+    Ok(segments)
+}
+
+pub fn validate_ignore_path(path: &str) -> std::result::Result<(), String> {
+    parse_ignore_path(path).map(|_| ())
+}
+
+fn mask_path(value: &mut Value, segments: &[PathSegment]) {
+    if segments.is_empty() {
+        if !value.is_null() {
+            *value = Value::String(IGNORED_VALUE.to_owned());
+        }
+        return;
+    }
+
+    match (&segments[0], value) {
+        (PathSegment::Field(field), Value::Object(map)) => {
+            if let Some(child) = map.get_mut(field) {
+                mask_path(child, &segments[1..]);
+            }
+        }
+        (PathSegment::Index(index), Value::Array(arr)) => {
+            if let Some(child) = arr.get_mut(*index) {
+                mask_path(child, &segments[1..]);
+            }
+        }
+        (PathSegment::Wildcard, Value::Array(arr)) => {
+            for child in arr.iter_mut() {
+                mask_path(child, &segments[1..]);
+            }
+        }
+        _ => { /* path does not exist in this response, nothing to ignore */ }
+    }
+}
+
+// parses NLP response and replaces values of all ignored paths with placeholder
+pub fn normalize_response(response: &str, ignore_paths: &Vec<String>) -> Result<Value> {
+    let mut value: Value = serde_json::from_str(response)?;
+    for path in ignore_paths {
+        // paths are validated when test suite is loaded
+        if let Ok(segments) = parse_ignore_path(path) {
+            mask_path(&mut value, &segments);
+        }
+    }
+    Ok(value)
+}
+
+fn write_snapshot(file: &Path, normalized_response: &Value) -> Result<()> {
+    if let Some(dir) = file.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    let mut snapshot = serde_json::to_string_pretty(normalized_response)?;
+    snapshot.push('\n');
+    fs::write(file, snapshot)?;
+    Ok(())
+}
+
+// compares response with stored snapshot, snapshot is (re)written only when snapshot update
+// is requested, i.e. missing snapshot fails the check
+pub fn check_snapshot(spec: &SnapshotSpec, response: &str) -> Result<()> {
+    let file = Path::new(&spec.file);
+    let normalized_response = normalize_response(response, &spec.ignore_paths)?;
+
+    if spec.update {
+        return write_snapshot(file, &normalized_response);
+    }
+
+    if !file.exists() {
+        return Err(new_service_call_error(
+            ErrorKind::InvalidTestAssertionResponseCheckEvaluation,
+            format!(
+                "Snapshot {} does not exist. Run with --update-snapshots to create it",
+                spec.file
+            ),
+            None,
+            Some(response.to_owned()),
+        ));
+    }
+
+    let snapshot: Value = serde_json::from_str(&fs::read_to_string(file)?)?;
+    match assert_json_eq_no_panic(&normalized_response, &snapshot) {
+        Ok(()) => Ok(()),
+        Err(diff) => Err(new_service_call_error(
+            ErrorKind::InvalidTestAssertionResponseCheckEvaluation,
+            format!(
+                "Response does not match snapshot {}. Run with --update-snapshots to accept the new response. Differences:\n{}",
+                spec.file, diff
+            ),
+            None,
+            Some(response.to_owned()),
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RESPONSE: &str = r#"{
+        "responseId": "24f4edc7-d7aa-43f6-a088-5069e9e90305-35305123",
+        "queryResult": {
+          "queryText": "hi",
+          "action": "input.welcome",
+          "outputContexts": [
+            {"name": "projects/cs-am-uat/agent/sessions/1234/contexts/welcome", "lifespanCount": 1},
+            {"name": "projects/cs-am-uat/agent/sessions/1234/contexts/tracking", "lifespanCount": 2}
+          ],
+          "intent": {"displayName": "Generic|BIT|0|Welcome|Gen"}
+        }
+    }"#;
+
+    #[test]
+    fn test_parse_ignore_path() {
+        assert_eq!(
+            parse_ignore_path(r#"queryResult.outputContexts[*].name"#),
+            Ok(vec![
+                PathSegment::Field("queryResult".to_owned()),
+                PathSegment::Field("outputContexts".to_owned()),
+                PathSegment::Wildcard,
+                PathSegment::Field("name".to_owned()),
+            ])
+        );
+        assert_eq!(
+            parse_ignore_path(r#"payload."google-data"[0]"#),
+            Ok(vec![
+                PathSegment::Field("payload".to_owned()),
+                PathSegment::Field("google-data".to_owned()),
+                PathSegment::Index(0),
+            ])
+        );
+        assert!(parse_ignore_path("queryResult..action").is_err());
+        assert!(parse_ignore_path("queryResult[?name == 'x']").is_err());
+        assert!(parse_ignore_path("").is_err());
+    }
+
+    #[test]
+    fn test_normalize_response() -> Result<()> {
+        let normalized =
+            normalize_response(RESPONSE, &default_ignore_paths(&TestSuiteType::DialogFlow))?;
+        assert_eq!(normalized["responseId"], IGNORED_VALUE);
+        assert_eq!(
+            normalized["queryResult"]["outputContexts"][1]["name"],
+            IGNORED_VALUE
+        );
+        assert_eq!(
+            normalized["queryResult"]["outputContexts"][1]["lifespanCount"],
+            2
+        );
+        assert_eq!(normalized["queryResult"]["action"], "input.welcome");
+        Ok(())
+    }
+
+    #[test]
+    fn test_check_snapshot() -> Result<()> {
+        let file = std::env::temp_dir()
+            .join("gdf_testing_test_check_snapshot")
+            .join("turn_1.json");
+        let _ = fs::remove_file(&file);
+        let mut spec = SnapshotSpec {
+            file: file.to_str().unwrap().to_owned(),
+            ignore_paths: default_ignore_paths(&TestSuiteType::DialogFlow),
+            update: false,
+        };
+
+        // missing snapshot is not created unless update is requested
+        let err = check_snapshot(&spec, RESPONSE).unwrap_err();
+        assert!(err.message.contains("does not exist"));
+        assert!(!file.exists());
+
+        // snapshot created by update matches response with different response id
+        spec.update = true;
+        check_snapshot(&spec, RESPONSE)?;
+        assert!(file.exists());
+        spec.update = false;
+        check_snapshot(&spec, &RESPONSE.replace("24f4edc7", "00000000"))?;
+
+        let changed_response = RESPONSE.replace("input.welcome", "input.unknown");
+        let err = check_snapshot(&spec, &changed_response).unwrap_err();
+        assert!(err.message.contains("input.unknown"));
+
+        // updated snapshot accepts changed response
+        spec.update = true;
+        check_snapshot(&spec, &changed_response)?;
+        spec.update = false;
+        check_snapshot(&spec, &changed_response)?;
+        Ok(())
+    }
+}
@@ -1,14 +1,58 @@
+use serde_json::json;
+use std::collections::HashMap;
 use std::env;
 use std::sync::mpsc;
 
 use crate::errors::{new_error, new_error_from, new_service_call_error, ErrorKind, Result};
+use crate::expectations::check_expectation;
+use crate::fulfillment::check_messages;
+use crate::gdf::{file_to_gdf_credentials, get_google_api_token};
 use crate::json_parser::{JmespathType, JsonParser};
+use crate::json_schema::JsonSchema;
+use crate::snapshots::check_snapshot;
+use crate::suite_keys as keys;
 use crate::test_executors::{GDFDefaultTestExecutor, TestExecutor, VAPTestExecutor};
+pub use crate::yaml_parser::ResponseCheckTreeRow;
 use crate::yaml_parser::{
     Test, TestAssertionResponseCheck, TestAssertionResponseCheckOperator,
-    TestAssertionResponseCheckValue, TestSuite, TestSuiteType,
+    TestAssertionResponseCheckValue, TestSuite, TestSuiteSpec, TestSuiteType,
 };
 
+// creates test executor of single test, results of executed test must be sent via provided channel
+pub type TestExecutorFactory = Box<
+    dyn Fn(&TestSuiteSpec, Test, mpsc::Sender<Test>) -> Result<Box<dyn TestExecutor + Send>>
+        + Send
+        + Sync,
+>;
+
+// test executor factories by suite type name. Factory registered for built-in suite type
+// (DialogFlow, DHLVAP) replaces its default test executor
+#[derive(Default)]
+pub struct TestExecutorRegistry {
+    factories: HashMap<String, TestExecutorFactory>,
+}
+
+impl TestExecutorRegistry {
+    pub fn new() -> Self {
+        TestExecutorRegistry::default()
+    }
+
+    pub fn register<F>(&mut self, suite_type: &str, factory: F)
+    where
+        F: Fn(&TestSuiteSpec, Test, mpsc::Sender<Test>) -> Result<Box<dyn TestExecutor + Send>>
+            + Send
+            + Sync
+            + 'static,
+    {
+        self.factories
+            .insert(suite_type.to_owned(), Box::new(factory));
+    }
+
+    pub fn get(&self, suite_type: &TestSuiteType) -> Option<&TestExecutorFactory> {
+        self.factories.get(suite_type.name())
+    }
+}
+
 pub struct TestSuiteExecutor<'a> {
     pub test_suite: TestSuite,
     pub test_executors: Vec<Box<dyn TestExecutor + 'a + Send>>, // Box references are by default 'static! we must ecplivitly indicate shorter lifetime
@@ -17,31 +61,54 @@ pub struct TestSuiteExecutor<'a> {
 
 impl<'a> TestSuiteExecutor<'a> {
     pub fn new(test_suite: TestSuite) -> Result<Self> {
+        TestSuiteExecutor::with_registry(test_suite, &TestExecutorRegistry::new())
+    }
+
+    pub fn with_registry(test_suite: TestSuite, registry: &TestExecutorRegistry) -> Result<Self> {
         let (tx, rx) = mpsc::channel(); // channel for receoving the results of tests which are running in parallel
 
         let mut test_executors: Vec<Box<dyn TestExecutor + 'a + Send>> = vec![];
 
+        if let Some(factory) = registry.get(&test_suite.suite_spec.suite_type) {
+            for (idx, test) in test_suite.tests.iter().enumerate() {
+                let mut _test = test.clone();
+                _test.execution_id = Some(idx);
+                test_executors.push(factory(&test_suite.suite_spec, _test, tx.clone())?);
+            }
+
+            return Ok(TestSuiteExecutor {
+                test_suite,
+                test_executors,
+                rx,
+            });
+        }
+
         match test_suite.suite_spec.suite_type {
+            TestSuiteType::Custom(ref suite_type) => Err(new_error_from(ErrorKind::GenericError(
+                format!("No test executor registered for suite type {}", suite_type),
+            ))),
             TestSuiteType::DHLVAP => {
-                let vap_access_token = test_suite.suite_spec.config.get("vap_access_token");
-                if let None = vap_access_token {
+                let vap_access_token = test_suite.suite_spec.config.get(keys::VAP_ACCESS_TOKEN);
+                if vap_access_token.is_none() {
                     return Err(new_error_from(ErrorKind::GenericError(
                         "vap_access_token config value not found".to_owned(),
                     )));
                 }
                 let vap_access_token = vap_access_token.unwrap();
 
-                let vap_url = test_suite.suite_spec.config.get("vap_url");
-                if let None = vap_url {
+                let vap_url = test_suite.suite_spec.config.get(keys::VAP_URL);
+                if vap_url.is_none() {
                     return Err(new_error_from(ErrorKind::GenericError(
                         "vap_url config value not found".to_owned(),
                     )));
                 }
                 let vap_url = vap_url.unwrap();
 
-                let vap_svc_account_email =
-                    test_suite.suite_spec.config.get("vap_svc_account_email");
-                if let None = vap_svc_account_email {
+                let vap_svc_account_email = test_suite
+                    .suite_spec
+                    .config
+                    .get(keys::VAP_SVC_ACCOUNT_EMAIL);
+                if vap_svc_account_email.is_none() {
                     return Err(new_error_from(ErrorKind::GenericError(
                         "vap_svc_account_email config value not found".to_owned(),
                     )));
@@ -49,9 +116,11 @@ impl<'a> TestSuiteExecutor<'a> {
                 let vap_svc_account_email = vap_svc_account_email.unwrap();
 
                 let vap_svc_account_password_from_env_var;
-                let mut vap_svc_account_password =
-                    test_suite.suite_spec.config.get("vap_svc_account_password");
-                if let None = vap_svc_account_password {
+                let mut vap_svc_account_password = test_suite
+                    .suite_spec
+                    .config
+                    .get(keys::VAP_SVC_ACCOUNT_PASSWORD);
+                if vap_svc_account_password.is_none() {
                     // try to retrieve password from environment var before throwing error
                     let svc_acc_pwd_env_var = env::var("VAP_SVC_ACCOUNT_PASSWORD");
 
@@ -69,37 +138,46 @@ impl<'a> TestSuiteExecutor<'a> {
                 }
                 let vap_svc_account_password = vap_svc_account_password.unwrap();
 
+                // jwt token is obtained once and shared by all tests (and their repetitions)
+                let jwt_token = VAPTestExecutor::get_vap_access_token(
+                    vap_svc_account_email,
+                    vap_svc_account_password,
+                    vap_url,
+                )?
+                .accessToken;
+
                 for (idx, test) in test_suite.tests.iter().enumerate() {
                     let mut _test = test.clone();
                     _test.execution_id = Some(idx);
 
-                    let vap_channel_id = match test_suite.suite_spec.config.get("vap_channel_id") {
-                        None => None,
-                        Some(channel_id) => Some(channel_id.to_owned()),
-                    };
-
-                    let vap_country = match test_suite.suite_spec.config.get("vap_country") {
-                        None => None,
-                        Some(country) => Some(country.to_owned()),
-                    };
-
-                    let vap_context_extra =
-                        match test_suite.suite_spec.config.get("vap_context_extra") {
-                            None => None,
-                            Some(extra_context) => Some(extra_context.to_owned()),
-                        };
-
-                    let _executor = Box::new(VAPTestExecutor::new(
+                    let vap_channel_id = test_suite
+                        .suite_spec
+                        .config
+                        .get(keys::VAP_CHANNEL_ID)
+                        .map(|channel_id| channel_id.to_owned());
+
+                    let vap_country = test_suite
+                        .suite_spec
+                        .config
+                        .get(keys::VAP_COUNTRY)
+                        .map(|country| country.to_owned());
+
+                    let vap_context_extra = test_suite
+                        .suite_spec
+                        .config
+                        .get(keys::VAP_CONTEXT_EXTRA)
+                        .map(|extra_context| extra_context.to_owned());
+
+                    let _executor = Box::new(VAPTestExecutor::with_jwt_token(
                         vap_access_token.to_owned(),
                         vap_url.to_owned(),
-                        vap_svc_account_email.to_owned(),
-                        vap_svc_account_password.to_owned(),
+                        jwt_token.clone(),
                         _test,
                         tx.clone(),
                         vap_channel_id,
                         vap_country,
                         vap_context_extra,
-                    )?) as Box<dyn TestExecutor + Send>;
+                    )) as Box<dyn TestExecutor + Send>;
                     test_executors.push(_executor);
                 }
 
@@ -110,30 +188,36 @@ impl<'a> TestSuiteExecutor<'a> {
                 })
             }
             TestSuiteType::DialogFlow => {
-                let credentials_file = test_suite.suite_spec.config.get("credentials_file");
-                if let None = credentials_file {
+                let credentials_file = test_suite.suite_spec.config.get(keys::CREDENTIALS_FILE);
+                if credentials_file.is_none() {
                     return Err(new_error_from(ErrorKind::GenericError(
                         "credentials_file config value not found".to_owned(),
                     )));
                 }
                 let credentials_file = credentials_file.unwrap();
 
+                let http_proxy = test_suite
+                    .suite_spec
+                    .config
+                    .get(keys::HTTP_PROXY)
+                    .map(|proxy_str| proxy_str.to_owned());
+
+                // OAuth token is obtained once and shared by all tests (and their repetitions)
+                let http_client = GDFDefaultTestExecutor::build_http_client(http_proxy)?;
+                let token = get_google_api_token(credentials_file, &http_client)?;
+                let cred = file_to_gdf_credentials(credentials_file)?;
+
                 for (idx, test) in test_suite.tests.iter().enumerate() {
                     let mut _test = test.clone();
 
-                    let http_proxy: Option<String>;
-                    match test_suite.suite_spec.config.get("http_proxy") {
-                        Some(proxy_str) => http_proxy = Some(proxy_str.to_owned()),
-                        _ => http_proxy = None,
-                    }
-
                     _test.execution_id = Some(idx);
-                    let _executor = Box::new(GDFDefaultTestExecutor::new(
-                        credentials_file.to_owned(),
+                    let _executor = Box::new(GDFDefaultTestExecutor::with_token(
+                        cred.clone(),
+                        token.clone(),
+                        http_client.clone(),
                         _test,
                         tx.clone(),
-                        http_proxy,
-                    )?) as Box<dyn TestExecutor + Send>;
+                    )) as Box<dyn TestExecutor + Send>;
                     test_executors.push(_executor);
                 }
 
@@ -149,191 +233,186 @@ impl<'a> TestSuiteExecutor<'a> {
     fn process_bool_equals(
         bool_val_expected: &bool,
         response_check: &TestAssertionResponseCheck,
-        response: &str,
+        parser: &JsonParser,
     ) -> Result<()> {
-        let parser = JsonParser::new(response);
-        let search_result = parser.search(&response_check.expression)?;
+        let search_result = response_check.search(parser)?;
 
         let value = JsonParser::extract_as_bool(&search_result);
         if let Some(bool_val_real) = value {
             if bool_val_real == *bool_val_expected {
-                return Ok(());
+                Ok(())
             } else {
                 let error_message = format!(
                     "Expected value ({}) does not match real value: ({}) for expression: {}",
                     bool_val_expected, bool_val_real, response_check.expression
                 );
-                return Err(new_service_call_error(
+                Err(new_service_call_error(
                     ErrorKind::InvalidTestAssertionResponseCheckEvaluation,
                     error_message,
                     None,
-                    Some(response.to_owned()),
-                ));
+                    Some(parser.json().to_owned()),
+                ))
             }
         } else {
             let error_message = format!(
                 "Unable to retrieve boolean value ({}) for expression: {}",
                 bool_val_expected, response_check.expression
             );
-            return Err(new_service_call_error(
+            Err(new_service_call_error(
                 ErrorKind::InvalidTestAssertionResponseCheckEvaluation,
                 error_message,
                 None,
-                Some(response.to_owned()),
-            ));
+                Some(parser.json().to_owned()),
+            ))
         }
     }
 
     fn process_bool_not_equals(
         bool_val_expected: &bool,
         response_check: &TestAssertionResponseCheck,
-        response: &str,
+        parser: &JsonParser,
     ) -> Result<()> {
-        let parser = JsonParser::new(response);
-        let search_result = parser.search(&response_check.expression)?;
+        let search_result = response_check.search(parser)?;
         let value = JsonParser::extract_as_bool(&search_result);
         if let Some(bool_val_real) = value {
             if bool_val_real != *bool_val_expected {
-                return Ok(());
+                Ok(())
             } else {
                 let error_message = format!(
                     "Expected value ({}), got instead value: ({}) for expression: {}",
                     !bool_val_expected, bool_val_real, response_check.expression
                 );
-                return Err(new_service_call_error(
+                Err(new_service_call_error(
                     ErrorKind::InvalidTestAssertionResponseCheckEvaluation,
                     error_message,
                     None,
-                    Some(response.to_owned()),
-                ));
+                    Some(parser.json().to_owned()),
+                ))
             }
         } else {
             let error_message = format!(
                 "Unable to retrieve boolean value ({}) for expression: {}",
                 !bool_val_expected, response_check.expression
             );
-            return Err(new_service_call_error(
+            Err(new_service_call_error(
                 ErrorKind::InvalidTestAssertionResponseCheckEvaluation,
                 error_message,
                 None,
-                Some(response.to_owned()),
-            ));
+                Some(parser.json().to_owned()),
+            ))
         }
     }
 
     fn process_string_equals(
         str_val_expected: &String,
         response_check: &TestAssertionResponseCheck,
-        response: &str,
+        parser: &JsonParser,
     ) -> Result<()> {
-        let parser = JsonParser::new(response);
-        let search_result = parser.search(&response_check.expression)?;
+        let search_result = response_check.search(parser)?;
         let value = JsonParser::extract_as_string(&search_result);
         if let Some(str_val_real) = value {
             if str_val_real == str_val_expected {
-                return Ok(());
+                Ok(())
             } else {
                 let error_message = format!(
                     "Expected value '{}' does not match real value: '{}' for expression: {}",
                     str_val_expected, str_val_real, response_check.expression
                 );
-                return Err(new_service_call_error(
+                Err(new_service_call_error(
                     ErrorKind::InvalidTestAssertionResponseCheckEvaluation,
                     error_message,
                     None,
-                    Some(response.to_owned()),
-                ));
+                    Some(parser.json().to_owned()),
+                ))
             }
         } else {
             let error_message = format!(
                 "Unable to retrieve string value for expression: {}",
                 response_check.expression
             );
-            return Err(new_service_call_error(
+            Err(new_service_call_error(
                 ErrorKind::InvalidTestAssertionResponseCheckEvaluation,
                 error_message,
                 None,
-                Some(response.to_owned()),
-            ));
+                Some(parser.json().to_owned()),
+            ))
         }
     }
 
     fn process_string_includes(
         str_val_expected: &String,
         response_check: &TestAssertionResponseCheck,
-        response: &str,
+        parser: &JsonParser,
     ) -> Result<()> {
-        let parser = JsonParser::new(response);
-        let search_result = parser.search(&response_check.expression)?;
+        let search_result = response_check.search(parser)?;
         let value = JsonParser::extract_as_string(&search_result);
         if let Some(str_val_real) = value {
-            if str_val_real.contains(str_val_expected) == true {
-                return Ok(());
+            if str_val_real.contains(str_val_expected) {
+                Ok(())
             } else {
                 let error_message = format!(
                     "Expected value '{}' not included in real value: '{}' for expression: {}",
                     str_val_expected, str_val_real, response_check.expression
                 );
-                return Err(new_service_call_error(
+                Err(new_service_call_error(
                     ErrorKind::InvalidTestAssertionResponseCheckEvaluation,
                     error_message,
                     None,
-                    Some(response.to_owned()),
-                ));
+                    Some(parser.json().to_owned()),
+                ))
             }
         } else {
             let error_message = format!(
                 "Unable to retrieve string value for expression: {}",
                 response_check.expression
             );
-            return Err(new_service_call_error(
+            Err(new_service_call_error(
                 ErrorKind::InvalidTestAssertionResponseCheckEvaluation,
                 error_message,
                 None,
-                Some(response.to_owned()),
-            ));
+                Some(parser.json().to_owned()),
+            ))
         }
     }
 
     fn process_string_json_equals(
-        str_val_expected: &String,
+        str_val_expected: &str,
         response_check: &TestAssertionResponseCheck,
-        response: &str,
+        parser: &JsonParser,
     ) -> Result<()> {
-        let parser = JsonParser::new(response);
-        let search_result = parser.search(&response_check.expression)?;
+        let search_result = response_check.search(parser)?;
 
         if JsonParser::get_jmespath_var_type(&search_result) == Some(JmespathType::Array) {
             let value = JsonParser::extract_as_array(&search_result);
             if let Some(array_val_real) = value {
                 let json_comparison_result =
-                    JsonParser::compare_array_with_str(&array_val_real, &str_val_expected);
+                    JsonParser::compare_array_with_str(&array_val_real, str_val_expected);
 
                 match json_comparison_result {
-                    Ok(str_val) if str_val == "__OK__" => return Ok(()),
+                    Ok(str_val) if str_val == "__OK__" => Ok(()),
                     Ok(err_msg) => {
                         let error_message = format!(
                             "Arrays not matching for expression '{}'. Error: {}",
                             response_check.expression, err_msg
                         );
-                        return Err(new_service_call_error(
+                        Err(new_service_call_error(
                             ErrorKind::InvalidTestAssertionResponseCheckEvaluation,
                             error_message,
                             None,
-                            Some(response.to_owned()),
-                        ));
+                            Some(parser.json().to_owned()),
+                        ))
                     }
                     Err(error) => {
                         let error_message = format!(
                             "Arrays not matching for expression '{}'. Error: {}",
                             response_check.expression, error
                         );
-                        return Err(new_service_call_error(
+                        Err(new_service_call_error(
                             ErrorKind::InvalidTestAssertionResponseCheckEvaluation,
                             error_message,
                             None,
-                            Some(response.to_owned()),
-                        ));
+                            Some(parser.json().to_owned()),
+                        ))
                     }
                 }
             } else {
@@ -341,45 +420,45 @@ impl<'a> TestSuiteExecutor<'a> {
                     "Unable to retrieve string value for expression: {}",
                     response_check.expression
                 );
-                return Err(new_service_call_error(
+                Err(new_service_call_error(
                     ErrorKind::InvalidTestAssertionResponseCheckEvaluation,
                     error_message,
                     None,
-                    Some(response.to_owned()),
-                ));
+                    Some(parser.json().to_owned()),
+                ))
             }
         } else if JsonParser::get_jmespath_var_type(&search_result) == Some(JmespathType::Object) {
             let value = JsonParser::extract_as_object(&search_result);
 
             if let Some(obj_val_real) = value {
                 let json_comparison_result =
-                    JsonParser::compare_object_with_str(&obj_val_real, &str_val_expected);
+                    JsonParser::compare_object_with_str(&obj_val_real, str_val_expected);
 
                 match json_comparison_result {
-                    Ok(str_val) if str_val == "__OK__" => return Ok(()),
+                    Ok(str_val) if str_val == "__OK__" => Ok(()),
                     Ok(err_msg) => {
                         let error_message = format!(
                             "Objects not matching for expression '{}'. Error: {}",
                             response_check.expression, err_msg
                         );
-                        return Err(new_service_call_error(
+                        Err(new_service_call_error(
                             ErrorKind::InvalidTestAssertionResponseCheckEvaluation,
                             error_message,
                             None,
-                            Some(response.to_owned()),
-                        ));
+                            Some(parser.json().to_owned()),
+                        ))
                     }
                     Err(error) => {
                         let error_message = format!(
                             "Objects not matching for expression '{}'. Error: {}",
                             response_check.expression, error
                         );
-                        return Err(new_service_call_error(
+                        Err(new_service_call_error(
                             ErrorKind::InvalidTestAssertionResponseCheckEvaluation,
                             error_message,
                             None,
-                            Some(response.to_owned()),
-                        ));
+                            Some(parser.json().to_owned()),
+                        ))
                     }
                 }
             } else {
@@ -387,106 +466,170 @@ impl<'a> TestSuiteExecutor<'a> {
                     "Unable to retrieve string value for expression: {}",
                     response_check.expression
                 );
-                return Err(new_service_call_error(
+                Err(new_service_call_error(
                     ErrorKind::InvalidTestAssertionResponseCheckEvaluation,
                     error_message,
                     None,
-                    Some(response.to_owned()),
-                ));
+                    Some(parser.json().to_owned()),
+                ))
             }
         } else {
             let error_message = format!("Cannot apply jsonequals operator. Retrieved value is neither object nor array for expression: {}", response_check.expression);
-            return Err(new_service_call_error(
+            Err(new_service_call_error(
                 ErrorKind::InvalidTestAssertionResponseCheckEvaluation,
                 error_message,
                 None,
-                Some(response.to_owned()),
-            ));
+                Some(parser.json().to_owned()),
+            ))
+        }
+    }
+
+    fn process_string_json_includes(
+        str_val_expected: &str,
+        unordered_arrays: bool,
+        response_check: &TestAssertionResponseCheck,
+        parser: &JsonParser,
+    ) -> Result<()> {
+        let search_result = response_check.search(parser)?;
+
+        let differences = JsonParser::compare_includes_with_str(
+            &search_result,
+            str_val_expected,
+            unordered_arrays,
+        )
+        .map_err(|error| {
+            new_service_call_error(
+                ErrorKind::InvalidTestAssertionResponseCheckEvaluation,
+                format!(
+                    "Cannot apply jsonincludes operator for expression '{}'. Error: {}",
+                    response_check.expression, error
+                ),
+                None,
+                Some(parser.json().to_owned()),
+            )
+        })?;
+
+        if differences.is_empty() {
+            return Ok(());
+        }
+
+        let error_message = format!(
+            "Expected json not included in value of expression '{}'. Differences:\n{}",
+            response_check.expression,
+            differences.join("\n")
+        );
+        Err(new_service_call_error(
+            ErrorKind::InvalidTestAssertionResponseCheckEvaluation,
+            error_message,
+            None,
+            Some(parser.json().to_owned()),
+        ))
+    }
+
+    fn process_schema(
+        schema: &JsonSchema,
+        response_check: &TestAssertionResponseCheck,
+        parser: &JsonParser,
+    ) -> Result<()> {
+        let search_result = response_check.search(parser)?;
+
+        let violations = schema.validate(&json!(search_result));
+        if violations.is_empty() {
+            return Ok(());
         }
+
+        let error_message = format!(
+            "Value of expression '{}' does not match json schema. Violations:\n{}",
+            response_check.expression,
+            violations.join("\n")
+        );
+        Err(new_service_call_error(
+            ErrorKind::InvalidTestAssertionResponseCheckEvaluation,
+            error_message,
+            None,
+            Some(parser.json().to_owned()),
+        ))
     }
 
     fn process_string_not_equals(
         str_val_expected: &String,
         response_check: &TestAssertionResponseCheck,
-        response: &str,
+        parser: &JsonParser,
     ) -> Result<()> {
-        let parser = JsonParser::new(response);
-        let search_result = parser.search(&response_check.expression)?;
+        let search_result = response_check.search(parser)?;
         let value = JsonParser::extract_as_string(&search_result);
         if let Some(str_val_real) = value {
             if str_val_real != str_val_expected {
-                return Ok(());
+                Ok(())
             } else {
                 let error_message = format!(
                     "Expected value '{}' does match real value: '{}' for expression: {}",
                     str_val_expected, str_val_real, response_check.expression
                 );
-                return Err(new_service_call_error(
+                Err(new_service_call_error(
                     ErrorKind::InvalidTestAssertionResponseCheckEvaluation,
                     error_message,
                     None,
-                    Some(response.to_owned()),
-                ));
+                    Some(parser.json().to_owned()),
+                ))
             }
         } else {
             let error_message = format!(
                 "Unable to retrieve string value for expression: {}",
                 response_check.expression
             );
-            return Err(new_service_call_error(
+            Err(new_service_call_error(
                 ErrorKind::InvalidTestAssertionResponseCheckEvaluation,
                 error_message,
                 None,
-                Some(response.to_owned()),
-            ));
+                Some(parser.json().to_owned()),
+            ))
         }
     }
 
     fn process_num_equals(
         num_val_expected: &f64,
         response_check: &TestAssertionResponseCheck,
-        response: &str,
+        parser: &JsonParser,
     ) -> Result<()> {
-        let parser = JsonParser::new(response);
-        let search_result = parser.search(&response_check.expression)?;
+        let search_result = response_check.search(parser)?;
         let value = JsonParser::extract_as_number(&search_result);
         if let Some(num_val_real) = value {
             if num_val_real == *num_val_expected {
-                return Ok(());
+                Ok(())
             } else {
                 let error_message = format!(
                     "Expected value ({}) does not match real value: ({}) for expression: {}",
                     num_val_expected, num_val_real, response_check.expression
                 );
-                return Err(new_service_call_error(
+                Err(new_service_call_error(
                     ErrorKind::InvalidTestAssertionResponseCheckEvaluation,
                     error_message,
                     None,
-                    Some(response.to_owned()),
-                ));
+                    Some(parser.json().to_owned()),
+                ))
             }
         } else {
             let error_message = format!(
                 "Unable to retrieve numerical value for expression: {}",
                 response_check.expression
             );
-            return Err(new_service_call_error(
+            Err(new_service_call_error(
                 ErrorKind::InvalidTestAssertionResponseCheckEvaluation,
                 error_message,
                 None,
-                Some(response.to_owned()),
-            ));
+                Some(parser.json().to_owned()),
+            ))
         }
     }
 
     fn process_num_length(
         num_val_expected: &f64,
         response_check: &TestAssertionResponseCheck,
-        response: &str,
+        parser: &JsonParser,
     ) -> Result<()> {
         // we do support length of arrays only, not lenght of strings or number of digits in number!
-        let parser = JsonParser::new(response);
-        let search_result = parser.search(&response_check.expression)?;
+        let search_result = response_check.search(parser)?;
 
         match JsonParser::get_jmespath_var_type(&search_result) {
             Some(JmespathType::Array) =>
@@ -496,7 +639,7 @@ impl<'a> TestSuiteExecutor<'a> {
                 if let Some(arr_value) = value {
                     if arr_value.len() == *num_val_expected as usize {
                         // TODO: num value in response check should be usize, f64 does not make sense if used only for array length comparison
-                        return Ok(());
+                        Ok(())
                     } else {
                         let error_message = format!(
                             "Expected array length {}, got {} for expression: {}",
@@ -504,24 +647,24 @@ impl<'a> TestSuiteExecutor<'a> {
                             arr_value.len(),
                             response_check.expression
                         );
-                        return Err(new_service_call_error(
+                        Err(new_service_call_error(
                             ErrorKind::InvalidTestAssertionResponseCheckEvaluation,
                             error_message,
                             None,
-                            Some(response.to_owned()),
-                        ));
+                            Some(parser.json().to_owned()),
+                        ))
                     }
                 } else {
                     let error_message = format!(
                         "Unable to retrieve array value for expression: {}",
                         response_check.expression
                     );
-                    return Err(new_service_call_error(
+                    Err(new_service_call_error(
                         ErrorKind::InvalidTestAssertionResponseCheckEvaluation,
                         error_message,
                         None,
-                        Some(response.to_owned()),
-                    ));
+                        Some(parser.json().to_owned()),
+                    ))
                 }
             }
             /* no type, i.e. expression does not match any value in json */
@@ -530,12 +673,12 @@ impl<'a> TestSuiteExecutor<'a> {
                     "Unable to retrieve array value for expression: {}",
                     response_check.expression
                 );
-                return Err(new_service_call_error(
+                Err(new_service_call_error(
                     ErrorKind::InvalidTestAssertionResponseCheckEvaluation,
                     error_message,
                     None,
-                    Some(response.to_owned()),
-                ));
+                    Some(parser.json().to_owned()),
+                ))
             }
             Some(_) =>
             /* some other type, e.g. object*/
@@ -544,12 +687,12 @@ impl<'a> TestSuiteExecutor<'a> {
                     "Operator length allowed for array expressions only. Expression: {}",
                     response_check.expression
                 );
-                return Err(new_service_call_error(
+                Err(new_service_call_error(
                     ErrorKind::InvalidTestAssertionResponseCheckEvaluation,
                     error_message,
                     None,
-                    Some(response.to_owned()),
-                ));
+                    Some(parser.json().to_owned()),
+                ))
             }
         }
     }
@@ -557,52 +700,247 @@ impl<'a> TestSuiteExecutor<'a> {
     fn process_num_not_equals(
         num_val_expected: &f64,
         response_check: &TestAssertionResponseCheck,
-        response: &str,
+        parser: &JsonParser,
     ) -> Result<()> {
-        let parser = JsonParser::new(response);
-        let search_result = parser.search(&response_check.expression)?;
+        let search_result = response_check.search(parser)?;
         let value = JsonParser::extract_as_number(&search_result);
         if let Some(num_val_real) = value {
             if num_val_real != *num_val_expected {
-                return Ok(());
+                Ok(())
             } else {
                 let error_message = format!(
                     "Expected value not equal to ({}) got value: ({}) for expression: {}",
                     num_val_expected, num_val_real, response_check.expression
                 );
-                return Err(new_service_call_error(
+                Err(new_service_call_error(
                     ErrorKind::InvalidTestAssertionResponseCheckEvaluation,
                     error_message,
                     None,
-                    Some(response.to_owned()),
-                ));
+                    Some(parser.json().to_owned()),
+                ))
             }
         } else {
             let error_message = format!(
                 "Unable to retrieve numerical value for expression: {}",
                 response_check.expression
             );
-            return Err(new_service_call_error(
+            Err(new_service_call_error(
                 ErrorKind::InvalidTestAssertionResponseCheckEvaluation,
                 error_message,
                 None,
-                Some(response.to_owned()),
-            ));
+                Some(parser.json().to_owned()),
+            ))
+        }
+    }
+
+    // evaluates (possibly nested) response check, used for check groups
+    // to get status of every check within group
+    pub fn evaluate_response_check_tree(
+        response_check: &TestAssertionResponseCheck,
+        response: &str,
+    ) -> Vec<ResponseCheckTreeRow> {
+        let parser = JsonParser::new(response);
+        TestSuiteExecutor::evaluate_response_check_tree_with_parser(response_check, &parser)
+    }
+
+    fn evaluate_response_check_tree_with_parser(
+        response_check: &TestAssertionResponseCheck,
+        parser: &JsonParser,
+    ) -> Vec<ResponseCheckTreeRow> {
+        let mut rows = vec![];
+        TestSuiteExecutor::evaluate_response_check_node(response_check, parser, 0, &mut rows);
+        rows
+    }
+
+    fn evaluate_response_check_node(
+        response_check: &TestAssertionResponseCheck,
+        parser: &JsonParser,
+        depth: usize,
+        rows: &mut Vec<ResponseCheckTreeRow>,
+    ) -> bool {
+        if let TestAssertionResponseCheckValue::GroupVal(checks) = &response_check.value {
+            let group_row_idx = rows.len();
+            rows.push(ResponseCheckTreeRow {
+                depth,
+                expression: String::new(),
+                operator: response_check.operator.to_string(),
+                value: String::new(),
+                ok: false,
+                error: None,
+            });
+
+            let results: Vec<bool> = checks
+                .iter()
+                .map(|check| {
+                    TestSuiteExecutor::evaluate_response_check_node(check, parser, depth + 1, rows)
+                })
+                .collect();
+
+            let ok = match response_check.operator {
+                TestAssertionResponseCheckOperator::AnyOf => results.iter().any(|ok| *ok),
+                TestAssertionResponseCheckOperator::AllOf => results.iter().all(|ok| *ok),
+                TestAssertionResponseCheckOperator::Not => !results.iter().all(|ok| *ok),
+                _ => false,
+            };
+            rows[group_row_idx].ok = ok;
+            return ok;
+        }
+
+        let result =
+            TestSuiteExecutor::process_assertion_response_check_with_parser(response_check, parser);
+        let ok = result.is_ok();
+        rows.push(ResponseCheckTreeRow {
+            depth,
+            expression: response_check.expression.to_owned(),
+            operator: response_check.operator.to_string(),
+            value: response_check.value.to_string(),
+            ok,
+            error: result.err().map(|err| err.message),
+        });
+        ok
+    }
+
+    pub fn render_response_check_tree(rows: &Vec<ResponseCheckTreeRow>) -> String {
+        let mut lines = vec![];
+        for row in rows {
+            let indent = "  ".repeat(row.depth);
+            let status = if row.ok { "[OK]" } else { "[KO]" };
+            if row.expression.is_empty() && row.value.is_empty() {
+                lines.push(format!("{}{} {}", indent, status, row.operator));
+            } else {
+                lines.push(format!(
+                    "{}{} {} {} {}",
+                    indent, status, row.expression, row.operator, row.value
+                ));
+            }
+            if let Some(error) = &row.error {
+                for error_line in error.lines() {
+                    lines.push(format!("{}     {}", indent, error_line));
+                }
+            }
+        }
+        lines.join("\n")
+    }
+
+    fn check_group_result(
+        response_check: &TestAssertionResponseCheck,
+        rows: &Vec<ResponseCheckTreeRow>,
+        parser: &JsonParser,
+    ) -> Result<()> {
+        if rows[0].ok {
+            return Ok(());
+        }
+        let error_message = format!(
+            "Response check group {} failed:\n{}",
+            response_check.operator,
+            TestSuiteExecutor::render_response_check_tree(rows)
+        );
+        Err(new_service_call_error(
+            ErrorKind::InvalidTestAssertionResponseCheckEvaluation,
+            error_message,
+            None,
+            Some(parser.json().to_owned()),
+        ))
+    }
+
+    // evaluates response check same way as process_assertion_response_check_with_parser,
+    // evaluated rows are returned for check groups (None for other checks)
+    pub fn process_assertion_response_check_with_rows(
+        response_check: &TestAssertionResponseCheck,
+        parser: &JsonParser,
+    ) -> (Result<()>, Option<Vec<ResponseCheckTreeRow>>) {
+        match &response_check.value {
+            TestAssertionResponseCheckValue::GroupVal(_) => {
+                let rows = TestSuiteExecutor::evaluate_response_check_tree_with_parser(
+                    response_check,
+                    parser,
+                );
+                let result = TestSuiteExecutor::check_group_result(response_check, &rows, parser);
+                (result, Some(rows))
+            }
+            _ => (
+                TestSuiteExecutor::process_assertion_response_check_with_parser(
+                    response_check,
+                    parser,
+                ),
+                None,
+            ),
         }
     }
 
     pub fn process_assertion_response_check(
         response_check: &TestAssertionResponseCheck,
         response: &str,
+    ) -> Result<()> {
+        let parser = JsonParser::new(response);
+        TestSuiteExecutor::process_assertion_response_check_with_parser(response_check, &parser)
+    }
+
+    // evaluates response check using parser shared by all checks of given assertion
+    pub fn process_assertion_response_check_with_parser(
+        response_check: &TestAssertionResponseCheck,
+        parser: &JsonParser,
     ) -> Result<()> {
         match &response_check.value {
+            TestAssertionResponseCheckValue::SnapshotVal(snapshot_spec) => {
+                match response_check.operator {
+                    TestAssertionResponseCheckOperator::Snapshot => {
+                        check_snapshot(snapshot_spec, parser.json())
+                    }
+                    _ => {
+                        let error_message = format!(
+                            "Snapshot value can be used only with snapshot operator. expression: {}",
+                            response_check.expression
+                        );
+                        Err(new_error(
+                            ErrorKind::InvalidTestAssertionResponseCheckEvaluation,
+                            error_message,
+                            None,
+                        ))
+                    }
+                }
+            }
+
+            TestAssertionResponseCheckValue::GroupVal(_) => {
+                let rows = TestSuiteExecutor::evaluate_response_check_tree_with_parser(
+                    response_check,
+                    parser,
+                );
+                TestSuiteExecutor::check_group_result(response_check, &rows, parser)
+            }
+
+            TestAssertionResponseCheckValue::ExpectVal(expect_spec) => {
+                check_expectation(expect_spec, response_check, parser)
+            }
+
+            TestAssertionResponseCheckValue::SchemaVal(schema) => match response_check.operator {
+                TestAssertionResponseCheckOperator::Schema => {
+                    TestSuiteExecutor::process_schema(schema, response_check, parser)
+                }
+                _ => {
+                    let error_message = format!(
+                        "Schema value can be used only with schema operator. expression: {}",
+                        response_check.expression
+                    );
+                    Err(new_error(
+                        ErrorKind::InvalidTestAssertionResponseCheckEvaluation,
+                        error_message,
+                        None,
+                    ))
+                }
+            },
+
+            TestAssertionResponseCheckValue::MessageVal(message_spec) => {
+                check_messages(message_spec, response_check, parser)
+            }
+
             TestAssertionResponseCheckValue::BoolVal(bool_val_expected) => {
                 match response_check.operator {
                     TestAssertionResponseCheckOperator::Equals => {
-                        return TestSuiteExecutor::process_bool_equals(
+                        TestSuiteExecutor::process_bool_equals(
                             bool_val_expected,
                             response_check,
-                            response,
+                            parser,
                         )
                     }
                     TestAssertionResponseCheckOperator::Includes => {
@@ -610,92 +948,192 @@ impl<'a> TestSuiteExecutor<'a> {
                             "Operator includes not allowed for boolean value of expression: {}",
                             response_check.expression
                         );
-                        return Err(new_error(
+                        Err(new_error(
                             ErrorKind::InvalidTestAssertionResponseCheckEvaluation,
                             error_message,
                             None,
-                        ));
+                        ))
                     }
                     TestAssertionResponseCheckOperator::JsonEquals => {
                         let error_message = format!(
                             "Operator jsonequals not allowed for boolean value of expression: {}",
                             response_check.expression
                         );
-                        return Err(new_error(
+                        Err(new_error(
+                            ErrorKind::InvalidTestAssertionResponseCheckEvaluation,
+                            error_message,
+                            None,
+                        ))
+                    }
+                    TestAssertionResponseCheckOperator::JsonIncludes { .. } => {
+                        let error_message = format!(
+                            "Operator jsonincludes not allowed for boolean value of expression: {}",
+                            response_check.expression
+                        );
+                        Err(new_error(
                             ErrorKind::InvalidTestAssertionResponseCheckEvaluation,
                             error_message,
                             None,
-                        ));
+                        ))
                     }
                     TestAssertionResponseCheckOperator::Length => {
                         let error_message = format!(
                             "Operator length not allowed for boolean value of expression: {}",
                             response_check.expression
                         );
-                        return Err(new_error(
+                        Err(new_error(
                             ErrorKind::InvalidTestAssertionResponseCheckEvaluation,
                             error_message,
                             None,
-                        ));
+                        ))
                     }
                     TestAssertionResponseCheckOperator::NotEquals => {
-                        return TestSuiteExecutor::process_bool_not_equals(
+                        TestSuiteExecutor::process_bool_not_equals(
                             bool_val_expected,
                             response_check,
-                            response,
+                            parser,
                         )
                     }
+                    TestAssertionResponseCheckOperator::Schema => {
+                        let error_message = format!(
+                            "Operator schema not allowed for boolean value of expression: {}",
+                            response_check.expression
+                        );
+                        Err(new_error(
+                            ErrorKind::InvalidTestAssertionResponseCheckEvaluation,
+                            error_message,
+                            None,
+                        ))
+                    }
+                    TestAssertionResponseCheckOperator::BotSays
+                    | TestAssertionResponseCheckOperator::QuickReplies
+                    | TestAssertionResponseCheckOperator::Card
+                    | TestAssertionResponseCheckOperator::ExpectParameters
+                    | TestAssertionResponseCheckOperator::ExpectContexts
+                    | TestAssertionResponseCheckOperator::AnyOf
+                    | TestAssertionResponseCheckOperator::AllOf
+                    | TestAssertionResponseCheckOperator::Not => {
+                        let error_message = format!(
+                            "Operator {} not allowed for boolean value of expression: {}",
+                            response_check.operator, response_check.expression
+                        );
+                        Err(new_error(
+                            ErrorKind::InvalidTestAssertionResponseCheckEvaluation,
+                            error_message,
+                            None,
+                        ))
+                    }
+                    TestAssertionResponseCheckOperator::Snapshot => {
+                        let error_message = format!(
+                            "Operator snapshot not allowed for boolean value of expression: {}",
+                            response_check.expression
+                        );
+                        Err(new_error(
+                            ErrorKind::InvalidTestAssertionResponseCheckEvaluation,
+                            error_message,
+                            None,
+                        ))
+                    }
                 }
             }
 
             TestAssertionResponseCheckValue::StrVal(str_val_expected) => {
                 match response_check.operator {
                     TestAssertionResponseCheckOperator::Equals => {
-                        return TestSuiteExecutor::process_string_equals(
+                        TestSuiteExecutor::process_string_equals(
                             str_val_expected,
                             response_check,
-                            response,
+                            parser,
                         )
                     }
                     TestAssertionResponseCheckOperator::Includes => {
-                        return TestSuiteExecutor::process_string_includes(
+                        TestSuiteExecutor::process_string_includes(
                             str_val_expected,
                             response_check,
-                            response,
+                            parser,
                         )
                     }
                     TestAssertionResponseCheckOperator::JsonEquals => {
-                        return TestSuiteExecutor::process_string_json_equals(
+                        TestSuiteExecutor::process_string_json_equals(
                             str_val_expected,
                             response_check,
-                            response,
+                            parser,
+                        )
+                    }
+                    TestAssertionResponseCheckOperator::JsonIncludes { unordered_arrays } => {
+                        TestSuiteExecutor::process_string_json_includes(
+                            str_val_expected,
+                            unordered_arrays,
+                            response_check,
+                            parser,
                         )
                     }
                     TestAssertionResponseCheckOperator::Length => {
                         let error_message = format!("Operator length not allowed for string value of expression: '{}'. If value is '4' use 4 instead.", response_check.expression);
-                        return Err(new_error(
+                        Err(new_error(
                             ErrorKind::InvalidTestAssertionResponseCheckEvaluation,
                             error_message,
                             None,
-                        ));
+                        ))
                     }
                     TestAssertionResponseCheckOperator::NotEquals => {
-                        return TestSuiteExecutor::process_string_not_equals(
+                        TestSuiteExecutor::process_string_not_equals(
                             str_val_expected,
                             response_check,
-                            response,
+                            parser,
                         )
                     }
+                    TestAssertionResponseCheckOperator::Schema => {
+                        // schema value is compiled when test suite is loaded
+                        let error_message = format!(
+                            "Operator schema requires compiled json schema value. expression: {}",
+                            response_check.expression
+                        );
+                        Err(new_error(
+                            ErrorKind::InvalidTestAssertionResponseCheckEvaluation,
+                            error_message,
+                            None,
+                        ))
+                    }
+                    TestAssertionResponseCheckOperator::BotSays
+                    | TestAssertionResponseCheckOperator::QuickReplies
+                    | TestAssertionResponseCheckOperator::Card
+                    | TestAssertionResponseCheckOperator::ExpectParameters
+                    | TestAssertionResponseCheckOperator::ExpectContexts
+                    | TestAssertionResponseCheckOperator::AnyOf
+                    | TestAssertionResponseCheckOperator::AllOf
+                    | TestAssertionResponseCheckOperator::Not => {
+                        let error_message = format!(
+                            "Operator {} not allowed for string value of expression: {}",
+                            response_check.operator, response_check.expression
+                        );
+                        Err(new_error(
+                            ErrorKind::InvalidTestAssertionResponseCheckEvaluation,
+                            error_message,
+                            None,
+                        ))
+                    }
+                    TestAssertionResponseCheckOperator::Snapshot => {
+                        let error_message = format!(
+                            "Operator snapshot not allowed for string value of expression: {}",
+                            response_check.expression
+                        );
+                        Err(new_error(
+                            ErrorKind::InvalidTestAssertionResponseCheckEvaluation,
+                            error_message,
+                            None,
+                        ))
+                    }
                 }
             }
 
             TestAssertionResponseCheckValue::NumVal(num_val_expected) => {
                 match response_check.operator {
                     TestAssertionResponseCheckOperator::Equals => {
-                        return TestSuiteExecutor::process_num_equals(
+                        TestSuiteExecutor::process_num_equals(
                             num_val_expected,
                             response_check,
-                            response,
+                            parser,
                         )
                     }
                     TestAssertionResponseCheckOperator::Includes => {
@@ -703,37 +1141,88 @@ impl<'a> TestSuiteExecutor<'a> {
                             "Operator includes not allowed for numeric value of expression: {}",
                             response_check.expression
                         );
-                        return Err(new_error(
+                        Err(new_error(
                             ErrorKind::InvalidTestAssertionResponseCheckEvaluation,
                             error_message,
                             None,
-                        ));
+                        ))
                     }
                     TestAssertionResponseCheckOperator::JsonEquals => {
                         let error_message = format!(
                             "Operator jsonequals not allowed for numeric value of expression: {}",
                             response_check.expression
                         );
-                        return Err(new_error(
+                        Err(new_error(
                             ErrorKind::InvalidTestAssertionResponseCheckEvaluation,
                             error_message,
                             None,
-                        ));
+                        ))
+                    }
+                    TestAssertionResponseCheckOperator::JsonIncludes { .. } => {
+                        let error_message = format!(
+                            "Operator jsonincludes not allowed for numeric value of expression: {}",
+                            response_check.expression
+                        );
+                        Err(new_error(
+                            ErrorKind::InvalidTestAssertionResponseCheckEvaluation,
+                            error_message,
+                            None,
+                        ))
                     }
                     TestAssertionResponseCheckOperator::Length => {
-                        return TestSuiteExecutor::process_num_length(
+                        TestSuiteExecutor::process_num_length(
                             num_val_expected,
                             response_check,
-                            response,
+                            parser,
                         )
                     }
                     TestAssertionResponseCheckOperator::NotEquals => {
-                        return TestSuiteExecutor::process_num_not_equals(
+                        TestSuiteExecutor::process_num_not_equals(
                             num_val_expected,
                             response_check,
-                            response,
+                            parser,
                         )
                     }
+                    TestAssertionResponseCheckOperator::Schema => {
+                        let error_message = format!(
+                            "Operator schema not allowed for numeric value of expression: {}",
+                            response_check.expression
+                        );
+                        Err(new_error(
+                            ErrorKind::InvalidTestAssertionResponseCheckEvaluation,
+                            error_message,
+                            None,
+                        ))
+                    }
+                    TestAssertionResponseCheckOperator::BotSays
+                    | TestAssertionResponseCheckOperator::QuickReplies
+                    | TestAssertionResponseCheckOperator::Card
+                    | TestAssertionResponseCheckOperator::ExpectParameters
+                    | TestAssertionResponseCheckOperator::ExpectContexts
+                    | TestAssertionResponseCheckOperator::AnyOf
+                    | TestAssertionResponseCheckOperator::AllOf
+                    | TestAssertionResponseCheckOperator::Not => {
+                        let error_message = format!(
+                            "Operator {} not allowed for numeric value of expression: {}",
+                            response_check.operator, response_check.expression
+                        );
+                        Err(new_error(
+                            ErrorKind::InvalidTestAssertionResponseCheckEvaluation,
+                            error_message,
+                            None,
+                        ))
+                    }
+                    TestAssertionResponseCheckOperator::Snapshot => {
+                        let error_message = format!(
+                            "Operator snapshot not allowed for numeric value of expression: {}",
+                            response_check.expression
+                        );
+                        Err(new_error(
+                            ErrorKind::InvalidTestAssertionResponseCheckEvaluation,
+                            error_message,
+                            None,
+                        ))
+                    }
                 }
             }
         }
@@ -867,10 +1356,10 @@ mod tests {
                     ErrorKind::InvalidTestAssertionResponseCheckEvaluation => {
                         assert_eq!(err.message, "Expected value 'foo.bar' does not match real value: 'input.welcome' for expression: queryResult.action");
                     },
-                    _ => assert!(false, "Expected InvalidTestAssertionResponseCheckEvaluation error, got different error")
+                    _ => panic!("Expected InvalidTestAssertionResponseCheckEvaluation error, got different error")
                 }
             },
-            _ => assert!(false, "Expected InvalidTestAssertionResponseCheckEvaluation error")
+            _ => panic!("Expected InvalidTestAssertionResponseCheckEvaluation error")
         }
 
         match TestSuiteExecutor::process_assertion_response_check(&check_ko_2, JSON) {
@@ -879,10 +1368,10 @@ mod tests {
                     ErrorKind::InvalidTestAssertionResponseCheckEvaluation => {
                         assert_eq!(err.message, "Unable to retrieve string value for expression: queryResult.action.does.not.exists");
                     },
-                    _ => assert!(false, "Expected InvalidTestAssertionResponseCheckEvaluation error, got different error")
+                    _ => panic!("Expected InvalidTestAssertionResponseCheckEvaluation error, got different error")
                 }
             },
-            _ => assert!(false, "Expected InvalidTestAssertionResponseCheckEvaluation error")
+            _ => panic!("Expected InvalidTestAssertionResponseCheckEvaluation error")
         }
     }
 
@@ -917,10 +1406,10 @@ mod tests {
                     ErrorKind::InvalidTestAssertionResponseCheckEvaluation => {
                         assert_eq!(err.message, "Expected value 'foo.bar' not included in real value: 'input.welcome' for expression: queryResult.action");
                     },
-                    _ => assert!(false, "Expected InvalidTestAssertionResponseCheckEvaluation error, got different error")
+                    _ => panic!("Expected InvalidTestAssertionResponseCheckEvaluation error, got different error")
                 }
             },
-            _ => assert!(false, "Expected InvalidTestAssertionResponseCheckEvaluation error")
+            _ => panic!("Expected InvalidTestAssertionResponseCheckEvaluation error")
         }
 
         match TestSuiteExecutor::process_assertion_response_check(&check_ko_2, JSON) {
@@ -929,10 +1418,10 @@ mod tests {
                     ErrorKind::InvalidTestAssertionResponseCheckEvaluation => {
                         assert_eq!(err.message, "Unable to retrieve string value for expression: queryResult.action.does.not.exists");
                     },
-                    _ => assert!(false, "Expected InvalidTestAssertionResponseCheckEvaluation error, got different error")
+                    _ => panic!("Expected InvalidTestAssertionResponseCheckEvaluation error, got different error")
                 }
             },
-            _ => assert!(false, "Expected InvalidTestAssertionResponseCheckEvaluation error")
+            _ => panic!("Expected InvalidTestAssertionResponseCheckEvaluation error")
         }
     }
 
@@ -967,10 +1456,10 @@ mod tests {
                     ErrorKind::InvalidTestAssertionResponseCheckEvaluation => {
                         assert_eq!(err.message, "Expected value 'input.welcome' does match real value: 'input.welcome' for expression: queryResult.action");
                     },
-                    _ => assert!(false, "Expected InvalidTestAssertionResponseCheckEvaluation error, got different error")
+                    _ => panic!("Expected InvalidTestAssertionResponseCheckEvaluation error, got different error")
                 }
             },
-            _ => assert!(false, "Expected InvalidTestAssertionResponseCheckEvaluation error")
+            _ => panic!("Expected InvalidTestAssertionResponseCheckEvaluation error")
         }
 
         match TestSuiteExecutor::process_assertion_response_check(&check_ko_2, JSON) {
@@ -979,10 +1468,10 @@ mod tests {
                     ErrorKind::InvalidTestAssertionResponseCheckEvaluation => {
                         assert_eq!(err.message, "Unable to retrieve string value for expression: queryResult.action.does.not.exists");
                     },
-                    _ => assert!(false, "Expected InvalidTestAssertionResponseCheckEvaluation error, got different error")
+                    _ => panic!("Expected InvalidTestAssertionResponseCheckEvaluation error, got different error")
                 }
             },
-            _ => assert!(false, "Expected InvalidTestAssertionResponseCheckEvaluation error")
+            _ => panic!("Expected InvalidTestAssertionResponseCheckEvaluation error")
         }
     }
 
@@ -1000,11 +1489,153 @@ mod tests {
                     ErrorKind::InvalidTestAssertionResponseCheckEvaluation => {
                         assert_eq!(err.message, "Operator length not allowed for string value of expression: 'queryResult.action'. If value is '4' use 4 instead.");
                     },
-                    _ => assert!(false, "Expected InvalidTestAssertionResponseCheckEvaluation error, got different error")
+                    _ => panic!("Expected InvalidTestAssertionResponseCheckEvaluation error, got different error")
                 }
             },
-            _ => assert!(false, "Expected InvalidTestAssertionResponseCheckEvaluation error")
+            _ => panic!("Expected InvalidTestAssertionResponseCheckEvaluation error")
+        }
+    }
+
+    #[test]
+    fn test_process_assertion_response_check_str_json_includes() {
+        let check_ok: TestAssertionResponseCheck = TestAssertionResponseCheck::new(
+            "queryResult".to_string(),
+            TestAssertionResponseCheckOperator::JsonIncludes {
+                unordered_arrays: false,
+            },
+            TestAssertionResponseCheckValue::StrVal(
+                r#"{"action": "input.welcome", "outputContexts": [{"lifespanCount": 1}]}"#
+                    .to_string(),
+            ),
+        );
+
+        let check_ko: TestAssertionResponseCheck = TestAssertionResponseCheck::new(
+            "queryResult".to_string(),
+            TestAssertionResponseCheckOperator::JsonIncludes {
+                unordered_arrays: true,
+            },
+            TestAssertionResponseCheckValue::StrVal(
+                r#"{"action": "input.unknown", "parameters": {"tracking_id": "1234"}}"#.to_string(),
+            ),
+        );
+
+        assert_eq!(
+            TestSuiteExecutor::process_assertion_response_check(&check_ok, JSON).unwrap(),
+            ()
+        );
+
+        let err = TestSuiteExecutor::process_assertion_response_check(&check_ko, JSON).unwrap_err();
+        assert_eq!(
+            err.message,
+            "Expected json not included in value of expression 'queryResult'. Differences:\n.action: expected \"input.unknown\", actual \"input.welcome\"\n.parameters.tracking_id: missing"
+        );
+    }
+
+    #[test]
+    fn test_process_assertion_response_check_groups() {
+        let action_check = |action: &str| {
+            TestAssertionResponseCheck::new(
+                "queryResult.action".to_string(),
+                TestAssertionResponseCheckOperator::Equals,
+                TestAssertionResponseCheckValue::StrVal(action.to_string()),
+            )
+        };
+
+        let any_of = TestAssertionResponseCheck::new(
+            String::new(),
+            TestAssertionResponseCheckOperator::AnyOf,
+            TestAssertionResponseCheckValue::GroupVal(vec![
+                action_check("input.unknown"),
+                TestAssertionResponseCheck::new(
+                    String::new(),
+                    TestAssertionResponseCheckOperator::Not,
+                    TestAssertionResponseCheckValue::GroupVal(vec![action_check("input.welcome")]),
+                ),
+            ]),
+        );
+
+        let rows = TestSuiteExecutor::evaluate_response_check_tree(&any_of, JSON);
+        assert_eq!(
+            rows.iter()
+                .map(|row| (row.depth, row.ok))
+                .collect::<Vec<(usize, bool)>>(),
+            vec![(0, false), (1, false), (1, false), (2, true)]
+        );
+
+        let err = TestSuiteExecutor::process_assertion_response_check(&any_of, JSON).unwrap_err();
+        assert_eq!(
+            err.message,
+            "Response check group anyOf failed:
+[KO] anyOf
+  [KO] queryResult.action = input.unknown
+       Expected value 'input.unknown' does not match real value: 'input.welcome' for expression: queryResult.action
+  [KO] not
+    [OK] queryResult.action = input.welcome"
+        );
+
+        let all_of = TestAssertionResponseCheck::new(
+            String::new(),
+            TestAssertionResponseCheckOperator::AllOf,
+            TestAssertionResponseCheckValue::GroupVal(vec![
+                action_check("input.welcome"),
+                TestAssertionResponseCheck::new(
+                    String::new(),
+                    TestAssertionResponseCheckOperator::Not,
+                    TestAssertionResponseCheckValue::GroupVal(vec![action_check("input.unknown")]),
+                ),
+            ]),
+        );
+        assert!(TestSuiteExecutor::process_assertion_response_check(&all_of, JSON).is_ok());
+    }
+
+    #[test]
+    fn test_process_assertion_response_check_schema() -> Result<()> {
+        let check_ok: TestAssertionResponseCheck = TestAssertionResponseCheck::new(
+            "queryResult.fulfillmentMessages[2]".to_string(),
+            TestAssertionResponseCheckOperator::Schema,
+            TestAssertionResponseCheckValue::SchemaVal(JsonSchema::compile(
+                r#"{
+                    "type": "object",
+                    "required": ["quickReplies", "platform"],
+                    "properties": {
+                        "quickReplies": {
+                            "type": "object",
+                            "properties": {"quickReplies": {"type": "array", "items": {"type": "string"}}}
+                        }
+                    }
+                }"#,
+            )?),
+        );
+
+        let check_ko: TestAssertionResponseCheck = TestAssertionResponseCheck::new(
+            "queryResult.fulfillmentMessages[2]".to_string(),
+            TestAssertionResponseCheckOperator::Schema,
+            TestAssertionResponseCheckValue::SchemaVal(JsonSchema::compile(
+                r#"{
+                    "required": ["payload"],
+                    "properties": {
+                        "platform": {"enum": ["ACTIONS_ON_GOOGLE"]},
+                        "quickReplies": {"properties": {"quickReplies": {"maxItems": 3}}}
+                    }
+                }"#,
+            )?),
+        );
+
+        assert_eq!(
+            TestSuiteExecutor::process_assertion_response_check(&check_ok, JSON).unwrap(),
+            ()
+        );
+
+        let err = TestSuiteExecutor::process_assertion_response_check(&check_ko, JSON).unwrap_err();
+        match *err.kind {
+            ErrorKind::InvalidTestAssertionResponseCheckEvaluation => {}
+            _ => panic!("Expected InvalidTestAssertionResponseCheckEvaluation error"),
         }
+        assert_eq!(
+            err.message,
+            "Value of expression 'queryResult.fulfillmentMessages[2]' does not match json schema. Violations:\n<root>: missing required property 'payload'\n/platform: value \"FACEBOOK\" is not one of [\"ACTIONS_ON_GOOGLE\"]\n/quickReplies/quickReplies: expected at most 3 items, got 5"
+        );
+        Ok(())
     }
 
     #[test]
@@ -1062,17 +1693,14 @@ mod tests {
                         json atom at path "[0].name2" is missing from lhs
                         */
 
-                        assert_eq!(err.message.contains("Arrays not matching for expression 'queryResult.outputContexts'"), true);
-                        assert_eq!(err.message.contains(r#"json atoms at path "[0].lifespanCount" are not equal"#), true);
-                        assert_eq!(err.message.contains(r#"json atom at path "[0].name2" is missing from lhs"#), true);
+                        assert!(err.message.contains("Arrays not matching for expression 'queryResult.outputContexts'"));
+                        assert!(err.message.contains(r#"json atoms at path "[0].lifespanCount" are not equal"#));
+                        assert!(err.message.contains(r#"json atom at path "[0].name2" is missing from lhs"#));
                     },
-                    _ => assert!(false, "Expected InvalidTestAssertionResponseCheckEvaluation error, got different error")
+                    _ => panic!("Expected InvalidTestAssertionResponseCheckEvaluation error, got different error")
                 }
             }
-            _ => assert!(
-                false,
-                "Expected InvalidTestAssertionResponseCheckEvaluation error"
-            ),
+            _ => panic!("Expected InvalidTestAssertionResponseCheckEvaluation error"),
         }
 
         match TestSuiteExecutor::process_assertion_response_check(&check_ko_2, JSON) {
@@ -1081,10 +1709,10 @@ mod tests {
                     ErrorKind::InvalidTestAssertionResponseCheckEvaluation => {
                         assert_eq!(err.message, "Unable to retrieve string value for expression: queryResult.outputContexts.does.not.exists");
                     },
-                    _ => assert!(false, "Expected InvalidTestAssertionResponseCheckEvaluation error, got different error")
+                    _ => panic!("Expected InvalidTestAssertionResponseCheckEvaluation error, got different error")
                 }
             },
-            _ => assert!(false, "Expected InvalidTestAssertionResponseCheckEvaluation error")
+            _ => panic!("Expected InvalidTestAssertionResponseCheckEvaluation error")
         }
     }
 
@@ -1160,18 +1788,15 @@ mod tests {
                         json atom at path ".name2" is missing from lhs
                         */
 
-                        assert_eq!(err.message.contains("Objects not matching for expression 'queryResult.outputContexts[0]'"), true);
-                        assert_eq!(err.message.contains(r#"json atoms at path ".lifespanCount" are not equal:"#), true);
-                        assert_eq!(err.message.contains(r#"json atom at path ".name" is missing from rhs"#), true);
-                        assert_eq!(err.message.contains(r#"json atom at path ".name2" is missing from lhs"#), true);
+                        assert!(err.message.contains("Objects not matching for expression 'queryResult.outputContexts[0]'"));
+                        assert!(err.message.contains(r#"json atoms at path ".lifespanCount" are not equal:"#));
+                        assert!(err.message.contains(r#"json atom at path ".name" is missing from rhs"#));
+                        assert!(err.message.contains(r#"json atom at path ".name2" is missing from lhs"#));
                     },
-                    _ => assert!(false, "Expected InvalidTestAssertionResponseCheckEvaluation error, got different error")
+                    _ => panic!("Expected InvalidTestAssertionResponseCheckEvaluation error, got different error")
                 }
             }
-            _ => assert!(
-                false,
-                "Expected InvalidTestAssertionResponseCheckEvaluation error"
-            ),
+            _ => panic!("Expected InvalidTestAssertionResponseCheckEvaluation error"),
         }
 
         match TestSuiteExecutor::process_assertion_response_check(&check_ko_2, JSON) {
@@ -1180,10 +1805,10 @@ mod tests {
                     ErrorKind::InvalidTestAssertionResponseCheckEvaluation => {
                         assert_eq!(err.message, "Unable to retrieve string value for expression: queryResult.outputContexts.does.not.exists");
                     },
-                    _ => assert!(false, "Expected InvalidTestAssertionResponseCheckEvaluation error, got different error")
+                    _ => panic!("Expected InvalidTestAssertionResponseCheckEvaluation error, got different error")
                 }
             },
-            _ => assert!(false, "Expected InvalidTestAssertionResponseCheckEvaluation error")
+            _ => panic!("Expected InvalidTestAssertionResponseCheckEvaluation error")
         }
     }
 
@@ -1218,10 +1843,10 @@ mod tests {
                     ErrorKind::InvalidTestAssertionResponseCheckEvaluation => {
                         assert_eq!(err.message, "Expected value (false) does not match real value: (true) for expression: queryResult.allRequiredParamsPresent");
                     },
-                    _ => assert!(false, "Expected InvalidTestAssertionResponseCheckEvaluation error, got different error")
+                    _ => panic!("Expected InvalidTestAssertionResponseCheckEvaluation error, got different error")
                 }
             },
-            _ => assert!(false, "Expected InvalidTestAssertionResponseCheckEvaluation error")
+            _ => panic!("Expected InvalidTestAssertionResponseCheckEvaluation error")
         }
 
         match TestSuiteExecutor::process_assertion_response_check(&check_ko_2, JSON) {
@@ -1230,10 +1855,10 @@ mod tests {
                     ErrorKind::InvalidTestAssertionResponseCheckEvaluation => {
                         assert_eq!(err.message, "Unable to retrieve boolean value (true) for expression: queryResult.allRequiredParamsPresent.does.not.exists");
                     },
-                    _ => assert!(false, "Expected InvalidTestAssertionResponseCheckEvaluation error, got different error")
+                    _ => panic!("Expected InvalidTestAssertionResponseCheckEvaluation error, got different error")
                 }
             },
-            _ => assert!(false, "Expected InvalidTestAssertionResponseCheckEvaluation error")
+            _ => panic!("Expected InvalidTestAssertionResponseCheckEvaluation error")
         }
     }
 
@@ -1268,10 +1893,10 @@ mod tests {
                     ErrorKind::InvalidTestAssertionResponseCheckEvaluation => {
                         assert_eq!(err.message, "Expected value (false), got instead value: (true) for expression: queryResult.allRequiredParamsPresent");
                     },
-                    _ => assert!(false, "Expected InvalidTestAssertionResponseCheckEvaluation error, got different error")
+                    _ => panic!("Expected InvalidTestAssertionResponseCheckEvaluation error, got different error")
                 }
             },
-            _ => assert!(false, "Expected InvalidTestAssertionResponseCheckEvaluation error")
+            _ => panic!("Expected InvalidTestAssertionResponseCheckEvaluation error")
         }
 
         match TestSuiteExecutor::process_assertion_response_check(&check_ko_2, JSON) {
@@ -1280,10 +1905,10 @@ mod tests {
                     ErrorKind::InvalidTestAssertionResponseCheckEvaluation => {
                         assert_eq!(err.message, "Unable to retrieve boolean value (false) for expression: queryResult.allRequiredParamsPresent.does.not.exists");
                     },
-                    _ => assert!(false, "Expected InvalidTestAssertionResponseCheckEvaluation error, got different error")
+                    _ => panic!("Expected InvalidTestAssertionResponseCheckEvaluation error, got different error")
                 }
             },
-            _ => assert!(false, "Expected InvalidTestAssertionResponseCheckEvaluation error")
+            _ => panic!("Expected InvalidTestAssertionResponseCheckEvaluation error")
         }
     }
 
@@ -1301,10 +1926,10 @@ mod tests {
                     ErrorKind::InvalidTestAssertionResponseCheckEvaluation => {
                         assert_eq!(err.message, "Operator includes not allowed for boolean value of expression: queryResult.allRequiredParamsPresent");
                     },
-                    _ => assert!(false, "Expected InvalidTestAssertionResponseCheckEvaluation error, got different error")
+                    _ => panic!("Expected InvalidTestAssertionResponseCheckEvaluation error, got different error")
                 }
             },
-            _ => assert!(false, "Expected InvalidTestAssertionResponseCheckEvaluation error")
+            _ => panic!("Expected InvalidTestAssertionResponseCheckEvaluation error")
         }
     }
 
@@ -1322,10 +1947,10 @@ mod tests {
                     ErrorKind::InvalidTestAssertionResponseCheckEvaluation => {
                         assert_eq!(err.message, "Operator jsonequals not allowed for boolean value of expression: queryResult.allRequiredParamsPresent");
                     },
-                    _ => assert!(false, "Expected InvalidTestAssertionResponseCheckEvaluation error, got different error")
+                    _ => panic!("Expected InvalidTestAssertionResponseCheckEvaluation error, got different error")
                 }
             },
-            _ => assert!(false, "Expected InvalidTestAssertionResponseCheckEvaluation error")
+            _ => panic!("Expected InvalidTestAssertionResponseCheckEvaluation error")
         }
     }
 
@@ -1343,10 +1968,10 @@ mod tests {
                     ErrorKind::InvalidTestAssertionResponseCheckEvaluation => {
                         assert_eq!(err.message, "Operator length not allowed for boolean value of expression: queryResult.allRequiredParamsPresent");
                     },
-                    _ => assert!(false, "Expected InvalidTestAssertionResponseCheckEvaluation error, got different error")
+                    _ => panic!("Expected InvalidTestAssertionResponseCheckEvaluation error, got different error")
                 }
             },
-            _ => assert!(false, "Expected InvalidTestAssertionResponseCheckEvaluation error")
+            _ => panic!("Expected InvalidTestAssertionResponseCheckEvaluation error")
         }
     }
 
@@ -1381,10 +2006,10 @@ mod tests {
                     ErrorKind::InvalidTestAssertionResponseCheckEvaluation => {
                         assert_eq!(err.message, "Expected value (2) does not match real value: (1) for expression: queryResult.outputContexts[0].lifespanCount");
                     },
-                    _ => assert!(false, "Expected InvalidTestAssertionResponseCheckEvaluation error, got different error")
+                    _ => panic!("Expected InvalidTestAssertionResponseCheckEvaluation error, got different error")
                 }
             },
-            _ => assert!(false, "Expected InvalidTestAssertionResponseCheckEvaluation error")
+            _ => panic!("Expected InvalidTestAssertionResponseCheckEvaluation error")
         }
 
         match TestSuiteExecutor::process_assertion_response_check(&check_ko_2, JSON) {
@@ -1393,10 +2018,10 @@ mod tests {
                     ErrorKind::InvalidTestAssertionResponseCheckEvaluation => {
                         assert_eq!(err.message, "Unable to retrieve numerical value for expression: queryResult.outputContexts[0].lifespanCount.does.not.exists");
                     },
-                    _ => assert!(false, "Expected InvalidTestAssertionResponseCheckEvaluation error, got different error")
+                    _ => panic!("Expected InvalidTestAssertionResponseCheckEvaluation error, got different error")
                 }
             },
-            _ => assert!(false, "Expected InvalidTestAssertionResponseCheckEvaluation error")
+            _ => panic!("Expected InvalidTestAssertionResponseCheckEvaluation error")
         }
     }
 
@@ -1431,10 +2056,10 @@ mod tests {
                     ErrorKind::InvalidTestAssertionResponseCheckEvaluation => {
                         assert_eq!(err.message, "Expected value not equal to (1) got value: (1) for expression: queryResult.outputContexts[0].lifespanCount");
                     },
-                    _ => assert!(false, "Expected InvalidTestAssertionResponseCheckEvaluation error, got different error")
+                    _ => panic!("Expected InvalidTestAssertionResponseCheckEvaluation error, got different error")
                 }
             },
-            _ => assert!(false, "Expected InvalidTestAssertionResponseCheckEvaluation error")
+            _ => panic!("Expected InvalidTestAssertionResponseCheckEvaluation error")
         }
 
         match TestSuiteExecutor::process_assertion_response_check(&check_ko_2, JSON) {
@@ -1443,10 +2068,10 @@ mod tests {
                     ErrorKind::InvalidTestAssertionResponseCheckEvaluation => {
                         assert_eq!(err.message, "Unable to retrieve numerical value for expression: queryResult.outputContexts[0].lifespanCount.does.not.exists");
                     },
-                    _ => assert!(false, "Expected InvalidTestAssertionResponseCheckEvaluation error, got different error")
+                    _ => panic!("Expected InvalidTestAssertionResponseCheckEvaluation error, got different error")
                 }
             },
-            _ => assert!(false, "Expected InvalidTestAssertionResponseCheckEvaluation error")
+            _ => panic!("Expected InvalidTestAssertionResponseCheckEvaluation error")
         }
     }
 
@@ -1464,10 +2089,10 @@ mod tests {
                     ErrorKind::InvalidTestAssertionResponseCheckEvaluation => {
                         assert_eq!(err.message, "Operator includes not allowed for numeric value of expression: queryResult.allRequiredParamsPresent");
                     },
-                    _ => assert!(false, "Expected InvalidTestAssertionResponseCheckEvaluation error, got different error")
+                    _ => panic!("Expected InvalidTestAssertionResponseCheckEvaluation error, got different error")
                 }
             },
-            _ => assert!(false, "Expected InvalidTestAssertionResponseCheckEvaluation error")
+            _ => panic!("Expected InvalidTestAssertionResponseCheckEvaluation error")
         }
     }
 
@@ -1485,10 +2110,10 @@ mod tests {
                     ErrorKind::InvalidTestAssertionResponseCheckEvaluation => {
                         assert_eq!(err.message, "Operator jsonequals not allowed for numeric value of expression: queryResult.allRequiredParamsPresent");
                     },
-                    _ => assert!(false, "Expected InvalidTestAssertionResponseCheckEvaluation error, got different error")
+                    _ => panic!("Expected InvalidTestAssertionResponseCheckEvaluation error, got different error")
                 }
             },
-            _ => assert!(false, "Expected InvalidTestAssertionResponseCheckEvaluation error")
+            _ => panic!("Expected InvalidTestAssertionResponseCheckEvaluation error")
         }
     }
 
@@ -1529,10 +2154,10 @@ mod tests {
                     ErrorKind::InvalidTestAssertionResponseCheckEvaluation => {
                         assert_eq!(err.message, "Expected array length 2, got 1 for expression: queryResult.outputContexts");
                     },
-                    _ => assert!(false, "Expected InvalidTestAssertionResponseCheckEvaluation error, got different error")
+                    _ => panic!("Expected InvalidTestAssertionResponseCheckEvaluation error, got different error")
                 }
             },
-            _ => assert!(false, "Expected InvalidTestAssertionResponseCheckEvaluation error")
+            _ => panic!("Expected InvalidTestAssertionResponseCheckEvaluation error")
         }
 
         match TestSuiteExecutor::process_assertion_response_check(&check_ko_2, JSON) {
@@ -1541,10 +2166,10 @@ mod tests {
                     ErrorKind::InvalidTestAssertionResponseCheckEvaluation => {
                         assert_eq!(err.message, "Unable to retrieve array value for expression: queryResult.outputContexts.does.not.exists");
                     },
-                    _ => assert!(false, "Expected InvalidTestAssertionResponseCheckEvaluation error, got different error")
+                    _ => panic!("Expected InvalidTestAssertionResponseCheckEvaluation error, got different error")
                 }
             },
-            _ => assert!(false, "Expected InvalidTestAssertionResponseCheckEvaluation error")
+            _ => panic!("Expected InvalidTestAssertionResponseCheckEvaluation error")
         }
 
         match TestSuiteExecutor::process_assertion_response_check(&check_ko_3, JSON) {
@@ -1553,10 +2178,10 @@ mod tests {
                     ErrorKind::InvalidTestAssertionResponseCheckEvaluation => {
                         assert_eq!(err.message, "Operator length allowed for array expressions only. Expression: queryResult.outputContexts[0]");
                     },
-                    _ => assert!(false, "Expected InvalidTestAssertionResponseCheckEvaluation error, got different error")
+                    _ => panic!("Expected InvalidTestAssertionResponseCheckEvaluation error, got different error")
                 }
             },
-            _ => assert!(false, "Expected InvalidTestAssertionResponseCheckEvaluation error")
+            _ => panic!("Expected InvalidTestAssertionResponseCheckEvaluation error")
         }
     }
 }
@@ -0,0 +1,108 @@
+// keys of the test suite file format, shared by suite parser and suite validator
+
+pub const SUITE_SPEC: &str = "suite-spec";
+pub const TESTS: &str = "tests";
+pub const CLASSIFICATION: &str = "classification";
+
+pub const NAME: &str = "name";
+pub const TYPE: &str = "type";
+pub const CONFIG: &str = "config";
+pub const SNAPSHOTS: &str = "snapshots";
+pub const MIN_CONFIDENCE: &str = "minConfidence";
+pub const CONFIDENCE_SEVERITY: &str = "confidenceSeverity";
+
+pub const DIR: &str = "dir";
+pub const IGNORE_PATHS: &str = "ignorePaths";
+
+pub const DESC: &str = "desc";
+pub const LANG: &str = "lang";
+pub const ASSERTIONS: &str = "assertions";
+pub const PARAMETERS: &str = "parameters";
+pub const SNAPSHOT: &str = "snapshot";
+
+pub const USER_SAYS: &str = "userSays";
+pub const BOT_RESPONDS_WITH: &str = "botRespondsWith";
+pub const BOT_DOES_NOT_RESPOND_WITH: &str = "botDoesNotRespondWith";
+pub const RESPONSE_CHECKS: &str = "responseChecks";
+pub const BOT_SAYS: &str = "botSays";
+pub const QUICK_REPLIES: &str = "quickReplies";
+pub const CARD: &str = "card";
+pub const EXPECT_PARAMETERS: &str = "expectParameters";
+pub const EXPECT_CONTEXTS: &str = "expectContexts";
+pub const IS_FALLBACK: &str = "isFallback";
+pub const SEVERITY: &str = "severity";
+
+pub const EXPRESSION: &str = "expression";
+pub const OPERATOR: &str = "operator";
+pub const VALUE: &str = "value";
+pub const ARRAY_ORDER: &str = "arrayOrder";
+pub const ANY_OF: &str = "anyOf";
+pub const ALL_OF: &str = "allOf";
+pub const NOT: &str = "not";
+
+pub const UTTERANCE: &str = "utterance";
+pub const INTENT: &str = "intent";
+
+pub const LIFESPAN: &str = "lifespan";
+
+pub const CREDENTIALS_FILE: &str = "credentials_file";
+pub const HTTP_PROXY: &str = "http_proxy";
+pub const VAP_URL: &str = "vap_url";
+pub const VAP_ACCESS_TOKEN: &str = "vap_access_token";
+pub const VAP_SVC_ACCOUNT_EMAIL: &str = "vap_svc_account_email";
+pub const VAP_SVC_ACCOUNT_PASSWORD: &str = "vap_svc_account_password";
+pub const VAP_CHANNEL_ID: &str = "vap_channel_id";
+pub const VAP_COUNTRY: &str = "vap_country";
+pub const VAP_CONTEXT_EXTRA: &str = "vap_context_extra";
+
+// allowed keys per section of the suite file
+pub const ROOT_KEYS: &[&str] = &[SUITE_SPEC, TESTS, CLASSIFICATION];
+pub const SUITE_SPEC_KEYS: &[&str] = &[
+    NAME,
+    TYPE,
+    CONFIG,
+    SNAPSHOTS,
+    MIN_CONFIDENCE,
+    CONFIDENCE_SEVERITY,
+];
+pub const SNAPSHOTS_KEYS: &[&str] = &[DIR, IGNORE_PATHS];
+pub const TEST_KEYS: &[&str] = &[NAME, DESC, LANG, ASSERTIONS, PARAMETERS, SNAPSHOT];
+pub const ASSERTION_KEYS: &[&str] = &[
+    USER_SAYS,
+    BOT_RESPONDS_WITH,
+    BOT_DOES_NOT_RESPOND_WITH,
+    RESPONSE_CHECKS,
+    BOT_SAYS,
+    QUICK_REPLIES,
+    CARD,
+    EXPECT_PARAMETERS,
+    EXPECT_CONTEXTS,
+    MIN_CONFIDENCE,
+    IS_FALLBACK,
+    SEVERITY,
+    CONFIDENCE_SEVERITY,
+    SNAPSHOT,
+];
+pub const RESPONSE_CHECK_KEYS: &[&str] = &[
+    EXPRESSION,
+    OPERATOR,
+    VALUE,
+    ARRAY_ORDER,
+    SEVERITY,
+    ANY_OF,
+    ALL_OF,
+    NOT,
+];
+pub const CLASSIFICATION_KEYS: &[&str] = &[UTTERANCE, INTENT, LANG];
+pub const CONTEXT_KEYS: &[&str] = &[NAME, LIFESPAN];
+
+pub const DIALOGFLOW_CONFIG_KEYS: &[&str] = &[CREDENTIALS_FILE, HTTP_PROXY];
+pub const VAP_CONFIG_KEYS: &[&str] = &[
+    VAP_URL,
+    VAP_ACCESS_TOKEN,
+    VAP_SVC_ACCOUNT_EMAIL,
+    VAP_SVC_ACCOUNT_PASSWORD,
+    VAP_CHANNEL_ID,
+    VAP_COUNTRY,
+    VAP_CONTEXT_EXTRA,
+];
@@ -0,0 +1,259 @@
+use std::path::Path;
+use yaml_rust::Yaml;
+
+use crate::errors::{new_error_from, Error, ErrorKind, Result, YamlParsingIssue};
+use crate::yaml_parser::{json_to_yaml, load_yaml, Test, TestSuite, TestSuiteSpec};
+use crate::yaml_positions::Position;
+
+// format of test suite definition file
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SuiteFormat {
+    Yaml,
+    Json,
+    Toml,
+}
+
+impl SuiteFormat {
+    // format is derived from file extension, files with unknown extension are considered yaml
+    pub fn from_path(path: &Path) -> SuiteFormat {
+        let extension = path
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .unwrap_or("")
+            .to_lowercase();
+        SuiteFormat::from_name(&extension).unwrap_or(SuiteFormat::Yaml)
+    }
+
+    pub fn from_name(name: &str) -> Option<SuiteFormat> {
+        match name {
+            "yaml" | "yml" => Some(SuiteFormat::Yaml),
+            "json" => Some(SuiteFormat::Json),
+            "toml" => Some(SuiteFormat::Toml),
+            _ => None,
+        }
+    }
+}
+
+// json report of previous run (see JsonResultReporter) contains tests only, i.e. suite-spec
+// must be provided separately (see suite_from_report)
+pub enum SuiteDefinition {
+    Suite(TestSuite),
+    Report(Vec<Test>),
+}
+
+// syntax errors of json and toml sources are reported as parsing issue with position, i.e. the same way
+// as issues of yaml suite. Position is removed from error message since it is displayed separately
+fn syntax_error(message: String, line: usize, col: usize) -> Error {
+    let message = message
+        .trim_end_matches(&format!(" at line {} column {}", line, col))
+        .to_string();
+    new_error_from(ErrorKind::YamlParsingError(vec![YamlParsingIssue {
+        message,
+        path: String::new(),
+        position: Some(Position { line, col }),
+    }]))
+}
+
+fn parse_json(content: &str) -> Result<serde_json::Value> {
+    serde_json::from_str(content)
+        .map_err(|err| syntax_error(err.to_string(), err.line(), err.column()))
+}
+
+fn parse_toml(content: &str) -> Result<toml::Value> {
+    toml::from_str(content).map_err(|err| match err.line_col() {
+        // toml positions are zero based
+        Some((line, col)) => syntax_error(err.to_string(), line + 1, col + 1),
+        None => err.into(),
+    })
+}
+
+pub(crate) fn to_yaml(content: &str, format: SuiteFormat) -> Result<Yaml> {
+    match format {
+        SuiteFormat::Yaml => load_yaml(content),
+        SuiteFormat::Json => Ok(json_to_yaml(&parse_json(content)?)),
+        SuiteFormat::Toml => Ok(json_to_yaml(&serde_json::to_value(parse_toml(content)?)?)),
+    }
+}
+
+// all formats are mapped to the same structure as yaml suite, i.e. the same keys and checks apply.
+// Relative paths in the suite (e.g. csv files) are resolved against base_dir (directory of suite file)
+pub fn load_suite(content: &str, format: SuiteFormat, base_dir: &Path) -> Result<SuiteDefinition> {
+    match format {
+        // yaml suite is parsed from source so that errors get line/column positions
+        SuiteFormat::Yaml => Ok(SuiteDefinition::Suite(TestSuite::from_yaml_str_in_dir(
+            content, base_dir,
+        )?)),
+        SuiteFormat::Json => {
+            let json = parse_json(content)?;
+            if json.is_array() {
+                let tests: Vec<Test> = serde_json::from_value(json)?;
+                return Ok(SuiteDefinition::Report(tests));
+            }
+            Ok(SuiteDefinition::Suite(TestSuite::from_yaml_in_dir(
+                &json_to_yaml(&json),
+                base_dir,
+            )?))
+        }
+        SuiteFormat::Toml => Ok(SuiteDefinition::Suite(TestSuite::from_yaml_in_dir(
+            &to_yaml(content, format)?,
+            base_dir,
+        )?)),
+    }
+}
+
+// parses suite-spec section only, tests are ignored
+pub fn load_suite_spec(content: &str, format: SuiteFormat) -> Result<TestSuiteSpec> {
+    TestSuite::spec_from_yaml(&to_yaml(content, format)?)
+}
+
+// tests of json report are run again as they are, results of previous run are discarded
+pub fn suite_from_report(tests: Vec<Test>, suite_spec: TestSuiteSpec) -> TestSuite {
+    let mut tests = tests;
+    for test in tests.iter_mut() {
+        test.reset_results();
+    }
+    TestSuite::new(suite_spec, tests)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::yaml_parser::TestResult;
+
+    const YAML: &str = "
+suite-spec:
+  name: 'Express Tracking'
+  type: 'DialogFlow'
+  config:
+    - credentials_file: '/path/to/cred'
+tests:
+  - name: 'Tracking'
+    assertions:
+      - userSays: 'track 1234567891'
+        botRespondsWith: 'Tracking'
+        responseChecks:
+          - expression: 'queryResult.parameters.tracking_id'
+            operator: 'length'
+            value: 10
+";
+
+    const JSON: &str = r#"{
+  "suite-spec": {
+    "name": "Express Tracking",
+    "type": "DialogFlow",
+    "config": [{ "credentials_file": "/path/to/cred" }]
+  },
+  "tests": [{
+    "name": "Tracking",
+    "assertions": [{
+      "userSays": "track 1234567891",
+      "botRespondsWith": "Tracking",
+      "responseChecks": [{
+        "expression": "queryResult.parameters.tracking_id",
+        "operator": "length",
+        "value": 10
+      }]
+    }]
+  }]
+}"#;
+
+    const TOML: &str = r#"
+[suite-spec]
+name = "Express Tracking"
+type = "DialogFlow"
+config = [{ credentials_file = "/path/to/cred" }]
+
+[[tests]]
+name = "Tracking"
+
+[[tests.assertions]]
+userSays = "track 1234567891"
+botRespondsWith = "Tracking"
+responseChecks = [{ expression = "queryResult.parameters.tracking_id", operator = "length", value = 10 }]
+"#;
+
+    fn unwrap_suite(definition: SuiteDefinition) -> TestSuite {
+        match definition {
+            SuiteDefinition::Suite(suite) => suite,
+            SuiteDefinition::Report(_) => panic!("Expected suite, got report!"),
+        }
+    }
+
+    #[test]
+    fn test_suite_format() {
+        assert_eq!(
+            SuiteFormat::from_path(Path::new("c:/tmp/suite.JSON")),
+            SuiteFormat::Json
+        );
+        assert_eq!(
+            SuiteFormat::from_path(Path::new("suite.toml")),
+            SuiteFormat::Toml
+        );
+        assert_eq!(
+            SuiteFormat::from_path(Path::new("suite")),
+            SuiteFormat::Yaml
+        );
+        assert_eq!(SuiteFormat::from_name("xml"), None);
+    }
+
+    #[test]
+    fn test_load_suite_formats() -> Result<()> {
+        let yaml_suite = unwrap_suite(load_suite(YAML, SuiteFormat::Yaml, Path::new(""))?);
+        for (content, format) in &[(JSON, SuiteFormat::Json), (TOML, SuiteFormat::Toml)] {
+            let suite = unwrap_suite(load_suite(content, *format, Path::new(""))?);
+            assert_eq!(suite.suite_spec.name, yaml_suite.suite_spec.name);
+            assert_eq!(suite.tests.len(), 1);
+            assert_eq!(
+                format!("{:?}", suite.tests[0].assertions),
+                format!("{:?}", yaml_suite.tests[0].assertions)
+            );
+        }
+
+        let spec = load_suite_spec(TOML, SuiteFormat::Toml)?;
+        assert_eq!(spec.config["credentials_file"], "/path/to/cred");
+        Ok(())
+    }
+
+    fn unwrap_issue(error: Error) -> YamlParsingIssue {
+        match *error.kind {
+            ErrorKind::YamlParsingError(mut issues) => issues.remove(0),
+            _ => panic!("Expected YamlParsingError, got different error type!"),
+        }
+    }
+
+    #[test]
+    fn test_syntax_error_positions() {
+        let issue = unwrap_issue(
+            load_suite("{\n  \"suite-spec\": }", SuiteFormat::Json, Path::new(""))
+                .err()
+                .unwrap(),
+        );
+        assert_eq!(issue.position, Some(Position { line: 2, col: 17 }));
+        assert_eq!(issue.message, "expected value");
+
+        let issue =
+            unwrap_issue(load_suite_spec("[suite-spec]\nname = ", SuiteFormat::Toml).unwrap_err());
+        assert_eq!(issue.position, Some(Position { line: 2, col: 8 }));
+        assert!(!issue.message.contains(" at line "));
+
+        let issue = unwrap_issue(load_suite_spec("# comment only", SuiteFormat::Yaml).unwrap_err());
+        assert_eq!(issue.message, "Test suite definition is empty");
+    }
+
+    #[test]
+    fn test_load_report() -> Result<()> {
+        let mut suite = unwrap_suite(load_suite(YAML, SuiteFormat::Yaml, Path::new(""))?);
+        suite.tests[0].test_result = Some(TestResult::Ko);
+        let report = serde_json::to_string_pretty(&suite.tests)?;
+
+        let tests = match load_suite(&report, SuiteFormat::Json, Path::new(""))? {
+            SuiteDefinition::Report(tests) => tests,
+            SuiteDefinition::Suite(_) => panic!("Expected report, got suite!"),
+        };
+        let rerun_suite = suite_from_report(tests, suite.suite_spec.clone());
+        assert_eq!(rerun_suite.tests.len(), 1);
+        assert!(rerun_suite.tests[0].test_result.is_none());
+        assert_eq!(rerun_suite.tests[0].assertions[0].response_checks.len(), 1);
+        Ok(())
+    }
+}
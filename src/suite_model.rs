@@ -0,0 +1,347 @@
+use schemars::gen::SchemaSettings;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+// serde model of test suite file format. It mirrors yaml structure (see README) and is used
+// to generate json schema of suite files (e.g. for editor autocompletion). Suites are parsed
+// by yaml parser (see TestSuite::from_yaml), model is kept in sync with it by tests
+// deserializing example suites
+
+/// Either single value or list of values
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(untagged)]
+pub enum OneOrMany<T> {
+    One(T),
+    Many(Vec<T>),
+}
+
+/// Scalar value (used for parameters and response check values)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(untagged)]
+pub enum ScalarValue {
+    Bool(bool),
+    Int(i64),
+    Num(f64),
+    Str(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub enum TestSuiteTypeModel {
+    DialogFlow,
+    DHLVAP,
+}
+
+/// Built-in NLP provider or name of custom suite type executed by registered test executor
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(untagged)]
+pub enum SuiteTypeModel {
+    BuiltIn(TestSuiteTypeModel),
+    Custom(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum SeverityModel {
+    Error,
+    Warning,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum ArrayOrderModel {
+    Ordered,
+    Unordered,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub enum OperatorModel {
+    #[serde(rename = "equals")]
+    Equals,
+    #[serde(rename = "!equals")]
+    NotEquals,
+    #[serde(rename = "jsonequals")]
+    JsonEquals,
+    #[serde(rename = "jsonincludes")]
+    JsonIncludes,
+    #[serde(rename = "includes")]
+    Includes,
+    #[serde(rename = "length")]
+    Length,
+    #[serde(rename = "schema")]
+    Schema,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct SnapshotsModel {
+    /// Directory of response snapshots, defaults to __snapshots__
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dir: Option<String>,
+    /// JMESPath-like paths ignored when comparing snapshots (e.g. responseId)
+    #[serde(rename = "ignorePaths", skip_serializing_if = "Option::is_none")]
+    pub ignore_paths: Option<Vec<String>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct TestSuiteSpecModel {
+    /// Human readable name of test suite
+    pub name: String,
+    /// NLP provider
+    #[serde(rename = "type")]
+    pub suite_type: SuiteTypeModel,
+    /// Provider configuration, list of single key maps (e.g. credentials_file)
+    pub config: Vec<HashMap<String, String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub snapshots: Option<SnapshotsModel>,
+    /// Default minimal intent detection confidence (0-1) of all assertions
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min_confidence: Option<f64>,
+    /// Default severity of minConfidence/isFallback checks of all assertions
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub confidence_severity: Option<SeverityModel>,
+}
+
+/// Either single response check or group of checks (anyOf, allOf, not)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(untagged)]
+pub enum TestAssertionResponseCheckModel {
+    Check(ResponseCheckModel),
+    AnyOf(AnyOfModel),
+    AllOf(AllOfModel),
+    Not(NotModel),
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct ResponseCheckModel {
+    /// JMESPath expression evaluated against NLP provider response
+    pub expression: String,
+    pub operator: OperatorModel,
+    /// Expected value, inline json schema or path to schema file for schema operator
+    pub value: ScalarValue,
+    /// Array comparison of jsonincludes operator, defaults to ordered
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub array_order: Option<ArrayOrderModel>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub severity: Option<SeverityModel>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct AnyOfModel {
+    /// At least one of the checks must pass
+    pub any_of: Vec<TestAssertionResponseCheckModel>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub severity: Option<SeverityModel>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct AllOfModel {
+    /// All of the checks must pass
+    pub all_of: Vec<TestAssertionResponseCheckModel>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub severity: Option<SeverityModel>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct NotModel {
+    /// Check (or all checks of the list) must fail
+    pub not: OneOrMany<Box<TestAssertionResponseCheckModel>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub severity: Option<SeverityModel>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct CardModel {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub subtitle: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub text: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub image_uri: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub buttons: Option<Vec<String>>,
+}
+
+/// Context name or context name with expected lifespan
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(untagged)]
+pub enum ContextModel {
+    Name(String),
+    WithLifespan {
+        name: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        lifespan: Option<i64>,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct TestAssertionModel {
+    /// Utterance sent to the bot, list of paraphrases expands into one test per paraphrase
+    pub user_says: OneOrMany<String>,
+    /// Expected intent rule(s): name, name prefix (prefix:) or regular expression (re:)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bot_responds_with: Option<OneOrMany<String>>,
+    /// Intent rule(s) which must not be detected
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bot_does_not_respond_with: Option<OneOrMany<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub response_checks: Option<Vec<TestAssertionResponseCheckModel>>,
+    /// Expected text message(s) of the bot
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bot_says: Option<OneOrMany<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub quick_replies: Option<OneOrMany<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub card: Option<OneOrMany<CardModel>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expect_parameters: Option<serde_json::Map<String, serde_json::Value>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expect_contexts: Option<Vec<ContextModel>>,
+    /// Minimal intent detection confidence (0-1)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min_confidence: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub is_fallback: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub severity: Option<SeverityModel>,
+    /// Severity of minConfidence/isFallback checks, assertion severity by default
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub confidence_severity: Option<SeverityModel>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub snapshot: Option<bool>,
+}
+
+/// Inline list of parameter rows or path to csv file
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(untagged)]
+pub enum ParametersModel {
+    Rows(Vec<HashMap<String, ScalarValue>>),
+    CsvFile(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct TestModel {
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub desc: Option<String>,
+    /// Language code, defaults to en
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub lang: Option<String>,
+    pub assertions: Vec<TestAssertionModel>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub parameters: Option<ParametersModel>,
+    /// Compare responses of all assertions with stored snapshots
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub snapshot: Option<bool>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct ClassificationItemModel {
+    pub utterance: String,
+    pub intent: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub lang: Option<String>,
+}
+
+/// Inline list of utterance/intent pairs or path to csv file
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(untagged)]
+pub enum ClassificationModel {
+    Items(Vec<ClassificationItemModel>),
+    CsvFile(String),
+}
+
+/// Test suite file of gdf_testing
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct TestSuiteModel {
+    #[serde(rename = "suite-spec")]
+    pub suite_spec: TestSuiteSpecModel,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tests: Option<Vec<TestModel>>,
+    /// Classification mode, cannot be combined with tests
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub classification: Option<ClassificationModel>,
+}
+
+// json schema of test suite file (e.g. for yaml language server used by VS Code)
+pub fn suite_schema() -> serde_json::Value {
+    // optional keys are omitted in suite files rather than set to null
+    let settings = SchemaSettings::draft07().with(|settings| settings.option_add_null_type = false);
+    let mut schema = settings
+        .into_generator()
+        .into_root_schema_for::<TestSuiteModel>();
+    schema.schema.metadata().title = Some("gdf_testing test suite".to_owned());
+    // safe to unwrap, schema is serializable by design
+    serde_json::to_value(schema).unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::yaml_parser::yaml_to_json;
+    use std::fs;
+    use yaml_rust::YamlLoader;
+
+    #[test]
+    fn test_examples_match_model() {
+        for example in &[
+            "examples/sample_gdf.yaml",
+            "examples/sample_vap.yaml",
+            "examples/complex_sample_1.yaml",
+            "examples/complex_sample_2.yaml",
+            "examples/classification_sample.yaml",
+        ] {
+            let yaml_str = fs::read_to_string(example).unwrap();
+            let docs = YamlLoader::load_from_str(&yaml_str).unwrap();
+            let json = yaml_to_json(&docs[0]).unwrap();
+            let model: serde_json::Result<TestSuiteModel> = serde_json::from_value(json);
+            if let Err(err) = model {
+                panic!("{} does not match model: {}", example, err);
+            }
+        }
+    }
+
+    #[test]
+    fn test_model_rejects_unknown_keys() {
+        let docs = YamlLoader::load_from_str(
+            "
+suite-spec:
+  name: 'Test'
+  type: 'DialogFlow'
+  config: []
+tests:
+  - name: 'Tracking'
+    assertions:
+      - userSays: 'track'
+        botRespondWith: 'Tracking'
+",
+        )
+        .unwrap();
+        let result: serde_json::Result<TestSuiteModel> =
+            serde_json::from_value(yaml_to_json(&docs[0]).unwrap());
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_suite_schema() {
+        let schema = suite_schema();
+        assert_eq!(schema["title"], "gdf_testing test suite");
+        assert_eq!(schema["required"], serde_json::json!(["suite-spec"]));
+        assert_eq!(
+            schema["definitions"]["TestSuiteTypeModel"]["enum"],
+            serde_json::json!(["DialogFlow", "DHLVAP"])
+        );
+    }
+}
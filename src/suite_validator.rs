@@ -0,0 +1,472 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::path::Path;
+use yaml_rust::{Yaml, YamlLoader};
+
+use crate::errors::{Error, ErrorKind};
+use crate::suite_keys as keys;
+use crate::suite_loader::{to_yaml, SuiteFormat};
+use crate::yaml_parser::TestSuite;
+use crate::yaml_positions::{child_path, item_path, Position, YamlPositions};
+
+// problem found in suite file, position is missing when it cannot be determined (e.g. in toml
+// suite), message then starts with path of the problematic key
+#[derive(Debug, PartialEq)]
+pub struct ValidationIssue {
+    pub position: Option<Position>,
+    pub message: String,
+}
+
+impl fmt::Display for ValidationIssue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.position {
+            Some(position) => write!(f, "{}: {}", position, self.message),
+            None => write!(f, "{}", self.message),
+        }
+    }
+}
+
+fn issue_at(
+    positions: &YamlPositions,
+    path: &str,
+    position: Option<Position>,
+    message: String,
+) -> ValidationIssue {
+    match position.or_else(|| positions.get(path)) {
+        Some(position) => ValidationIssue {
+            position: Some(position),
+            message,
+        },
+        None if !path.is_empty() => ValidationIssue {
+            position: None,
+            message: format!("{}: {}", path, message),
+        },
+        None => ValidationIssue {
+            position: None,
+            message,
+        },
+    }
+}
+
+// parsing errors are reported one issue per problem found
+fn error_issues(err: Error, positions: &YamlPositions) -> Vec<ValidationIssue> {
+    match *err.kind {
+        ErrorKind::YamlParsingError(parsing_issues) => parsing_issues
+            .into_iter()
+            .map(|parsing_issue| {
+                issue_at(
+                    positions,
+                    &parsing_issue.path,
+                    parsing_issue.position,
+                    parsing_issue.message,
+                )
+            })
+            .collect(),
+        _ => vec![ValidationIssue {
+            position: None,
+            message: err.message,
+        }],
+    }
+}
+
+struct SuiteLinter<'a> {
+    positions: &'a YamlPositions,
+    issues: Vec<ValidationIssue>,
+}
+
+impl<'a> SuiteLinter<'a> {
+    fn issue(&mut self, path: &str, message: String) {
+        self.issues
+            .push(issue_at(self.positions, path, None, message));
+    }
+
+    fn check_keys(&mut self, yaml: &Yaml, path: &str, allowed: &[&str], section: &str) {
+        if let Some(hash) = yaml.as_hash() {
+            for key in hash.keys() {
+                match key.as_str() {
+                    Some(key) if allowed.contains(&key) => {}
+                    Some(key) => self.issue(
+                        &child_path(path, key),
+                        format!(
+                            "unknown key '{}' in {}. Allowed keys: {}",
+                            key,
+                            section,
+                            allowed.join(", ")
+                        ),
+                    ),
+                    None => self.issue(path, format!("{} contains non-string key", section)),
+                }
+            }
+        }
+    }
+
+    fn check_suite_spec(&mut self, yaml: &Yaml) {
+        let suite_spec = &yaml[keys::SUITE_SPEC];
+        self.check_keys(
+            suite_spec,
+            keys::SUITE_SPEC,
+            keys::SUITE_SPEC_KEYS,
+            "suite-spec",
+        );
+        self.check_keys(
+            &suite_spec[keys::SNAPSHOTS],
+            &child_path(keys::SUITE_SPEC, keys::SNAPSHOTS),
+            keys::SNAPSHOTS_KEYS,
+            "snapshots",
+        );
+
+        let config_keys = match suite_spec[keys::TYPE].as_str() {
+            Some("DialogFlow") => keys::DIALOGFLOW_CONFIG_KEYS,
+            Some("DHLVAP") => keys::VAP_CONFIG_KEYS,
+            _ => return,
+        };
+        if let Some(config) = suite_spec[keys::CONFIG].as_vec() {
+            for (idx, config_item) in config.iter().enumerate() {
+                let path = item_path("suite-spec.config", idx);
+                for key in config_item
+                    .as_hash()
+                    .into_iter()
+                    .flat_map(|hash| hash.keys())
+                {
+                    if let Some(key) = key.as_str() {
+                        if !config_keys.contains(&key) {
+                            self.issue(
+                                &child_path(&path, key),
+                                format!(
+                                    "config key '{}' is not used by suite type {}",
+                                    key,
+                                    suite_spec[keys::TYPE].as_str().unwrap_or("")
+                                ),
+                            );
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    fn check_tests(&mut self, yaml: &Yaml) {
+        let tests = match yaml[keys::TESTS].as_vec() {
+            Some(tests) => tests,
+            None => return,
+        };
+
+        let mut test_names: HashMap<String, String> = HashMap::new();
+        for (test_idx, test) in tests.iter().enumerate() {
+            let test_path = item_path(keys::TESTS, test_idx);
+            self.check_keys(test, &test_path, keys::TEST_KEYS, "test");
+
+            if let Some(name) = test[keys::NAME].as_str() {
+                let name_path = child_path(&test_path, keys::NAME);
+                match test_names.get(name) {
+                    Some(first_path) => {
+                        let first_position = self
+                            .positions
+                            .get(first_path)
+                            .map(|position| format!(" (first defined at {})", position))
+                            .unwrap_or_default();
+                        self.issue(
+                            &name_path,
+                            format!("duplicate test name '{}'{}", name, first_position),
+                        );
+                    }
+                    None => {
+                        test_names.insert(name.to_owned(), name_path);
+                    }
+                }
+            }
+
+            let assertions = test[keys::ASSERTIONS].as_vec().cloned().unwrap_or_default();
+            for (assertion_idx, assertion) in assertions.iter().enumerate() {
+                let assertion_path =
+                    item_path(&child_path(&test_path, keys::ASSERTIONS), assertion_idx);
+                self.check_assertion(assertion, &assertion_path);
+            }
+        }
+    }
+
+    fn check_assertion(&mut self, assertion: &Yaml, path: &str) {
+        self.check_keys(assertion, path, keys::ASSERTION_KEYS, "assertion");
+
+        if let Some(contexts) = assertion[keys::EXPECT_CONTEXTS].as_vec() {
+            for (idx, context) in contexts.iter().enumerate() {
+                self.check_keys(
+                    context,
+                    &item_path(&child_path(path, keys::EXPECT_CONTEXTS), idx),
+                    keys::CONTEXT_KEYS,
+                    "expectContexts item",
+                );
+            }
+        }
+
+        let checks_path = child_path(path, keys::RESPONSE_CHECKS);
+        match &assertion[keys::RESPONSE_CHECKS] {
+            Yaml::BadValue => {}
+            Yaml::Array(checks) if !checks.is_empty() => {
+                self.check_response_checks(checks, &checks_path)
+            }
+            _ => self.issue(
+                &checks_path,
+                "responseChecks is empty, remove it or specify at least one check".to_string(),
+            ),
+        }
+    }
+
+    fn check_response_checks(&mut self, checks: &[Yaml], path: &str) {
+        for (idx, check) in checks.iter().enumerate() {
+            self.check_response_check(check, &item_path(path, idx));
+        }
+    }
+
+    fn check_response_check(&mut self, check: &Yaml, path: &str) {
+        self.check_keys(check, path, keys::RESPONSE_CHECK_KEYS, "response check");
+
+        for group_key in &[keys::ANY_OF, keys::ALL_OF, keys::NOT] {
+            match &check[*group_key] {
+                Yaml::Array(checks) => {
+                    self.check_response_checks(checks, &child_path(path, group_key))
+                }
+                Yaml::Hash(_) => {
+                    self.check_response_check(&check[*group_key], &child_path(path, group_key))
+                }
+                _ => {}
+            }
+        }
+
+        let operator = match check[keys::OPERATOR].as_str() {
+            Some(operator) => operator,
+            None => return,
+        };
+        let value = &check[keys::VALUE];
+        let value_type = match value {
+            Yaml::String(_) => "string",
+            Yaml::Integer(_) | Yaml::Real(_) => "number",
+            Yaml::Boolean(_) => "boolean",
+            Yaml::BadValue => return, // missing value is reported by suite parser
+            _ => "structured",
+        };
+        let expected_type = match operator {
+            "length" => "number",
+            "includes" | "jsonequals" | "jsonincludes" | "schema" => "string",
+            _ => return,
+        };
+        if value_type != expected_type {
+            self.issue(
+                &child_path(path, keys::VALUE),
+                format!(
+                    "operator '{}' requires {} value, got {} value",
+                    operator, expected_type, value_type
+                ),
+            );
+        }
+    }
+
+    fn check_classification(&mut self, yaml: &Yaml) {
+        if let Some(rows) = yaml[keys::CLASSIFICATION].as_vec() {
+            for (idx, row) in rows.iter().enumerate() {
+                self.check_keys(
+                    row,
+                    &item_path(keys::CLASSIFICATION, idx),
+                    keys::CLASSIFICATION_KEYS,
+                    "classification item",
+                );
+            }
+        }
+    }
+}
+
+// static checks of suite file, no backend is called. Suite is parsed as it would be when
+// running tests, deeper checks cover problems silently ignored by parser (e.g. unknown keys).
+// Json and toml suites are checked the same way as yaml suite they are mapped to.
+// Relative paths (e.g. csv files) are resolved against base_dir (directory of suite file)
+pub fn validate_suite(content: &str, format: SuiteFormat, base_dir: &Path) -> Vec<ValidationIssue> {
+    let yaml = match format {
+        SuiteFormat::Yaml => {
+            let mut docs = match YamlLoader::load_from_str(content) {
+                Ok(docs) => docs,
+                Err(err) => {
+                    return vec![ValidationIssue {
+                        position: Some(Position::from(err.marker())),
+                        message: format!("invalid yaml: {}", err),
+                    }]
+                }
+            };
+            if docs.is_empty() {
+                return vec![ValidationIssue {
+                    position: None,
+                    message: "suite file is empty".to_string(),
+                }];
+            }
+            docs.remove(0)
+        }
+        _ => match to_yaml(content, format) {
+            Ok(yaml) => yaml,
+            Err(err) => return error_issues(err, &YamlPositions::default()),
+        },
+    };
+    let yaml = &yaml;
+    // json report of previous run would be reported as bunch of unrelated problems
+    if yaml.as_vec().is_some() {
+        return vec![ValidationIssue {
+            position: None,
+            message: "json report contains no suite-spec, only suite files can be validated"
+                .to_string(),
+        }];
+    }
+
+    // json is subset of yaml flow syntax, i.e. positions are available for yaml and json suites
+    let positions = match format {
+        SuiteFormat::Toml => YamlPositions::default(),
+        _ => YamlPositions::from_yaml_str(content).unwrap_or_default(),
+    };
+    let mut linter = SuiteLinter {
+        positions: &positions,
+        issues: vec![],
+    };
+    linter.check_keys(yaml, "", keys::ROOT_KEYS, "suite file");
+    linter.check_suite_spec(yaml);
+    linter.check_tests(yaml);
+    linter.check_classification(yaml);
+    let mut issues = linter.issues;
+
+    // parser reports remaining problems (incl. invalid JMESPath expressions), all of them at once
+    if let Err(err) = TestSuite::from_yaml_in_dir(yaml, base_dir) {
+        issues.extend(error_issues(err, &positions));
+    }
+
+    issues.sort_by_key(|issue| {
+        issue
+            .position
+            .map(|position| (position.line, position.col))
+            .unwrap_or((0, 0))
+    });
+    issues
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_suite() {
+        const YAML: &str = "suite-spec:
+  name: 'Express Tracking'
+  type: 'DialogFlow'
+  config:
+    - credentials_file: '/path/to/cred'
+    - vap_url: 'https://vap.dhl.com'
+tests:
+  - name: 'Tracking'
+    assertions:
+      - userSays: 'track 1234567891'
+        botRespondsWith: 'Tracking|CS|3|ID valid|Gen'
+        responseCheck:
+          - expression: 'queryResult.action'
+      - userSays: 'thanks'
+        botRespondsWith: 'Generic|BIT|0|Thanks|Gen'
+        responseChecks: []
+  - name: 'Tracking'
+    assertions:
+      - userSays: 'track 1234567891'
+        botRespondsWith: 'Tracking|CS|3|ID valid|Gen'
+        responseChecks:
+          - expression: 'queryResult.fulfillmentMessages'
+            operator: 'length'
+            value: 'two'
+          - anyOf:
+              - expression: 'queryResult..action'
+                operator: 'equals'
+                value: 'express_track'
+";
+        let issues: Vec<String> = validate_suite(YAML, SuiteFormat::Yaml, Path::new(""))
+            .iter()
+            .map(|issue| issue.to_string())
+            .collect();
+        assert_eq!(issues.len(), 6);
+        assert_eq!(
+            issues[0],
+            "6:7: config key 'vap_url' is not used by suite type DialogFlow"
+        );
+        assert!(issues[1].starts_with("12:9: unknown key 'responseCheck' in assertion."));
+        assert_eq!(
+            issues[2],
+            "16:9: responseChecks is empty, remove it or specify at least one check"
+        );
+        assert_eq!(
+            issues[3],
+            "17:5: duplicate test name 'Tracking' (first defined at 8:5)"
+        );
+        assert_eq!(
+            issues[4],
+            "24:13: operator 'length' requires number value, got string value"
+        );
+        assert!(issues[5].starts_with(
+            "26:17: Invalid JMESPath expression 'queryResult..action'. test: 'Tracking'"
+        ));
+
+        let issues = validate_suite("suite-spec:\n  name: [\n", SuiteFormat::Yaml, Path::new(""));
+        assert_eq!(issues.len(), 1);
+        assert!(issues[0].message.starts_with("invalid yaml: "));
+    }
+
+    #[test]
+    fn test_validate_json_and_toml_suite() {
+        const JSON: &str = r#"{
+  "suite-spec": {
+    "name": "Express Tracking",
+    "type": "DialogFlow",
+    "config": [{"credentials_file": "/path/to/cred"}]
+  },
+  "tests": [
+    {
+      "name": "Tracking",
+      "assertions": [
+        {"userSays": "track", "botRespondWith": "Tracking"}
+      ]
+    }
+  ]
+}"#;
+        let issues: Vec<String> = validate_suite(JSON, SuiteFormat::Json, Path::new(""))
+            .iter()
+            .map(|issue| issue.to_string())
+            .collect();
+        // json suite is yaml as well, i.e. issues have positions
+        assert_eq!(issues.len(), 2, "{:?}", issues);
+        assert_eq!(
+            issues[0],
+            "11:10: Test assertions missing botRespondsWith for Tracking"
+        );
+        assert!(issues[1].starts_with("11:31: unknown key 'botRespondWith' in assertion."));
+
+        const TOML: &str = r#"
+[suite-spec]
+name = "Express Tracking"
+type = "DialogFlow"
+config = [{ credentials_file = "/path/to/cred" }]
+
+[[tests]]
+name = "Tracking"
+
+[[tests.assertions]]
+userSays = "track"
+botRespondsWith = "Tracking"
+minConfidence = "high"
+"#;
+        let issues: Vec<String> = validate_suite(TOML, SuiteFormat::Toml, Path::new(""))
+            .iter()
+            .map(|issue| issue.to_string())
+            .collect();
+        assert_eq!(issues.len(), 1, "{:?}", issues);
+        assert!(issues[0].starts_with("tests[0].assertions[0].minConfidence: "));
+
+        let issues = validate_suite("name = 1\n[suite-spec\n", SuiteFormat::Toml, Path::new(""));
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].position.unwrap().line, 2);
+
+        let issues = validate_suite("[]", SuiteFormat::Json, Path::new(""));
+        assert_eq!(
+            issues[0].message,
+            "json report contains no suite-spec, only suite files can be validated"
+        );
+    }
+}
@@ -3,15 +3,16 @@ use log::debug;
 use reqwest;
 use std::sync::mpsc;
 
-use crate::errors::{new_service_call_error, ErrorKind, Result};
+use crate::errors::Result;
 use crate::gdf::{
     call_dialogflow, file_to_gdf_credentials, get_google_api_token, prepare_dialogflow_request,
     GDFCredentials, GoogleApisOauthToken,
 };
-use crate::json_parser::JsonParser;
-use crate::yaml_parser::{Test, TestAssertion, TestAssertionResult, TestResult};
+use crate::yaml_parser::{
+    CheckGroupResult, Test, TestAssertion, TestAssertionResult, TestAssertionWarning, TestResult,
+};
 
-use crate::test_executors::TestExecutor;
+use crate::test_executors::{IntentExpressions, NlpResponse, TestExecutor};
 
 pub type HttpClient = reqwest::blocking::Client;
 
@@ -23,6 +24,7 @@ pub struct GDFDefaultTestExecutor {
     conv_id: String,
     cred: GDFCredentials,
     tx: mpsc::Sender<Test>,
+    intent_expressions: IntentExpressions,
 }
 
 impl GDFDefaultTestExecutor {
@@ -32,26 +34,31 @@ impl GDFDefaultTestExecutor {
         tx: mpsc::Sender<Test>,
         http_proxy: Option<String>,
     ) -> Result<Self> {
-        let http_client;
+        let http_client = GDFDefaultTestExecutor::build_http_client(http_proxy)?;
+        let token = get_google_api_token(&credentials_file, &http_client)?;
+        let cred = file_to_gdf_credentials(&credentials_file)?;
 
-        match http_proxy {
-            Some(proxy) => {
-                debug!("building http client with proxy {}", proxy);
-                http_client = HttpClient::builder()
-                    .proxy(reqwest::Proxy::http(&proxy)?)
-                    .build()?;
-            }
-            _ => {
-                debug!("building http client with no proxy");
-                http_client = HttpClient::new()
-            }
-        }
+        Ok(GDFDefaultTestExecutor::with_token(
+            cred,
+            token,
+            http_client,
+            test,
+            tx,
+        ))
+    }
 
-        let token = get_google_api_token(&credentials_file, &http_client)?;
+    // credentials, OAuth token and http client can be shared by executors of all tests of the suite
+    // (including repetitions of the same test), every executor starts new conversation
+    pub fn with_token(
+        cred: GDFCredentials,
+        token: GoogleApisOauthToken,
+        http_client: HttpClient,
+        test: Test,
+        tx: mpsc::Sender<Test>,
+    ) -> Self {
         let conv_id = GUID::rand().to_string();
-        let cred = file_to_gdf_credentials(&credentials_file)?;
 
-        Ok(GDFDefaultTestExecutor {
+        GDFDefaultTestExecutor {
             test,
             next_assertion: 0,
             http_client,
@@ -59,7 +66,23 @@ impl GDFDefaultTestExecutor {
             conv_id,
             cred,
             tx,
-        })
+            intent_expressions: IntentExpressions::new("queryResult"),
+        }
+    }
+
+    pub fn build_http_client(http_proxy: Option<String>) -> Result<HttpClient> {
+        match http_proxy {
+            Some(proxy) => {
+                debug!("building http client with proxy {}", proxy);
+                Ok(HttpClient::builder()
+                    .proxy(reqwest::Proxy::http(&proxy)?)
+                    .build()?)
+            }
+            _ => {
+                debug!("building http client with no proxy");
+                Ok(HttpClient::new())
+            }
+        }
     }
 
     fn make_pretty_json(response: String) -> Result<String> {
@@ -71,7 +94,7 @@ impl GDFDefaultTestExecutor {
 
 impl TestExecutor for GDFDefaultTestExecutor {
     fn move_to_next_assertion(&mut self) {
-        self.next_assertion = self.next_assertion + 1;
+        self.next_assertion += 1;
     }
 
     fn move_behind_last_assertion(&mut self) {
@@ -91,6 +114,21 @@ impl TestExecutor for GDFDefaultTestExecutor {
         self.test.assertions[idx].test_assertion_result = Some(test_assertion_result);
     }
 
+    fn set_test_assertion_warnings(&mut self, warnings: Vec<TestAssertionWarning>) {
+        let idx = self.get_next_assertion_no();
+        self.test.assertions[idx].warnings = warnings;
+    }
+
+    fn set_test_assertion_check_groups(&mut self, check_groups: Vec<CheckGroupResult>) {
+        let idx = self.get_next_assertion_no();
+        self.test.assertions[idx].check_groups = check_groups;
+    }
+
+    fn set_test_assertion_confidence(&mut self, detected_confidence: Option<f64>) {
+        let idx = self.get_next_assertion_no();
+        self.test.assertions[idx].detected_confidence = detected_confidence;
+    }
+
     fn get_next_assertion_no(&self) -> usize {
         self.next_assertion
     }
@@ -100,7 +138,7 @@ impl TestExecutor for GDFDefaultTestExecutor {
         Ok(())
     }
 
-    fn invoke_nlp(&self, assertion: &TestAssertion) -> Result<String> {
+    fn call_nlp(&self, assertion: &TestAssertion) -> Result<String> {
         let payload = prepare_dialogflow_request(&assertion.user_says, &self.test.lang);
         let resp = call_dialogflow(
             payload,
@@ -110,41 +148,13 @@ impl TestExecutor for GDFDefaultTestExecutor {
             &self.token.access_token,
         )?;
         let resp = GDFDefaultTestExecutor::make_pretty_json(resp)?; // GDF sends pretty jsons but just for any case let's prettify it anyway
-        let parser = JsonParser::new(&resp);
-        let real_intent_name = parser.search("queryResult.intent.displayName")?;
-        let real_intent_name = JsonParser::extract_as_string(&real_intent_name);
-
-        if let Some(intent_name) = real_intent_name {
-            if !assertion
-                .bot_responds_with
-                .contains(&intent_name.to_string())
-            {
-                let error_message = format!(
-                    "Wrong intent name received. Expected one of: '{}', got: '{}'",
-                    assertion.bot_responds_with.join(","),
-                    intent_name
-                );
-                return Err(new_service_call_error(
-                    ErrorKind::InvalidTestAssertionEvaluation,
-                    error_message,
-                    None,
-                    Some(resp.to_owned()),
-                ));
-            }
-        } else {
-            let error_message = format!(
-                "No intent name received. Expected: '{}'",
-                assertion.bot_responds_with.join(",")
-            );
-            return Err(new_service_call_error(
-                ErrorKind::InvalidTestAssertionEvaluation,
-                error_message,
-                None,
-                Some(resp.to_owned()),
-            ));
-        }
         Ok(resp)
     }
+
+    fn invoke_nlp(&self, assertion: &TestAssertion) -> Result<NlpResponse> {
+        let resp = self.call_nlp(assertion)?;
+        Ok(NlpResponse::new(assertion, resp, &self.intent_expressions))
+    }
 }
 
 #[cfg(test)]
@@ -202,7 +212,7 @@ mod tests {
             println!();
             let details_result = test1_executor.next_assertion_details();
 
-            if let None = details_result {
+            if details_result.is_none() {
                 println!("all assertions processed!");
                 test1_executor.set_test_result(TestResult::Ok);
                 break; // all asertions were processed -> break
@@ -213,7 +223,7 @@ mod tests {
             print!("Saying {}", user_says);
             let assertion_exec_result = test1_executor.execute_next_assertion();
 
-            if let Some(_) = assertion_exec_result {
+            if assertion_exec_result.is_some() {
                 print!(" - ok!");
             } else {
                 print!(" - ko!");
@@ -284,7 +294,7 @@ mod tests {
             pool.execute(move || {
                 loop {
                     let assertion_exec_result = test_executor.execute_next_assertion();
-                    if let None = assertion_exec_result {
+                    if assertion_exec_result.is_none() {
                         break;
                     }
                 }
@@ -1,13 +1,133 @@
-use crate::errors::Result;
+use crate::errors::{new_error_from, new_service_call_error, Error, ErrorKind, Result};
 
+use crate::intent_matcher::{check_confidence, check_intent_name};
+use crate::json_parser::{JmespathExpression, JsonParser};
 use crate::suite_executor::TestSuiteExecutor;
-use crate::yaml_parser::{TestAssertion, TestAssertionResult, TestResult};
+use crate::yaml_parser::{
+    CheckGroupResult, Severity, TestAssertion, TestAssertionResult, TestAssertionWarning,
+    TestResult,
+};
 
 mod gdf_executor;
 mod vap_executor;
 pub use gdf_executor::GDFDefaultTestExecutor;
 pub use vap_executor::VAPTestExecutor;
 
+// intent name mismatch as opposed to failed call of NLP provider
+fn is_intent_mismatch(error: &Error) -> bool {
+    match *error.kind {
+        ErrorKind::InvalidTestAssertionEvaluation => error.backend_response.is_some(),
+        _ => false,
+    }
+}
+
+// expected intent detected, but minConfidence or isFallback check failed
+fn is_confidence_mismatch(error: &Error) -> bool {
+    match *error.kind {
+        ErrorKind::InvalidTestAssertionConfidence => error.backend_response.is_some(),
+        _ => false,
+    }
+}
+
+// JMESPath expressions of detected intent within NLP provider response (DialogFlow query result
+// is nested in VAP response), compiled once when test executor is created
+#[derive(Clone)]
+pub struct IntentExpressions {
+    display_name: JmespathExpression,
+    confidence: JmespathExpression,
+    is_fallback: JmespathExpression,
+}
+
+impl IntentExpressions {
+    pub fn new(query_result: &str) -> IntentExpressions {
+        let compile = |path: &str| {
+            JmespathExpression::compile(&format!("{}.{}", query_result, path))
+                .expect("query result expression must be valid JMESPath field expression")
+        };
+        IntentExpressions {
+            display_name: compile("intent.displayName"),
+            confidence: compile("intentDetectionConfidence"),
+            is_fallback: compile("intent.isFallback"),
+        }
+    }
+
+    // intentDetectionConfidence observed in response, None if response does not contain it at all
+    pub fn detected_confidence(&self, parser: &JsonParser) -> Option<f64> {
+        let confidence = parser.search_expression(&self.confidence).ok()?;
+        JsonParser::extract_as_number(&confidence)
+    }
+}
+
+// response of NLP provider parsed once, i.e. the same parser is used for intent checks
+// and all response checks of the assertion
+pub struct NlpResponse {
+    pub parser: JsonParser<'static>,
+    pub detected_confidence: Option<f64>,
+    pub intent_check: Result<()>, // intent name mismatch or failed minConfidence/isFallback check
+}
+
+impl NlpResponse {
+    pub fn new(
+        assertion: &TestAssertion,
+        response: String,
+        expressions: &IntentExpressions,
+    ) -> NlpResponse {
+        let parser = JsonParser::from_string(response);
+        let detected_confidence = expressions.detected_confidence(&parser);
+        let intent_check =
+            check_detected_intent(assertion, &parser, expressions, detected_confidence);
+        NlpResponse {
+            parser,
+            detected_confidence,
+            intent_check,
+        }
+    }
+}
+
+// checks detected intent (botRespondsWith/botDoesNotRespondWith) and its minConfidence/isFallback.
+// Confidence is checked even if no intent was detected and botRespondsWith is not specified
+fn check_detected_intent(
+    assertion: &TestAssertion,
+    parser: &JsonParser,
+    expressions: &IntentExpressions,
+    confidence: Option<f64>,
+) -> Result<()> {
+    let mismatch = |kind: ErrorKind, message: String| {
+        new_service_call_error(kind, message, None, Some(parser.json().to_owned()))
+    };
+
+    let intent_name = parser.search_expression(&expressions.display_name)?;
+    let intent_name = JsonParser::extract_as_string(&intent_name);
+    match intent_name {
+        Some(intent_name) => check_intent_name(
+            &assertion.bot_responds_with,
+            &assertion.bot_does_not_respond_with,
+            intent_name,
+        )
+        .map_err(|message| mismatch(ErrorKind::InvalidTestAssertionEvaluation, message))?,
+        None if !assertion.bot_responds_with.is_empty() => {
+            return Err(mismatch(
+                ErrorKind::InvalidTestAssertionEvaluation,
+                format!(
+                    "No intent name received. Expected: '{}'",
+                    assertion.bot_responds_with.join(",")
+                ),
+            ))
+        }
+        None => {}
+    }
+
+    let is_fallback = parser.search_expression(&expressions.is_fallback)?;
+    check_confidence(
+        assertion.min_confidence,
+        assertion.is_fallback,
+        confidence,
+        JsonParser::extract_as_bool(&is_fallback).unwrap_or(false),
+        intent_name,
+    )
+    .map_err(|message| mismatch(ErrorKind::InvalidTestAssertionConfidence, message))
+}
+
 pub trait TestExecutor {
     // helper abstaract methods so that we can use default implementations for next_assertion_details/execute_next_assertion
     fn move_to_next_assertion(&mut self);
@@ -18,9 +138,24 @@ pub trait TestExecutor {
     fn get_next_assertion_no(&self) -> usize;
     fn send_test_results(&self) -> Result<()>;
     //
-    // core abstract method to be provided for every test executor //
+    // core abstract methods to be provided for every test executor //
     //
-    fn invoke_nlp(&self, assertion: &TestAssertion) -> Result<String>;
+    // calls NLP provider and checks that detected intent is one of expected intents (see NlpResponse),
+    // error is returned only if NLP provider call failed
+    fn invoke_nlp(&self, assertion: &TestAssertion) -> Result<NlpResponse>;
+
+    // optional methods, executors which do not keep warnings, check group rows or detected
+    // confidence of assertions (e.g. custom executors) can ignore them
+    fn set_test_assertion_warnings(&mut self, _warnings: Vec<TestAssertionWarning>) {}
+    fn set_test_assertion_check_groups(&mut self, _check_groups: Vec<CheckGroupResult>) {}
+    fn set_test_assertion_confidence(&mut self, _detected_confidence: Option<f64>) {}
+
+    // sends user utterance to NLP provider and returns raw response, used by recorder only
+    fn call_nlp(&self, _assertion: &TestAssertion) -> Result<String> {
+        Err(new_error_from(ErrorKind::GenericError(
+            "Test executor does not support recording of conversations".to_owned(),
+        )))
+    }
 
     // these default implementation hardcode default flow for convenience
     // every test executor can than focus on invoke_nlp only
@@ -44,33 +179,98 @@ pub trait TestExecutor {
         if next_assertion_no >= assertions.len() {
             self.set_test_result(TestResult::Ok);
             let _ = self.send_test_results();
-            return None;
+            None
         } else {
             let assertion_to_execute = &assertions[next_assertion_no];
+            let warning_assertion = assertion_to_execute.severity == Severity::Warning;
+            // failed checks with warning severity are recorded and execution continues
+            let mut warnings = vec![];
 
-            let assertion_response = self.invoke_nlp(assertion_to_execute);
-
-            if let Err(intent_mismatch_error) = assertion_response {
-                // if intent name does not match expected value do not continue
-                self.set_test_assertion_result(TestAssertionResult::KoIntentNameMismatch(
-                    intent_mismatch_error,
-                ));
-                self.set_test_result(TestResult::Ko);
-                self.move_behind_last_assertion();
-                let _ = self.send_test_results();
-                return None;
-            }
+            let nlp_response = match self.invoke_nlp(assertion_to_execute) {
+                Ok(nlp_response) => nlp_response,
+                Err(backend_error) => {
+                    // NLP provider call failed, do not continue
+                    self.set_test_assertion_result(TestAssertionResult::KoBackendError(
+                        backend_error,
+                    ));
+                    self.set_test_result(TestResult::Ko);
+                    self.move_behind_last_assertion();
+                    let _ = self.send_test_results();
+                    return None;
+                }
+            };
+            let detected_confidence = nlp_response.detected_confidence;
 
-            // otherwise try to run assertion response checks
-            let assertion_response = assertion_response.unwrap();
+            match nlp_response.intent_check {
+                Ok(()) => {}
+                // intent mismatch of warning assertion, backend errors still fail the test
+                Err(intent_mismatch_error)
+                    if warning_assertion && is_intent_mismatch(&intent_mismatch_error) =>
+                {
+                    warnings.push(TestAssertionWarning::IntentNameMismatch(
+                        intent_mismatch_error,
+                    ));
+                }
+                // low confidence (or fallback flag mismatch) can be flagged as warning only
+                Err(confidence_error)
+                    if assertion_to_execute.effective_confidence_severity()
+                        == Severity::Warning
+                        && is_confidence_mismatch(&confidence_error) =>
+                {
+                    warnings.push(TestAssertionWarning::ConfidenceMismatch(confidence_error));
+                }
+                Err(intent_mismatch_error) => {
+                    // if intent name does not match expected value (or response is not valid json) do not continue
+                    self.set_test_assertion_confidence(detected_confidence);
+                    if is_intent_mismatch(&intent_mismatch_error) {
+                        self.set_test_assertion_result(TestAssertionResult::KoIntentNameMismatch(
+                            intent_mismatch_error,
+                        ));
+                    } else if is_confidence_mismatch(&intent_mismatch_error) {
+                        self.set_test_assertion_result(TestAssertionResult::KoConfidenceMismatch(
+                            intent_mismatch_error,
+                        ));
+                    } else {
+                        self.set_test_assertion_result(TestAssertionResult::KoBackendError(
+                            intent_mismatch_error,
+                        ));
+                    }
+                    self.set_test_result(TestResult::Ko);
+                    self.move_behind_last_assertion();
+                    let _ = self.send_test_results();
+                    return None;
+                }
+            };
 
+            // otherwise try to run assertion response checks using the same parsed response
+            let parser = nlp_response.parser;
+            // status of every check within check groups is kept for reporters
+            let mut check_groups = vec![];
             for (idx, response_check) in assertion_to_execute.response_checks.iter().enumerate() {
-                let response_check_result = TestSuiteExecutor::process_assertion_response_check(
-                    response_check,
-                    &assertion_response,
-                );
+                let (response_check_result, rows) =
+                    TestSuiteExecutor::process_assertion_response_check_with_rows(
+                        response_check,
+                        &parser,
+                    );
+                if let Some(rows) = rows {
+                    check_groups.push(CheckGroupResult {
+                        check_idx: idx,
+                        rows,
+                    });
+                }
 
                 if let Err(some_response_check_error) = response_check_result {
+                    if warning_assertion || response_check.severity == Severity::Warning {
+                        warnings.push(TestAssertionWarning::ResponseCheckError(
+                            some_response_check_error,
+                            idx,
+                        ));
+                        continue;
+                    }
+
+                    self.set_test_assertion_confidence(detected_confidence);
+                    self.set_test_assertion_warnings(warnings);
+                    self.set_test_assertion_check_groups(check_groups);
                     self.set_test_assertion_result(TestAssertionResult::KoResponseCheckError(
                         some_response_check_error,
                         idx,
@@ -82,9 +282,386 @@ pub trait TestExecutor {
                 }
             }
 
-            self.set_test_assertion_result(TestAssertionResult::Ok(assertion_response));
+            self.set_test_assertion_confidence(detected_confidence);
+            self.set_test_assertion_warnings(warnings);
+            self.set_test_assertion_check_groups(check_groups);
+            self.set_test_assertion_result(TestAssertionResult::Ok(parser.into_json()));
             self.move_to_next_assertion();
-            return Some(());
+            Some(())
         }
     }
+
+    // used when recording conversations (see recorder module), intent names and response checks
+    // are not evaluated, raw response of every turn is stored as assertion result
+    fn record_next_assertion(&mut self) -> Option<()> {
+        let next_assertion_no = self.get_next_assertion_no();
+        let assertions = self.get_assertions();
+
+        if next_assertion_no >= assertions.len() {
+            self.set_test_result(TestResult::Ok);
+            let _ = self.send_test_results();
+            return None;
+        }
+
+        let assertion_response = self.call_nlp(&assertions[next_assertion_no]);
+        match assertion_response {
+            Ok(response) => {
+                self.set_test_assertion_result(TestAssertionResult::Ok(response));
+                self.move_to_next_assertion();
+                Some(())
+            }
+            Err(some_error) => {
+                self.set_test_assertion_result(TestAssertionResult::KoBackendError(some_error));
+                self.set_test_result(TestResult::Ko);
+                self.move_behind_last_assertion();
+                let _ = self.send_test_results();
+                None
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::intent_matcher::IntentRule;
+    use crate::yaml_parser::{Test, TestSuite};
+    use yaml_rust::YamlLoader;
+
+    // executor returning canned response for every utterance, intent name is checked as usual
+    struct CannedTestExecutor {
+        test: Test,
+        next_assertion: usize,
+        response: String,
+        backend_error: bool, // every NLP provider call fails
+    }
+
+    impl TestExecutor for CannedTestExecutor {
+        fn move_to_next_assertion(&mut self) {
+            self.next_assertion += 1;
+        }
+
+        fn move_behind_last_assertion(&mut self) {
+            self.next_assertion = self.test.assertions.len();
+        }
+
+        fn get_assertions(&self) -> &Vec<TestAssertion> {
+            &self.test.assertions
+        }
+
+        fn set_test_result(&mut self, test_result: TestResult) {
+            self.test.test_result = Some(test_result);
+        }
+
+        fn set_test_assertion_result(&mut self, test_assertion_result: TestAssertionResult) {
+            let idx = self.get_next_assertion_no();
+            self.test.assertions[idx].test_assertion_result = Some(test_assertion_result);
+        }
+
+        fn set_test_assertion_warnings(&mut self, warnings: Vec<TestAssertionWarning>) {
+            let idx = self.get_next_assertion_no();
+            self.test.assertions[idx].warnings = warnings;
+        }
+
+        fn set_test_assertion_check_groups(&mut self, check_groups: Vec<CheckGroupResult>) {
+            let idx = self.get_next_assertion_no();
+            self.test.assertions[idx].check_groups = check_groups;
+        }
+
+        fn set_test_assertion_confidence(&mut self, detected_confidence: Option<f64>) {
+            let idx = self.get_next_assertion_no();
+            self.test.assertions[idx].detected_confidence = detected_confidence;
+        }
+
+        fn get_next_assertion_no(&self) -> usize {
+            self.next_assertion
+        }
+
+        fn send_test_results(&self) -> Result<()> {
+            Ok(())
+        }
+
+        fn call_nlp(&self, _assertion: &TestAssertion) -> Result<String> {
+            if self.backend_error {
+                return Err(new_service_call_error(
+                    ErrorKind::GDFInvocationError,
+                    "service unavailable".to_owned(),
+                    Some("503".to_owned()),
+                    None,
+                ));
+            }
+            Ok(self.response.clone())
+        }
+
+        fn invoke_nlp(&self, assertion: &TestAssertion) -> Result<NlpResponse> {
+            let resp = self.call_nlp(assertion)?;
+            Ok(NlpResponse::new(
+                assertion,
+                resp,
+                &IntentExpressions::new("queryResult"),
+            ))
+        }
+    }
+
+    #[test]
+    fn test_warnings_do_not_fail_test() -> Result<()> {
+        const YAML: &str = "
+        suite-spec:
+            name: 'Express Tracking'
+            type: 'DialogFlow'
+            config: 
+              - credentials_file: '/path/to/cred'
+        tests:
+            - name: 'Tracking'
+              assertions:
+                - userSays: 'track 1234567891'
+                  botRespondsWith: 'Tracking|CS|3|ID valid|Gen'
+                  responseChecks:
+                    - expression: 'queryResult.action'
+                      operator: 'equals'
+                      value: 'express_track'
+                      severity: 'warning'
+                    - expression: 'queryResult.action'
+                      operator: 'equals'
+                      value: 'track'
+                - userSays: 'thanks'
+                  botRespondsWith: 'Generic|BIT|0|Thanks|Gen'
+                  severity: 'warning'
+        ";
+
+        let docs = YamlLoader::load_from_str(YAML)?;
+        let suite = TestSuite::from_yaml(&docs[0])?;
+        let mut executor = CannedTestExecutor {
+            test: suite.tests[0].clone(),
+            next_assertion: 0,
+            response: r#"{"queryResult": {"intent": {"displayName": "Tracking|CS|3|ID valid|Gen"}, "action": "track"}}"#.to_owned(),
+            backend_error: false,
+        };
+        while executor.execute_next_assertion().is_some() {}
+
+        let test = &executor.test;
+        assert!(matches_ok(&test.test_result));
+        assert_eq!(test.assertions[0].warnings.len(), 1);
+        assert!(test.assertions[0].response_check_warning(0).is_some());
+        assert!(test.assertions[1].has_intent_warning());
+        assert!(test.has_warnings());
+
+        // the same failures with error severity fail the test
+        let docs =
+            YamlLoader::load_from_str(&YAML.replace("severity: 'warning'", "severity: 'error'"))?;
+        let suite = TestSuite::from_yaml(&docs[0])?;
+        executor.test = suite.tests[0].clone();
+        executor.next_assertion = 0;
+        while executor.execute_next_assertion().is_some() {}
+        assert!(!matches_ok(&executor.test.test_result));
+        assert!(!executor.test.has_warnings());
+        Ok(())
+    }
+
+    #[test]
+    fn test_check_group_rows_recorded() -> Result<()> {
+        const YAML: &str = "
+        suite-spec:
+            name: 'Express Tracking'
+            type: 'DialogFlow'
+            config: 
+              - credentials_file: '/path/to/cred'
+        tests:
+            - name: 'Tracking'
+              assertions:
+                - userSays: 'track 1234567891'
+                  botRespondsWith: 'Tracking|CS|3|ID valid|Gen'
+                  responseChecks:
+                    - expression: 'queryResult.action'
+                      operator: 'equals'
+                      value: 'track'
+                    - anyOf:
+                        - expression: 'queryResult.action'
+                          operator: 'equals'
+                          value: 'express_track'
+                        - expression: 'queryResult.action'
+                          operator: 'equals'
+                          value: 'track'
+        ";
+
+        let docs = YamlLoader::load_from_str(YAML)?;
+        let suite = TestSuite::from_yaml(&docs[0])?;
+        let mut executor = CannedTestExecutor {
+            test: suite.tests[0].clone(),
+            next_assertion: 0,
+            response: r#"{"queryResult": {"intent": {"displayName": "Tracking|CS|3|ID valid|Gen"}, "action": "track"}}"#.to_owned(),
+            backend_error: false,
+        };
+        while executor.execute_next_assertion().is_some() {}
+
+        let assertion = &executor.test.assertions[0];
+        assert!(matches_ok(&executor.test.test_result));
+        // only check groups are recorded, group row is followed by its nested checks
+        assert!(assertion.check_group_rows(0).is_none());
+        let rows = assertion.check_group_rows(1).unwrap();
+        let statuses: Vec<(usize, bool)> = rows.iter().map(|row| (row.depth, row.ok)).collect();
+        assert_eq!(statuses, vec![(0, true), (1, false), (1, true)]);
+
+        // rows are cleared when test is executed again
+        executor.test.reset_results();
+        assert!(executor.test.assertions[0].check_group_rows(1).is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn test_confidence_mismatch() -> Result<()> {
+        const YAML: &str = "
+        suite-spec:
+            name: 'Express Tracking'
+            type: 'DialogFlow'
+            config: 
+              - credentials_file: '/path/to/cred'
+            minConfidence: 0.8
+        tests:
+            - name: 'Tracking'
+              assertions:
+                - userSays: 'track 1234567891'
+                  botRespondsWith: 'Tracking|CS|3|ID valid|Gen'
+        ";
+
+        let docs = YamlLoader::load_from_str(YAML)?;
+        let suite = TestSuite::from_yaml(&docs[0])?;
+        let mut executor = CannedTestExecutor {
+            test: suite.tests[0].clone(),
+            next_assertion: 0,
+            response: r#"{"queryResult": {"intent": {"displayName": "Tracking|CS|3|ID valid|Gen"}, "intentDetectionConfidence": 0.5}}"#.to_owned(),
+            backend_error: false,
+        };
+        while executor.execute_next_assertion().is_some() {}
+        assert!(!matches_ok(&executor.test.test_result));
+        match &executor.test.assertions[0].test_assertion_result {
+            Some(TestAssertionResult::KoConfidenceMismatch(err)) => assert_eq!(
+                err.message,
+                "Intent detection confidence too low. Expected at least: 0.8, got: 0.5 (intent: 'Tracking|CS|3|ID valid|Gen')"
+            ),
+            other => panic!("Expected confidence mismatch, got {:?}", other),
+        }
+        assert_eq!(executor.test.assertions[0].detected_confidence, Some(0.5));
+
+        // missing confidence is unknown, i.e. it does not satisfy the threshold
+        executor.test = suite.tests[0].clone();
+        executor.next_assertion = 0;
+        executor.response =
+            r#"{"queryResult": {"intent": {"displayName": "Tracking|CS|3|ID valid|Gen"}}}"#
+                .to_owned();
+        while executor.execute_next_assertion().is_some() {}
+        match &executor.test.assertions[0].test_assertion_result {
+            Some(TestAssertionResult::KoConfidenceMismatch(err)) => {
+                assert!(err
+                    .message
+                    .starts_with("Intent detection confidence missing"))
+            }
+            other => panic!("Expected confidence mismatch, got {:?}", other),
+        }
+
+        // low confidence flagged as warning only
+        let docs = YamlLoader::load_from_str(&YAML.replace(
+            "minConfidence: 0.8",
+            "minConfidence: 0.8\n            confidenceSeverity: 'warning'",
+        ))?;
+        let suite = TestSuite::from_yaml(&docs[0])?;
+        assert_eq!(
+            suite.tests[0].assertions[0].confidence_severity,
+            Some(Severity::Warning)
+        );
+        executor.test = suite.tests[0].clone();
+        executor.next_assertion = 0;
+        executor.response = r#"{"queryResult": {"intent": {"displayName": "Tracking|CS|3|ID valid|Gen"}, "intentDetectionConfidence": 0.5}}"#.to_owned();
+        while executor.execute_next_assertion().is_some() {}
+        let assertion = &executor.test.assertions[0];
+        assert!(matches_ok(&executor.test.test_result));
+        assert!(assertion.has_intent_warning());
+        match &assertion.warnings[0] {
+            TestAssertionWarning::ConfidenceMismatch(_) => {}
+            other => panic!("Expected confidence warning, got {:?}", other),
+        }
+
+        // confidence is checked even if no intent was detected and only forbidden intents are specified
+        let docs = YamlLoader::load_from_str(&YAML.replace(
+            "botRespondsWith: 'Tracking|CS|3|ID valid|Gen'",
+            "botDoesNotRespondWith: 'Default Fallback Intent'",
+        ))?;
+        let suite = TestSuite::from_yaml(&docs[0])?;
+        executor.test = suite.tests[0].clone();
+        executor.next_assertion = 0;
+        executor.response = r#"{"queryResult": {"intentDetectionConfidence": 0.3}}"#.to_owned();
+        while executor.execute_next_assertion().is_some() {}
+        assert!(!matches_ok(&executor.test.test_result));
+        match &executor.test.assertions[0].test_assertion_result {
+            Some(TestAssertionResult::KoConfidenceMismatch(err)) => assert_eq!(
+                err.message,
+                "Intent detection confidence too low. Expected at least: 0.8, got: 0.3 (no intent detected)"
+            ),
+            other => panic!("Expected confidence mismatch, got {:?}", other),
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_detected_confidence() {
+        let response = r#"{"queryResult": {"intent": {"displayName": "Welcome"}, "intentDetectionConfidence": 0.93}}"#;
+        let parser = JsonParser::new(response);
+        assert_eq!(
+            IntentExpressions::new("queryResult").detected_confidence(&parser),
+            Some(0.93)
+        );
+        assert_eq!(
+            IntentExpressions::new("dfResponse.queryResult").detected_confidence(&parser),
+            None
+        );
+        // missing confidence is unknown rather than zero
+        let parser = JsonParser::new(r#"{"queryResult": {"queryText": "foo"}}"#);
+        assert_eq!(
+            IntentExpressions::new("queryResult").detected_confidence(&parser),
+            None
+        );
+        // response is not valid json
+        let parser = JsonParser::new("<html>");
+        assert_eq!(
+            IntentExpressions::new("queryResult").detected_confidence(&parser),
+            None
+        );
+    }
+
+    #[test]
+    fn test_backend_errors_are_not_intent_mismatches() -> Result<()> {
+        let mut test = Test::new("Tracking".to_owned(), None, "en".to_owned());
+        test.assertions.push(TestAssertion::new(
+            "track 1234567891".to_owned(),
+            vec![IntentRule::intent_name("Tracking|CS|3|ID valid|Gen")],
+            vec![],
+        ));
+        let mut executor = CannedTestExecutor {
+            test: test.clone(),
+            next_assertion: 0,
+            response: String::new(),
+            backend_error: true,
+        };
+        while executor.execute_next_assertion().is_some() {}
+        assert!(!matches_ok(&executor.test.test_result));
+        match &executor.test.assertions[0].test_assertion_result {
+            Some(TestAssertionResult::KoBackendError(err)) => {
+                assert_eq!(err.message, "service unavailable")
+            }
+            other => panic!("Expected backend error, got {:?}", other),
+        }
+
+        executor.test = test;
+        executor.next_assertion = 0;
+        while executor.record_next_assertion().is_some() {}
+        match &executor.test.assertions[0].test_assertion_result {
+            Some(TestAssertionResult::KoBackendError(_)) => {}
+            other => panic!("Expected backend error, got {:?}", other),
+        }
+        Ok(())
+    }
+
+    fn matches_ok(test_result: &Option<TestResult>) -> bool {
+        matches!(test_result, Some(TestResult::Ok))
+    }
 }
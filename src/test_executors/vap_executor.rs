@@ -4,10 +4,11 @@ use serde::{Deserialize, Serialize};
 use serde_json::json;
 use std::sync::mpsc;
 
-use crate::errors::{new_service_call_error, ErrorKind, Result};
-use crate::json_parser::JsonParser;
-use crate::test_executors::TestExecutor;
-use crate::yaml_parser::{Test, TestAssertion, TestAssertionResult, TestResult};
+use crate::errors::Result;
+use crate::test_executors::{IntentExpressions, NlpResponse, TestExecutor};
+use crate::yaml_parser::{
+    CheckGroupResult, Test, TestAssertion, TestAssertionResult, TestAssertionWarning, TestResult,
+};
 use reqwest::header::{HeaderMap, HeaderValue};
 pub type HttpClient = reqwest::blocking::Client;
 
@@ -63,9 +64,7 @@ fn prepare_vap_request(
 
     let vap_context_extra_str = match vap_context_extra {
         None => "".to_string(),
-        Some(extra_context) => {
-            format!("{_vap_context_extra_}", _vap_context_extra_ = extra_context)
-        }
+        Some(extra_context) => extra_context.to_string(),
     };
 
     // so far we do not support neither vaContext dynamic enhancement nor development identity
@@ -106,7 +105,7 @@ fn call_vap(
     vap_url: &str,
 ) -> Result<String> {
     let mut headers = HeaderMap::new();
-    let bearer_str = format!("{}", bearer);
+    let bearer_str = bearer.to_string();
     headers.insert("Authorization", HeaderValue::from_str(&bearer_str).unwrap());
     headers.insert(
         "Content-Type",
@@ -148,9 +147,11 @@ pub struct VAPTestExecutor {
     vap_channel_id: Option<String>,
     vap_country: Option<String>,
     vap_context_extra: Option<String>,
+    intent_expressions: IntentExpressions,
 }
 
 impl VAPTestExecutor {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         vap_access_token: String,
         vap_url: String,
@@ -162,9 +163,6 @@ impl VAPTestExecutor {
         vap_country: Option<String>,
         vap_context_extra: Option<String>,
     ) -> Result<Self> {
-        let http_client = HttpClient::new();
-        let conv_id = GUID::rand().to_string();
-
         let jwt_token = VAPTestExecutor::get_vap_access_token(
             &vap_svc_account_email,
             &vap_svc_account_password,
@@ -172,7 +170,35 @@ impl VAPTestExecutor {
         )?
         .accessToken;
 
-        Ok(VAPTestExecutor {
+        Ok(VAPTestExecutor::with_jwt_token(
+            vap_access_token,
+            vap_url,
+            jwt_token,
+            test,
+            tx,
+            vap_channel_id,
+            vap_country,
+            vap_context_extra,
+        ))
+    }
+
+    // jwt token obtained once can be shared by executors of all tests of the suite
+    // (including repetitions of the same test), every executor starts new conversation
+    #[allow(clippy::too_many_arguments)]
+    pub fn with_jwt_token(
+        vap_access_token: String,
+        vap_url: String,
+        jwt_token: String,
+        test: Test,
+        tx: mpsc::Sender<Test>,
+        vap_channel_id: Option<String>,
+        vap_country: Option<String>,
+        vap_context_extra: Option<String>,
+    ) -> Self {
+        let http_client = HttpClient::new();
+        let conv_id = GUID::rand().to_string();
+
+        VAPTestExecutor {
             vap_access_token,
             vap_url,
             test,
@@ -184,10 +210,11 @@ impl VAPTestExecutor {
             vap_channel_id,
             vap_country,
             vap_context_extra,
-        })
+            intent_expressions: IntentExpressions::new("dfResponse.queryResult"),
+        }
     }
 
-    fn get_vap_access_token(
+    pub fn get_vap_access_token(
         svc_account_email: &str,
         svc_account_password: &str,
         vap_url: &str,
@@ -243,7 +270,7 @@ impl VAPTestExecutor {
 
 impl TestExecutor for VAPTestExecutor {
     fn move_to_next_assertion(&mut self) {
-        self.next_assertion = self.next_assertion + 1;
+        self.next_assertion += 1;
     }
 
     fn move_behind_last_assertion(&mut self) {
@@ -263,6 +290,21 @@ impl TestExecutor for VAPTestExecutor {
         self.test.assertions[idx].test_assertion_result = Some(test_assertion_result);
     }
 
+    fn set_test_assertion_warnings(&mut self, warnings: Vec<TestAssertionWarning>) {
+        let idx = self.get_next_assertion_no();
+        self.test.assertions[idx].warnings = warnings;
+    }
+
+    fn set_test_assertion_check_groups(&mut self, check_groups: Vec<CheckGroupResult>) {
+        let idx = self.get_next_assertion_no();
+        self.test.assertions[idx].check_groups = check_groups;
+    }
+
+    fn set_test_assertion_confidence(&mut self, detected_confidence: Option<f64>) {
+        let idx = self.get_next_assertion_no();
+        self.test.assertions[idx].detected_confidence = detected_confidence;
+    }
+
     fn get_next_assertion_no(&self) -> usize {
         self.next_assertion
     }
@@ -272,7 +314,7 @@ impl TestExecutor for VAPTestExecutor {
         Ok(())
     }
 
-    fn invoke_nlp(&self, assertion: &TestAssertion) -> Result<String> {
+    fn call_nlp(&self, assertion: &TestAssertion) -> Result<String> {
         let payload = prepare_vap_request(
             &self.vap_access_token,
             &assertion.user_says,
@@ -284,41 +326,13 @@ impl TestExecutor for VAPTestExecutor {
         );
         let resp = call_vap(payload, &self.http_client, &self.jwt_token, &self.vap_url)?;
         let resp = remove_va_context_config(resp)?; // remove vaContext.config since it contains sensitive data
-        let parser = JsonParser::new(&resp);
-        let real_intent_name = parser.search("dfResponse.queryResult.intent.displayName")?;
-        let real_intent_name = JsonParser::extract_as_string(&real_intent_name);
-
-        if let Some(intent_name) = real_intent_name {
-            if !assertion
-                .bot_responds_with
-                .contains(&intent_name.to_string())
-            {
-                let error_message = format!(
-                    "Wrong intent name received. Expected one of: '{}', got: '{}'",
-                    assertion.bot_responds_with.join(","),
-                    intent_name
-                );
-                return Err(new_service_call_error(
-                    ErrorKind::InvalidTestAssertionEvaluation,
-                    error_message,
-                    None,
-                    Some(resp.to_owned()),
-                ));
-            }
-        } else {
-            let error_message = format!(
-                "No intent name received. Expected: '{}'",
-                assertion.bot_responds_with.join(",")
-            );
-            return Err(new_service_call_error(
-                ErrorKind::InvalidTestAssertionEvaluation,
-                error_message,
-                None,
-                Some(resp.to_owned()),
-            ));
-        }
         Ok(resp)
     }
+
+    fn invoke_nlp(&self, assertion: &TestAssertion) -> Result<NlpResponse> {
+        let resp = self.call_nlp(assertion)?;
+        Ok(NlpResponse::new(assertion, resp, &self.intent_expressions))
+    }
 }
 
 #[cfg(test)]
@@ -428,7 +442,7 @@ mod tests {
         )
         .unwrap();
 
-        assert_eq!(executor.jwt_token.trim().len() > 0, true);
+        assert!(!executor.jwt_token.trim().is_empty());
 
         let vap_access_token = VAPTestExecutor::get_vap_access_token(
             suite
@@ -476,7 +490,7 @@ mod tests {
             println!();
             let details_result = test1_executor.next_assertion_details();
 
-            if let None = details_result {
+            if details_result.is_none() {
                 println!("all assertions processed!");
                 test1_executor.set_test_result(TestResult::Ok);
                 break; // all asertions were processed -> break
@@ -487,7 +501,7 @@ mod tests {
             print!("Saying {}", user_says);
             let assertion_exec_result = test1_executor.execute_next_assertion();
 
-            if let Some(_) = assertion_exec_result {
+            if assertion_exec_result.is_some() {
                 print!(" - ok!");
             } else {
                 print!(" - ko!");
@@ -517,7 +531,7 @@ mod tests {
             pool.execute(move || {
                 loop {
                     let assertion_exec_result = test_executor.execute_next_assertion();
-                    if let None = assertion_exec_result {
+                    if assertion_exec_result.is_none() {
                         break;
                     }
                 }
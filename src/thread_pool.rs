@@ -59,9 +59,9 @@ impl Drop for ThreadPool {
     fn drop(&mut self) {
         debug!("Sending terminate message to all workers.");
 
-        // ask to workers to terminate ...
+        // ask to workers to terminate (workers stopped by running flag do not receive anymore) ...
         for _ in &mut self.workers {
-            self.sender.send(Message::Terminate).unwrap();
+            let _ = self.sender.send(Message::Terminate);
         }
 
         debug!("Shutting down all workers.");
@@ -100,7 +100,7 @@ impl Worker {
                 //
                 let recv_res = receiver.lock().unwrap().recv();
 
-                if let Err(_) = recv_res {
+                if recv_res.is_err() {
                     debug!(
                         "Sender for worker {} got disconnected, worker will terminate.",
                         id
@@ -110,7 +110,7 @@ impl Worker {
 
                 let message = recv_res.unwrap();
 
-                if running.load(Ordering::SeqCst) == false {
+                if !running.load(Ordering::SeqCst) {
                     debug!("Worker {} was told to terminate (ctrl+c pressed).", id);
                     break; // break the worker loop once asked to do so
                 }
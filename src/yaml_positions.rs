@@ -0,0 +1,196 @@
+use std::collections::HashMap;
+use std::fmt;
+use yaml_rust::parser::{Event, MarkedEventReceiver, Parser};
+use yaml_rust::scanner::{Marker, ScanError};
+
+// position within yaml file, both line and column are 1-based
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Position {
+    pub line: usize,
+    pub col: usize,
+}
+
+impl fmt::Display for Position {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}:{}", self.line, self.col)
+    }
+}
+
+impl From<&Marker> for Position {
+    fn from(marker: &Marker) -> Self {
+        Position {
+            line: marker.line(),
+            col: marker.col() + 1,
+        }
+    }
+}
+
+// positions of yaml nodes (first document only) addressed by paths like
+// tests[0].assertions[1].responseChecks[0].operator. Map keys are positioned
+// at the key itself, sequence items at their first token.
+#[derive(Debug, Default)]
+pub struct YamlPositions {
+    positions: HashMap<String, Position>,
+}
+
+impl YamlPositions {
+    pub fn from_yaml_str(yaml_str: &str) -> Result<YamlPositions, ScanError> {
+        let mut builder = PositionsBuilder::default();
+        let mut parser = Parser::new(yaml_str.chars());
+        parser.load(&mut builder, false)?;
+        Ok(YamlPositions {
+            positions: builder.positions,
+        })
+    }
+
+    // position of given path or of its closest ancestor
+    pub fn get(&self, path: &str) -> Option<Position> {
+        let mut path = path;
+        loop {
+            if let Some(position) = self.positions.get(path) {
+                return Some(*position);
+            }
+            match path.rfind(['.', '[']) {
+                Some(idx) => path = &path[..idx],
+                None => return None,
+            }
+        }
+    }
+}
+
+pub fn child_path(path: &str, key: &str) -> String {
+    if path.is_empty() {
+        key.to_owned()
+    } else {
+        format!("{}.{}", path, key)
+    }
+}
+
+pub fn item_path(path: &str, idx: usize) -> String {
+    format!("{}[{}]", path, idx)
+}
+
+enum Frame {
+    Map { key: Option<String> },
+    Seq { idx: usize },
+}
+
+#[derive(Default)]
+struct PositionsBuilder {
+    frames: Vec<Frame>,
+    positions: HashMap<String, Position>,
+    done: bool, // only first document is indexed
+}
+
+impl PositionsBuilder {
+    fn current_path(&self) -> String {
+        let mut path = String::new();
+        for frame in &self.frames {
+            match frame {
+                Frame::Map { key: Some(key) } => path = child_path(&path, key),
+                Frame::Map { key: None } => {}
+                Frame::Seq { idx } => path = item_path(&path, *idx),
+            }
+        }
+        path
+    }
+
+    // called once node (scalar or whole collection) was processed
+    fn node_done(&mut self) {
+        match self.frames.last_mut() {
+            Some(Frame::Map { key }) => *key = None,
+            Some(Frame::Seq { idx }) => *idx += 1,
+            None => {}
+        }
+    }
+
+    fn expecting_key(&self) -> bool {
+        matches!(self.frames.last(), Some(Frame::Map { key: None }))
+    }
+
+    fn record(&mut self, mark: &Marker) {
+        let path = self.current_path();
+        if !path.is_empty() {
+            self.positions.entry(path).or_insert(Position::from(mark));
+        }
+    }
+}
+
+impl MarkedEventReceiver for PositionsBuilder {
+    fn on_event(&mut self, event: Event, mark: Marker) {
+        if self.done {
+            return;
+        }
+
+        match event {
+            Event::DocumentEnd => self.done = true,
+            Event::Scalar(value, ..) => {
+                if self.expecting_key() {
+                    // mapping itself is positioned at its first key
+                    self.record(&mark);
+                    if let Some(Frame::Map { key }) = self.frames.last_mut() {
+                        *key = Some(value);
+                    }
+                    self.record(&mark);
+                } else {
+                    self.record(&mark);
+                    self.node_done();
+                }
+            }
+            Event::Alias(_) => {
+                self.record(&mark);
+                self.node_done();
+            }
+            Event::MappingStart(_) => {
+                // block mapping start is marked at first ':', position is recorded with first key
+                self.frames.push(Frame::Map { key: None });
+            }
+            Event::SequenceStart(_) => {
+                self.record(&mark);
+                self.frames.push(Frame::Seq { idx: 0 });
+            }
+            Event::MappingEnd | Event::SequenceEnd => {
+                self.frames.pop();
+                self.node_done();
+            }
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_yaml_positions() {
+        const YAML: &str = "suite-spec:
+  name: 'Express Tracking'
+tests:
+  - name: 'Tracking'
+    assertions:
+      - userSays: 'hello'
+        responseChecks:
+          - expression: 'queryResult.action'
+            operator: 'equals'
+";
+        let positions = YamlPositions::from_yaml_str(YAML).unwrap();
+        assert_eq!(
+            positions.get("suite-spec.name"),
+            Some(Position { line: 2, col: 3 })
+        );
+        assert_eq!(
+            positions.get("tests[0].name"),
+            Some(Position { line: 4, col: 5 })
+        );
+        assert_eq!(
+            positions.get("tests[0].assertions[0].responseChecks[0].operator"),
+            Some(Position { line: 9, col: 13 })
+        );
+        // unknown path falls back to closest ancestor
+        assert_eq!(
+            positions.get("tests[0].assertions[0].botRespondsWith"),
+            Some(Position { line: 6, col: 9 })
+        );
+    }
+}